@@ -0,0 +1,70 @@
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode, Throughput,
+};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+use tokio_uring::fs::File;
+
+const ITERATIONS: usize = 100000;
+const CONCURRENCY: usize = 32;
+const BLOCK_SIZE: usize = 4096;
+const BLOCKS: u64 = 256;
+
+fn run_reads(path: &Path, registered: bool, count: u64) -> Duration {
+    let mut m = Duration::ZERO;
+
+    for _ in 0..count {
+        m += tokio_uring::start(async move {
+            let file = File::open(path).await.unwrap();
+            let registered = registered.then(|| file.register().unwrap());
+
+            let start = Instant::now();
+            stream::iter(0..ITERATIONS)
+                .for_each_concurrent(Some(CONCURRENCY), |i| {
+                    let file = &file;
+                    let registered = &registered;
+                    async move {
+                        let buf = Vec::with_capacity(BLOCK_SIZE);
+                        let pos = (i as u64 % BLOCKS) * BLOCK_SIZE as u64;
+                        let (res, _) = match registered {
+                            Some(registered) => registered.read_at(buf, pos).await,
+                            None => file.read_at(buf, pos).await,
+                        };
+                        res.unwrap();
+                    }
+                })
+                .await;
+            start.elapsed()
+        })
+    }
+    m
+}
+
+fn bench(c: &mut Criterion) {
+    // Reads are served from the page cache, so the per-operation overhead
+    // dominates.
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile
+        .write_all(&vec![0; BLOCK_SIZE * BLOCKS as usize])
+        .unwrap();
+
+    let mut group = c.benchmark_group("read_at");
+    group.sampling_mode(SamplingMode::Flat);
+    group.throughput(Throughput::Elements(ITERATIONS as u64));
+
+    for registered in [false, true] {
+        let name = if registered { "registered" } else { "file" };
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &registered,
+            |b, &registered| b.iter_custom(|iter| run_reads(tempfile.path(), registered, iter)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
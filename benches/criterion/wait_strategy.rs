@@ -0,0 +1,81 @@
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode, Throughput,
+};
+use std::net::UdpSocket as StdUdpSocket;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio_uring::net::UdpSocket;
+use tokio_uring::WaitStrategy;
+
+const ROUND_TRIPS: usize = 1000;
+
+/// Echoes datagrams back from another thread.
+fn spawn_echo() -> (std::net::SocketAddr, thread::JoinHandle<()>) {
+    let socket = StdUdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    let thread = thread::spawn(move || {
+        let mut buf = [0; 64];
+        loop {
+            let (n, peer) = socket.recv_from(&mut buf).unwrap();
+            if n == 0 {
+                return;
+            }
+            socket.send_to(&buf[..n], peer).unwrap();
+        }
+    });
+    (addr, thread)
+}
+
+fn run_round_trips(strategy: WaitStrategy, count: u64) -> Duration {
+    let (echo, thread) = spawn_echo();
+
+    let elapsed = tokio_uring::builder()
+        .wait_strategy(strategy)
+        .start(async move {
+            let socket = UdpSocket::bind("127.0.0.1:0".parse().unwrap())
+                .await
+                .unwrap();
+            socket.connect(echo).await.unwrap();
+
+            let mut buf = vec![0; 64];
+            let start = Instant::now();
+            for _ in 0..count as usize * ROUND_TRIPS {
+                socket.send(b"ping".to_vec()).await.0.unwrap();
+                let (res, b) = socket.recv(buf).await;
+                res.unwrap();
+                buf = b;
+            }
+            let elapsed = start.elapsed();
+
+            // An empty datagram stops the echo thread.
+            socket.send(vec![]).await.0.unwrap();
+            elapsed
+        });
+
+    thread.join().unwrap();
+    elapsed
+}
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("udp_round_trip");
+    group.sampling_mode(SamplingMode::Flat);
+    group.throughput(Throughput::Elements(ROUND_TRIPS as u64));
+
+    for (name, strategy) in [
+        ("block", WaitStrategy::Block),
+        ("spin_1000", WaitStrategy::Spin { max_spins: 1000 }),
+        ("spin_100000", WaitStrategy::Spin { max_spins: 100_000 }),
+    ] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &strategy,
+            |b, &strategy| b.iter_custom(|iter| run_round_trips(strategy, iter)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
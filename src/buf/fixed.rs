@@ -0,0 +1,228 @@
+use crate::buf::{IoBuf, IoBufMut};
+use crate::driver::Releaser;
+use crate::runtime::CONTEXT;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::ops;
+use std::rc::Rc;
+
+/// A set of buffers registered with the kernel.
+///
+/// Registering buffers lets the kernel map their memory once, instead of for
+/// every operation using them. The registered buffers are handed out as
+/// [`FixedBuf`]s, which return to the registry when dropped.
+///
+/// A runtime holds at most one registry at a time: registering a second one
+/// fails with `EBUSY` until the first is dropped. The buffers count against
+/// `RLIMIT_MEMLOCK` on kernels older than 5.12.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::buf::FixedBufRegistry;
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let registry = FixedBufRegistry::register((0..4).map(|_| Vec::with_capacity(4096)))?;
+///         let file = File::open("hello.txt").await?;
+///
+///         let bufs = (0..4).map(|i| registry.check_out(i).unwrap()).collect();
+///         let (res, bufs) = file.readv_fixed_at(bufs, 0).await;
+///         println!("read {} bytes into {} buffers", res?, bufs.len());
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Clone)]
+pub struct FixedBufRegistry {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    /// Registered buffers by index, `None` while checked out
+    bufs: RefCell<Vec<Option<Vec<u8>>>>,
+
+    /// Unregisters the buffers from the driver once dropped
+    releaser: Releaser,
+}
+
+impl FixedBufRegistry {
+    /// Registers `bufs` with the kernel, each buffer being registered with its
+    /// full capacity.
+    ///
+    /// The buffers keep their initialized contents. This must be called from
+    /// within a runtime, to which the registry is then bound.
+    pub fn register(bufs: impl IntoIterator<Item = Vec<u8>>) -> io::Result<FixedBufRegistry> {
+        let mut bufs: Vec<_> = bufs.into_iter().collect();
+
+        if bufs.iter().any(|buf| buf.capacity() == 0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot register a buffer without capacity",
+            ));
+        }
+
+        let iovecs: Vec<_> = bufs
+            .iter_mut()
+            .map(|buf| libc::iovec {
+                iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+                iov_len: buf.capacity(),
+            })
+            .collect();
+
+        let releaser = CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| {
+                // The buffers are owned by the registry, which unregisters
+                // them before they are freed.
+                driver.uring()?.submitter().register_buffers(&iovecs)?;
+                driver.buffers_registered = true;
+                Ok::<_, io::Error>(driver.releaser())
+            })
+        })?;
+
+        Ok(FixedBufRegistry {
+            inner: Rc::new(Inner {
+                bufs: RefCell::new(bufs.into_iter().map(Some).collect()),
+                releaser,
+            }),
+        })
+    }
+
+    /// Takes the buffer registered at `index`, or returns `None` if it is
+    /// already checked out or `index` is out of bounds.
+    pub fn check_out(&self, index: usize) -> Option<FixedBuf> {
+        let buf = self.inner.bufs.borrow_mut().get_mut(index)?.take()?;
+
+        Some(FixedBuf {
+            buf,
+            index: index as u16,
+            registry: self.inner.clone(),
+        })
+    }
+
+    /// Returns the number of registered buffers.
+    pub fn len(&self) -> usize {
+        self.inner.bufs.borrow().len()
+    }
+
+    /// Returns `true` if no buffers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for FixedBufRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBufRegistry")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // The buffers are freed once unregistered.
+        let bufs = self.bufs.take();
+        self.releaser.release(move |driver| {
+            let _ = driver.live_uring().submitter().unregister_buffers();
+            driver.buffers_registered = false;
+            drop(bufs);
+        });
+    }
+}
+
+/// A buffer checked out of a [`FixedBufRegistry`].
+///
+/// The buffer returns to its registry when dropped, keeping its contents. Its
+/// capacity is fixed to the registered one.
+pub struct FixedBuf {
+    buf: Vec<u8>,
+    index: u16,
+    registry: Rc<Inner>,
+}
+
+impl FixedBuf {
+    /// Returns the index of the buffer in its registry, as used by the kernel
+    /// to refer to it.
+    pub fn buf_index(&self) -> u16 {
+        self.index
+    }
+
+    /// Returns the number of initialized bytes.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if the buffer holds no initialized bytes.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Discards the contents of the buffer, keeping its capacity.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+unsafe impl IoBuf for FixedBuf {
+    fn stable_ptr(&self) -> *const u8 {
+        self.buf.stable_ptr()
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.buf.bytes_init()
+    }
+
+    fn bytes_total(&self) -> usize {
+        self.buf.bytes_total()
+    }
+}
+
+unsafe impl IoBufMut for FixedBuf {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.buf.stable_mut_ptr()
+    }
+
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.buf.set_init(pos);
+    }
+}
+
+impl ops::Deref for FixedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl ops::DerefMut for FixedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+impl fmt::Debug for FixedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBuf")
+            .field("buf_index", &self.index)
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl Drop for FixedBuf {
+    fn drop(&mut self) {
+        let buf = std::mem::take(&mut self.buf);
+        self.registry.bufs.borrow_mut()[self.index as usize] = Some(buf);
+    }
+}
@@ -21,6 +21,12 @@ use std::ops;
 /// region. While the runtime holds ownership to a buffer, the pointer returned
 /// by `stable_ptr` must remain valid even if the `IoBuf` value is moved.
 ///
+/// This is why `IoBuf` is implemented for `Box<[u8; N]>`, but not for arrays
+/// themselves: an operation is submitted before its future is first polled,
+/// the future may be moved afterwards, and a future dropped before completion
+/// hands its buffer over to the runtime, moving it again while the kernel may
+/// still access it. An array would move along with its future.
+///
 /// [`slice()`]: IoBuf::slice
 pub unsafe trait IoBuf: Unpin + 'static {
     /// Returns a raw pointer to the vector’s buffer.
@@ -103,6 +109,20 @@ unsafe impl IoBuf for Vec<u8> {
     }
 }
 
+unsafe impl<const N: usize> IoBuf for Box<[u8; N]> {
+    fn stable_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    fn bytes_init(&self) -> usize {
+        N
+    }
+
+    fn bytes_total(&self) -> usize {
+        N
+    }
+}
+
 unsafe impl IoBuf for &'static [u8] {
     fn stable_ptr(&self) -> *const u8 {
         self.as_ptr()
@@ -1,9 +1,16 @@
-use crate::buf::IoBuf;
+use crate::buf::{IoBuf, SplitBuf};
+
+use std::ops;
 
 /// A mutable`io-uring` compatible buffer.
 ///
 /// The `IoBufMut` trait is implemented by buffer types that can be passed to
-/// io-uring operations. Users will not need to use this trait directly.
+/// io-uring operations. Users will not need to use this trait directly, except
+/// to implement their own buffer types, or to report the bytes filled by an
+/// operation submitted with [`submit_raw`] through [`set_init`].
+///
+/// [`submit_raw`]: crate::submit_raw
+/// [`set_init`]: IoBufMut::set_init
 ///
 /// # Safety
 ///
@@ -24,13 +31,65 @@ pub unsafe trait IoBufMut: IoBuf {
     /// Updates the number of initialized bytes.
     ///
     /// The specified `pos` becomes the new value returned by
-    /// `IoBuf::bytes_init`.
+    /// `IoBuf::bytes_init`, unless the buffer already has more initialized
+    /// bytes: the initialized prefix never shrinks, so implementations
+    /// ignore a smaller `pos`.
+    ///
+    /// The runtime calls this after an operation filled the buffer. Code
+    /// filling the buffer by other means, such as an operation submitted with
+    /// [`submit_raw`], must call it to expose the data, which is otherwise
+    /// considered uninitialized.
     ///
     /// # Safety
     ///
-    /// The caller must ensure that all bytes starting at `stable_mut_ptr()` up
-    /// to `pos` are initialized and owned by the buffer.
+    /// The caller must ensure that `pos` is at most `bytes_total()`, and that
+    /// all bytes starting at `stable_mut_ptr()` up to `pos` are initialized
+    /// and owned by the buffer.
+    ///
+    /// [`submit_raw`]: crate::submit_raw
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_uring::buf::{IoBuf, IoBufMut};
+    ///
+    /// let mut buf = Vec::with_capacity(16);
+    /// unsafe {
+    ///     buf.stable_mut_ptr().copy_from(b"hello".as_ptr(), 5);
+    ///     buf.set_init(5);
+    /// }
+    /// assert_eq!(buf.bytes_init(), 5);
+    /// assert_eq!(buf, b"hello");
+    /// ```
     unsafe fn set_init(&mut self, pos: usize);
+
+    /// Splits the buffer into owned regions with the specified ranges, which
+    /// can be used by concurrent operations.
+    ///
+    /// The regions are returned in the order of `ranges`, and are reassembled
+    /// with [`SplitBuf::join`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ranges overlap or are out of the bounds of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_uring::buf::{IoBufMut, SplitBuf};
+    ///
+    /// let mut parts = b"hello world".to_vec().split([0..5, 6..11]);
+    /// parts[1].copy_from_slice(b"there");
+    ///
+    /// let buf = SplitBuf::join(parts).unwrap();
+    /// assert_eq!(buf, b"hello there");
+    /// ```
+    fn split(self, ranges: impl IntoIterator<Item = ops::Range<usize>>) -> Vec<SplitBuf<Self>>
+    where
+        Self: Sized,
+    {
+        SplitBuf::split(self, ranges)
+    }
 }
 
 unsafe impl IoBufMut for Vec<u8> {
@@ -45,6 +104,16 @@ unsafe impl IoBufMut for Vec<u8> {
     }
 }
 
+unsafe impl<const N: usize> IoBufMut for Box<[u8; N]> {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.as_mut_ptr()
+    }
+
+    unsafe fn set_init(&mut self, _init_len: usize) {
+        // Arrays are always fully initialized.
+    }
+}
+
 #[cfg(feature = "bytes")]
 unsafe impl IoBufMut for bytes::BytesMut {
     fn stable_mut_ptr(&mut self) -> *mut u8 {
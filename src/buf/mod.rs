@@ -4,15 +4,30 @@
 //! crate defines [`IoBuf`] and [`IoBufMut`] traits which are implemented by buffer
 //! types that respect the `io-uring` contract.
 
+mod fixed;
+pub use fixed::{FixedBuf, FixedBufRegistry};
+
 mod io_buf;
 pub use io_buf::IoBuf;
 
 mod io_buf_mut;
 pub use io_buf_mut::IoBufMut;
 
+mod pool;
+pub use pool::{BufPool, PooledBuf};
+
+mod provided;
+pub use provided::{ProvidedBuf, ProvidedBufPool};
+
+mod ring;
+pub use ring::{BufRing, RingBuf};
+
 mod slice;
 pub use slice::Slice;
 
+mod split;
+pub use split::SplitBuf;
+
 pub(crate) fn deref(buf: &impl IoBuf) -> &[u8] {
     // Safety: the `IoBuf` trait is marked as unsafe and is expected to be
     // implemented correctly.
@@ -0,0 +1,200 @@
+use crate::buf::{IoBuf, IoBufMut};
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops;
+use std::rc::Rc;
+
+/// A pool of reusable buffers of a fixed size.
+///
+/// Allocating a fresh buffer for every read churns the allocator, e.g. when
+/// reading from many connections. A `BufPool` hands out [`PooledBuf`]s
+/// instead, which return to the pool when dropped, to be handed out again.
+///
+/// The pool starts with a number of pre-allocated buffers, and allocates more
+/// whenever all buffers are in use. Buffers returned to the pool are kept
+/// until the pool and all of its buffers are dropped.
+///
+/// This is unrelated to the buffers registered with the kernel, see
+/// [`FixedBufRegistry`]: pooled buffers are passed to operations like any
+/// other buffer.
+///
+/// [`FixedBufRegistry`]: crate::buf::FixedBufRegistry
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::buf::BufPool;
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let pool = BufPool::new(16, 4096);
+///         let file = File::open("hello.txt").await?;
+///
+///         for pos in (0..).step_by(4096).take(100) {
+///             // The buffer returns to the pool at the end of each iteration
+///             let (res, buf) = file.read_at(pool.get(), pos).await;
+///             if res? == 0 {
+///                 break;
+///             }
+///             println!("{:?}", &buf[..]);
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Clone)]
+pub struct BufPool {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    /// Capacity of each buffer
+    buf_size: usize,
+
+    /// Buffers not in use, all empty
+    free: RefCell<Vec<Vec<u8>>>,
+}
+
+impl BufPool {
+    /// Creates a pool of buffers of `buf_size` bytes, with `count` buffers
+    /// allocated up front.
+    pub fn new(count: usize, buf_size: usize) -> BufPool {
+        let free = (0..count).map(|_| Vec::with_capacity(buf_size)).collect();
+        BufPool {
+            inner: Rc::new(Inner {
+                buf_size,
+                free: RefCell::new(free),
+            }),
+        }
+    }
+
+    /// Takes a buffer from the pool, allocating a new one if none is
+    /// available.
+    ///
+    /// The buffer is empty, with a capacity of [`buf_size`] bytes.
+    ///
+    /// [`buf_size`]: BufPool::buf_size
+    pub fn get(&self) -> PooledBuf {
+        let buf = self
+            .inner
+            .free
+            .borrow_mut()
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(self.inner.buf_size));
+
+        PooledBuf {
+            buf,
+            pool: self.inner.clone(),
+        }
+    }
+
+    /// Returns the capacity of the buffers in the pool.
+    pub fn buf_size(&self) -> usize {
+        self.inner.buf_size
+    }
+
+    /// Returns the number of buffers available in the pool, which can be
+    /// taken without allocating.
+    pub fn available(&self) -> usize {
+        self.inner.free.borrow().len()
+    }
+}
+
+impl fmt::Debug for BufPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufPool")
+            .field("buf_size", &self.buf_size())
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+/// A buffer taken from a [`BufPool`].
+///
+/// The buffer returns to its pool when dropped. Its contents are discarded
+/// then, so the next user of the buffer gets an empty one.
+pub struct PooledBuf {
+    buf: Vec<u8>,
+    pool: Rc<Inner>,
+}
+
+impl PooledBuf {
+    /// Returns the number of initialized bytes.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if the buffer holds no initialized bytes.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Discards the contents of the buffer, keeping its capacity.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+unsafe impl IoBuf for PooledBuf {
+    fn stable_ptr(&self) -> *const u8 {
+        self.buf.stable_ptr()
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.buf.bytes_init()
+    }
+
+    fn bytes_total(&self) -> usize {
+        self.buf.bytes_total()
+    }
+}
+
+unsafe impl IoBufMut for PooledBuf {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.buf.stable_mut_ptr()
+    }
+
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.buf.set_init(pos);
+    }
+}
+
+impl ops::Deref for PooledBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl ops::DerefMut for PooledBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+impl fmt::Debug for PooledBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledBuf")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl Drop for PooledBuf {
+    fn drop(&mut self) {
+        let mut buf = std::mem::take(&mut self.buf);
+
+        // Reset the initialized length, so the next read sees an empty buffer.
+        buf.clear();
+        self.pool.free.borrow_mut().push(buf);
+    }
+}
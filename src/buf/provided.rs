@@ -0,0 +1,282 @@
+use crate::buf::IoBuf;
+use crate::driver::{Op, Releaser};
+use crate::runtime::CONTEXT;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::ops;
+use std::rc::Rc;
+use std::task::Waker;
+
+/// A group of buffers provided to the kernel, which picks one for each read
+/// as data arrives.
+///
+/// Operations such as [`File::read_multi`] do not take a buffer: the kernel
+/// selects a free buffer of the pool once data is available, and hands it out
+/// as a [`ProvidedBuf`]. The buffer is provided again to the kernel once
+/// dropped. Memory is thus only tied up by data actually read, rather than by
+/// a buffer per pending read.
+///
+/// The pool is bound to the runtime it was created on. Provided buffers
+/// require Linux 5.7.
+///
+/// [`File::read_multi`]: crate::fs::File::read_multi
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use tokio_uring::buf::ProvidedBufPool;
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let pool = ProvidedBufPool::new(16, 4096).await?;
+///         let fifo = File::open("/tmp/fifo").await?;
+///
+///         let mut reads = Box::pin(fifo.read_multi(&pool));
+///         while let Some(buf) = reads.next().await {
+///             println!("{:?}", &buf?[..]);
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ProvidedBufPool {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    /// Memory of the buffers, laid out back to back
+    _mem: Vec<u8>,
+
+    /// Pointer to the memory, through which the buffers are accessed
+    ptr: *mut u8,
+
+    /// Capacity of each buffer
+    buf_size: usize,
+
+    /// Number of buffers
+    count: u16,
+
+    /// Identifier of the group in the kernel
+    group: u16,
+
+    /// Buffers dropped, to be provided again
+    returned: RefCell<Vec<u16>>,
+
+    /// Task waiting for buffers to be returned
+    waker: RefCell<Option<Waker>>,
+
+    /// Removes the group from the driver once dropped
+    releaser: Releaser,
+}
+
+impl ProvidedBufPool {
+    /// Provides `count` buffers of `buf_size` bytes to the kernel.
+    ///
+    /// This must be called from within a runtime.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind [`InvalidInput`] if `count` or `buf_size`
+    /// is zero, or if `buf_size` does not fit an `i32`.
+    ///
+    /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    pub async fn new(count: u16, buf_size: usize) -> io::Result<ProvidedBufPool> {
+        if count == 0 || buf_size == 0 || buf_size > i32::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid number or size of provided buffers",
+            ));
+        }
+
+        let len = (count as usize).checked_mul(buf_size).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "provided buffers too large")
+        })?;
+        let mut mem = Vec::with_capacity(len);
+
+        let (group, releaser) = CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| {
+                Ok::<_, io::Error>((driver.new_buf_group()?, driver.releaser()))
+            })
+        })?;
+        let op = match Op::provide_buffers(mem.as_mut_ptr(), buf_size, count, group, 0) {
+            Ok(op) => op,
+            Err(e) => {
+                CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.free_buf_group(group)));
+                return Err(e);
+            }
+        };
+
+        // Once the group exists, the pool removes it when dropped.
+        let pool = ProvidedBufPool {
+            inner: Rc::new(Inner {
+                ptr: mem.as_mut_ptr(),
+                _mem: mem,
+                buf_size,
+                count,
+                group,
+                returned: RefCell::new(vec![]),
+                waker: RefCell::new(None),
+                releaser,
+            }),
+        };
+
+        op.await?;
+        Ok(pool)
+    }
+
+    /// Returns the capacity of the buffers in the pool.
+    pub fn buf_size(&self) -> usize {
+        self.inner.buf_size
+    }
+
+    /// Returns the number of buffers in the pool.
+    pub fn count(&self) -> u16 {
+        self.inner.count
+    }
+
+    /// Returns the identifier of the group of buffers in the kernel.
+    pub(crate) fn group(&self) -> u16 {
+        self.inner.group
+    }
+
+    /// Takes the buffer `bid`, selected by the kernel for `len` bytes.
+    pub(crate) fn take(&self, bid: u16, len: usize) -> ProvidedBuf {
+        assert!(bid < self.inner.count && len <= self.inner.buf_size);
+
+        ProvidedBuf {
+            pool: self.inner.clone(),
+            bid,
+            len,
+        }
+    }
+
+    /// Provides the returned buffers to the kernel again, returning whether
+    /// there were any.
+    pub(crate) fn provide_returned(&self) -> io::Result<bool> {
+        let mut returned = self.inner.returned.borrow_mut();
+        if returned.is_empty() {
+            return Ok(false);
+        }
+
+        CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| {
+                while let Some(&bid) = returned.last() {
+                    driver.provide_buffers_untracked(
+                        self.inner.buf_ptr(bid),
+                        self.inner.buf_size,
+                        1,
+                        self.inner.group,
+                        bid,
+                    )?;
+                    returned.pop();
+                }
+                Ok(true)
+            })
+        })
+    }
+
+    /// Wake the current task once a buffer is returned.
+    pub(crate) fn wait_returned(&self, waker: &Waker) {
+        *self.inner.waker.borrow_mut() = Some(waker.clone());
+    }
+}
+
+impl Inner {
+    fn buf_ptr(&self, bid: u16) -> *mut u8 {
+        // Safety: the buffers are within the allocation.
+        unsafe { self.ptr.add(bid as usize * self.buf_size) }
+    }
+}
+
+impl fmt::Debug for ProvidedBufPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvidedBufPool")
+            .field("buf_size", &self.buf_size())
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // Only operations holding the pool can select its buffers, so none
+        // is left, and no other group gets the identifier until the buffers
+        // are removed.
+        let (count, group) = (self.count, self.group);
+        self.releaser.release(move |driver| {
+            let _ = driver.remove_buf_group(count, group);
+        });
+    }
+}
+
+/// A buffer of a [`ProvidedBufPool`], filled by the kernel.
+///
+/// The buffer is provided to the kernel again once dropped, discarding its
+/// contents.
+pub struct ProvidedBuf {
+    pool: Rc<Inner>,
+    bid: u16,
+    len: usize,
+}
+
+impl ProvidedBuf {
+    /// Returns the identifier of the buffer in its pool.
+    pub fn buf_id(&self) -> u16 {
+        self.bid
+    }
+
+    /// Returns the number of bytes read into the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+unsafe impl IoBuf for ProvidedBuf {
+    fn stable_ptr(&self) -> *const u8 {
+        self.pool.buf_ptr(self.bid)
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+
+    fn bytes_total(&self) -> usize {
+        self.pool.buf_size
+    }
+}
+
+impl ops::Deref for ProvidedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        super::deref(self)
+    }
+}
+
+impl fmt::Debug for ProvidedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvidedBuf")
+            .field("buf_id", &self.bid)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Drop for ProvidedBuf {
+    fn drop(&mut self) {
+        self.pool.returned.borrow_mut().push(self.bid);
+        if let Some(waker) = self.pool.waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+}
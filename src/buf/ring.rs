@@ -0,0 +1,415 @@
+use crate::buf::IoBuf;
+use crate::driver::Releaser;
+use crate::runtime::CONTEXT;
+
+use io_uring::types::BufRingEntry;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io;
+use std::ops;
+use std::ptr;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU16, Ordering};
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+
+/// A group of buffers shared with the kernel through a ring, from which it
+/// picks one for each read as data arrives.
+///
+/// This is the successor of [`ProvidedBufPool`]: instead of submitting an
+/// operation to provide each buffer back to the kernel, buffers are returned
+/// by writing them to a ring mapped in memory shared with the kernel, which
+/// costs a couple of stores. Reads such as [`File::read_ring`] do not take a
+/// buffer, the kernel selects a free buffer of the ring once data is
+/// available, and hands it out as a [`RingBuf`]. The buffer is returned to the
+/// ring once dropped.
+///
+/// The ring is bound to the runtime it was created on. Buffer rings require
+/// Linux 5.19.
+///
+/// [`ProvidedBufPool`]: crate::buf::ProvidedBufPool
+/// [`File::read_ring`]: crate::fs::File::read_ring
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::buf::BufRing;
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let ring = BufRing::new(16, 4096)?;
+///         let file = File::open("hello.txt").await?;
+///
+///         let mut pos = 0;
+///         while let Some(buf) = file.read_ring(&ring, pos).await? {
+///             pos += buf.len() as u64;
+///             println!("{:?}", &buf[..]);
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Clone)]
+pub struct BufRing {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    /// Entries of the ring, mapped for the kernel to read
+    entries: Mapping,
+
+    /// Memory of the buffers, laid out back to back
+    _mem: Vec<u8>,
+
+    /// Pointer to the memory, through which the buffers are accessed
+    ptr: *mut u8,
+
+    /// Capacity of each buffer
+    buf_size: usize,
+
+    /// Number of buffers, and of entries of the ring
+    count: u16,
+
+    /// Identifier of the group in the kernel
+    group: u16,
+
+    /// Tail of the ring, where the next returned buffer is written. The
+    /// kernel owns the head, and consumes entries up to the tail.
+    tail: Cell<u16>,
+
+    /// Whether each buffer is handed out as a `RingBuf`
+    held: RefCell<Vec<bool>>,
+
+    /// Number of reads in flight which may select a buffer of the ring
+    reads: Cell<usize>,
+
+    /// Notified when a buffer is returned to the ring
+    returned: Notify,
+
+    /// Unregisters the ring from the driver once dropped
+    releaser: Releaser,
+}
+
+/// Memory mapped for the entries of a ring, unmapped once dropped
+struct Mapping {
+    ptr: *mut BufRingEntry,
+    len: usize,
+}
+
+impl BufRing {
+    /// Registers a ring of `count` buffers of `buf_size` bytes with the
+    /// kernel.
+    ///
+    /// This must be called from within a runtime.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind [`InvalidInput`] if `count` is not a power
+    /// of two up to 32768, if `buf_size` is zero or does not fit an `i32`, or
+    /// if the kernel does not support buffer rings.
+    ///
+    /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    pub fn new(count: u16, buf_size: usize) -> io::Result<BufRing> {
+        if !count.is_power_of_two() || count > 1 << 15 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the number of buffers of a ring must be a power of two up to 32768",
+            ));
+        }
+        if buf_size == 0 || buf_size > i32::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid size of ring buffers",
+            ));
+        }
+
+        let len = (count as usize)
+            .checked_mul(buf_size)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "ring buffers too large"))?;
+        let mut mem = Vec::with_capacity(len);
+
+        // The kernel requires the entries to be page aligned.
+        let entries = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                Inner::entries_len(count),
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if entries == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let entries = Mapping {
+            ptr: entries as *mut BufRingEntry,
+            len: Inner::entries_len(count),
+        };
+
+        let (group, releaser) = CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| {
+                Ok::<_, io::Error>((driver.new_buf_group()?, driver.releaser()))
+            })
+        })?;
+        let inner = Inner {
+            entries,
+            ptr: mem.as_mut_ptr(),
+            _mem: mem,
+            buf_size,
+            count,
+            group,
+            tail: Cell::new(0),
+            held: RefCell::new(vec![false; count as usize]),
+            reads: Cell::new(0),
+            returned: Notify::new(),
+            releaser,
+        };
+
+        // From now on, the ring is unregistered and unmapped when dropped.
+        inner.register()?;
+        Ok(BufRing {
+            inner: Rc::new(inner),
+        })
+    }
+
+    /// Returns the capacity of the buffers in the ring.
+    pub fn buf_size(&self) -> usize {
+        self.inner.buf_size
+    }
+
+    /// Returns the number of buffers in the ring.
+    pub fn count(&self) -> u16 {
+        self.inner.count
+    }
+
+    /// Returns the identifier of the group of buffers in the kernel.
+    pub(crate) fn group(&self) -> u16 {
+        self.inner.group
+    }
+
+    /// Takes the buffer `bid`, selected by the kernel for `len` bytes.
+    pub(crate) fn take(&self, bid: u16, len: usize) -> RingBuf {
+        assert!(bid < self.inner.count && len <= self.inner.buf_size);
+
+        let mut held = self.inner.held.borrow_mut();
+        assert!(!held[bid as usize], "buffer selected twice");
+        held[bid as usize] = true;
+
+        RingBuf {
+            ring: self.inner.clone(),
+            bid,
+            len,
+        }
+    }
+
+    /// Records the start of a read selecting a buffer of the ring, until
+    /// `end_read` is called.
+    pub(crate) fn start_read(&self) {
+        self.inner.reads.set(self.inner.reads.get() + 1);
+    }
+
+    /// Records the end of a read started with `start_read`.
+    pub(crate) fn end_read(&self) {
+        self.inner.reads.set(self.inner.reads.get() - 1);
+    }
+
+    /// Returns a future completing once a buffer is returned to the ring
+    /// after this call.
+    pub(crate) fn returned(&self) -> Notified<'_> {
+        self.inner.returned.notified()
+    }
+
+    /// Recovers the buffers the kernel selected for reads whose result was
+    /// discarded, returning whether there were any.
+    ///
+    /// The kernel does not share the head of the ring, so buffers it selected
+    /// for reads dropped before completing can't be told apart from buffers
+    /// still in the ring. Once no read is in flight, the ring is registered
+    /// again from scratch with the buffers not handed out.
+    pub(crate) fn reclaim(&self) -> io::Result<bool> {
+        let held = self
+            .inner
+            .held
+            .borrow()
+            .iter()
+            .filter(|&&held| held)
+            .count();
+        if self.inner.reads.get() > 0 || held == self.inner.count as usize {
+            return Ok(false);
+        }
+
+        CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| {
+                driver
+                    .uring()?
+                    .submitter()
+                    .unregister_buf_ring(self.inner.group)
+            })
+        })?;
+        self.inner.tail.set(0);
+        self.inner.register()?;
+        self.inner.returned.notify_waiters();
+        Ok(true)
+    }
+}
+
+impl Inner {
+    fn entries_len(count: u16) -> usize {
+        count as usize * std::mem::size_of::<BufRingEntry>()
+    }
+
+    fn buf_ptr(&self, bid: u16) -> *mut u8 {
+        // Safety: the buffers are within the allocation.
+        unsafe { self.ptr.add(bid as usize * self.buf_size) }
+    }
+
+    /// Registers the ring with the kernel, filled with the buffers not handed
+    /// out.
+    fn register(&self) -> io::Result<()> {
+        let held = self.held.borrow();
+        for bid in (0..self.count).filter(|&bid| !held[bid as usize]) {
+            self.push(bid);
+        }
+
+        CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| {
+                driver.uring()?.submitter().register_buf_ring(
+                    self.entries.ptr as u64,
+                    self.count,
+                    self.group,
+                )
+            })
+        })
+    }
+
+    /// Returns the buffer `bid` to the ring.
+    ///
+    /// Every buffer is either in the ring or held, so the tail never laps the
+    /// head of the kernel.
+    fn push(&self, bid: u16) {
+        let tail = self.tail.get();
+        let index = tail & (self.count - 1);
+
+        // Safety: the index is within the ring, and the kernel only reads
+        // entries up to the tail, which is published below.
+        unsafe {
+            let entry = &mut *self.entries.ptr.add(index as usize);
+            entry.set_addr(self.buf_ptr(bid) as u64);
+            entry.set_len(self.buf_size as u32);
+            entry.set_bid(bid);
+        }
+
+        self.tail.set(tail.wrapping_add(1));
+        // Safety: the tail overlays the reserved field of the first entry,
+        // which is left alone when writing entries.
+        let shared = unsafe { &*(BufRingEntry::tail(self.entries.ptr) as *const AtomicU16) };
+        shared.store(tail.wrapping_add(1), Ordering::Release);
+    }
+}
+
+impl fmt::Debug for BufRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufRing")
+            .field("buf_size", &self.buf_size())
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // Only reads holding the ring can select its buffers, so none is
+        // left. The entries stay mapped, and the group identifier taken,
+        // until the ring is unregistered.
+        let group = self.group;
+        let entries = std::mem::replace(
+            &mut self.entries,
+            Mapping {
+                ptr: ptr::null_mut(),
+                len: 0,
+            },
+        );
+        self.releaser.release(move |driver| {
+            let _ = driver.live_uring().submitter().unregister_buf_ring(group);
+            driver.free_buf_group(group);
+            drop(entries);
+        });
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe {
+                libc::munmap(self.ptr as *mut libc::c_void, self.len);
+            }
+        }
+    }
+}
+
+/// A buffer of a [`BufRing`], filled by the kernel.
+///
+/// The buffer is returned to the ring once dropped, discarding its contents.
+pub struct RingBuf {
+    ring: Rc<Inner>,
+    bid: u16,
+    len: usize,
+}
+
+impl RingBuf {
+    /// Returns the identifier of the buffer in its ring.
+    pub fn buf_id(&self) -> u16 {
+        self.bid
+    }
+
+    /// Returns the number of bytes read into the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+unsafe impl IoBuf for RingBuf {
+    fn stable_ptr(&self) -> *const u8 {
+        self.ring.buf_ptr(self.bid)
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+
+    fn bytes_total(&self) -> usize {
+        self.ring.buf_size
+    }
+}
+
+impl ops::Deref for RingBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        super::deref(self)
+    }
+}
+
+impl fmt::Debug for RingBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBuf")
+            .field("buf_id", &self.bid)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Drop for RingBuf {
+    fn drop(&mut self) {
+        self.ring.held.borrow_mut()[self.bid as usize] = false;
+        self.ring.push(self.bid);
+        self.ring.returned.notify_waiters();
+    }
+}
@@ -0,0 +1,198 @@
+use crate::buf::{IoBuf, IoBufMut};
+
+use std::cell::UnsafeCell;
+use std::cmp;
+use std::fmt;
+use std::ops;
+use std::rc::Rc;
+
+/// An owned, disjoint region of a buffer split by [`IoBufMut::split`].
+///
+/// Each region can be passed to its own operation, e.g. to read different
+/// parts of a file concurrently into a single allocation. Regions of a buffer
+/// never overlap, which is checked when splitting. Once the operations
+/// completed, [`join`] reassembles the buffer.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::buf::{IoBufMut, SplitBuf};
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let file = File::open("hello.txt").await?;
+///
+///         let parts = Vec::with_capacity(8192).split([0..4096, 4096..8192]);
+///         let reads = parts.into_iter().map(|part| {
+///             let offset = part.begin() as u64;
+///             file.read_at(part, offset)
+///         });
+///
+///         let mut parts = vec![];
+///         for (res, part) in futures::future::join_all(reads).await {
+///             res?;
+///             parts.push(part);
+///         }
+///
+///         let buf = SplitBuf::join(parts).unwrap();
+///         println!("read {} bytes", buf.len());
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// [`join`]: SplitBuf::join
+pub struct SplitBuf<T> {
+    shared: Rc<Shared<T>>,
+    ptr: *mut u8,
+    begin: usize,
+    end: usize,
+    init: usize,
+}
+
+struct Shared<T> {
+    /// Only accessed through the regions' pointers until joined
+    buf: UnsafeCell<T>,
+}
+
+impl<T: IoBufMut> SplitBuf<T> {
+    pub(crate) fn split(
+        mut buf: T,
+        ranges: impl IntoIterator<Item = ops::Range<usize>>,
+    ) -> Vec<SplitBuf<T>> {
+        let ranges: Vec<_> = ranges.into_iter().collect();
+
+        let mut sorted: Vec<_> = ranges.iter().collect();
+        sorted.sort_by_key(|range| range.start);
+        for range in &sorted {
+            assert!(range.start <= range.end, "range start is after its end");
+            assert!(range.end <= buf.bytes_total(), "range out of bounds");
+        }
+        for pair in sorted.windows(2) {
+            assert!(pair[0].end <= pair[1].start, "ranges overlap");
+        }
+
+        let ptr = buf.stable_mut_ptr();
+        let init = buf.bytes_init();
+        let shared = Rc::new(Shared {
+            buf: UnsafeCell::new(buf),
+        });
+
+        ranges
+            .into_iter()
+            .map(|range| SplitBuf {
+                shared: shared.clone(),
+                ptr,
+                begin: range.start,
+                end: range.end,
+                init: cmp::min(init.saturating_sub(range.start), range.len()),
+            })
+            .collect()
+    }
+
+    /// Reassembles the buffer from its regions.
+    ///
+    /// The initialized part of the buffer grows over every region initialized
+    /// from its beginning, as long as no gap is left. Fails, returning the
+    /// regions, if they belong to different buffers or if other regions of
+    /// the buffer are still alive. Dropped regions do not need to be joined.
+    pub fn join(parts: Vec<SplitBuf<T>>) -> Result<T, Vec<SplitBuf<T>>> {
+        let shared = match parts.first() {
+            Some(part) => part.shared.clone(),
+            None => return Err(parts),
+        };
+
+        // The clone above accounts for one reference.
+        if parts.iter().any(|part| !Rc::ptr_eq(&part.shared, &shared))
+            || Rc::strong_count(&shared) != parts.len() + 1
+        {
+            return Err(parts);
+        }
+
+        let mut regions: Vec<_> = parts
+            .into_iter()
+            .map(|part| (part.begin, part.begin + part.init))
+            .collect();
+        regions.sort_unstable();
+
+        let mut buf = match Rc::try_unwrap(shared) {
+            Ok(shared) => shared.buf.into_inner(),
+            Err(_) => unreachable!("all regions were dropped"),
+        };
+
+        let mut init = buf.bytes_init();
+        for (begin, end) in regions {
+            if begin <= init {
+                init = cmp::max(init, end);
+            }
+        }
+
+        // Safety: every byte up to `init` was either initialized in the
+        // buffer or in one of its regions.
+        unsafe { buf.set_init(init) };
+        Ok(buf)
+    }
+}
+
+impl<T> SplitBuf<T> {
+    /// Offset in the underlying buffer at which this region starts.
+    pub fn begin(&self) -> usize {
+        self.begin
+    }
+
+    /// Offset in the underlying buffer at which this region ends.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+}
+
+impl<T: IoBufMut> ops::Deref for SplitBuf<T> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        super::deref(self)
+    }
+}
+
+impl<T: IoBufMut> ops::DerefMut for SplitBuf<T> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        super::deref_mut(self)
+    }
+}
+
+unsafe impl<T: IoBufMut> IoBuf for SplitBuf<T> {
+    fn stable_ptr(&self) -> *const u8 {
+        // Safety: the region is within the bounds of the buffer.
+        unsafe { self.ptr.add(self.begin) }
+    }
+
+    fn bytes_init(&self) -> usize {
+        self.init
+    }
+
+    fn bytes_total(&self) -> usize {
+        self.end - self.begin
+    }
+}
+
+unsafe impl<T: IoBufMut> IoBufMut for SplitBuf<T> {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        // Safety: the region is within the bounds of the buffer.
+        unsafe { self.ptr.add(self.begin) }
+    }
+
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.init = cmp::max(self.init, pos);
+    }
+}
+
+impl<T> fmt::Debug for SplitBuf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitBuf")
+            .field("begin", &self.begin)
+            .field("end", &self.end)
+            .field("init", &self.init)
+            .finish()
+    }
+}
@@ -0,0 +1,86 @@
+use crate::driver::op::{self, Completable};
+use crate::driver::util::RawSqe;
+use crate::driver::{Op, SharedFd};
+
+use socket2::SockAddr;
+use std::io;
+
+/// `IORING_OP_BIND`, added in Linux 6.11 and not known to the `io-uring`
+/// crate.
+pub(crate) const IORING_OP_BIND: u8 = 56;
+
+/// `IORING_OP_LISTEN`, added in Linux 6.11 and not known to the `io-uring`
+/// crate.
+pub(crate) const IORING_OP_LISTEN: u8 = 57;
+
+pub(crate) struct Bind {
+    /// Holds a strong ref to the FD, preventing the socket from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    /// The address, read by the kernel once the operation is submitted.
+    addr: SockAddr,
+}
+
+impl Op<Bind> {
+    /// Binds `fd` to `addr`.
+    pub(crate) fn bind(fd: &SharedFd, addr: SockAddr) -> io::Result<Op<Bind>> {
+        Op::submit_with(
+            Bind {
+                fd: fd.clone(),
+                addr,
+            },
+            |bind| {
+                RawSqe {
+                    opcode: IORING_OP_BIND,
+                    fd: bind.fd.raw_fd(),
+                    addr: bind.addr.as_ptr() as u64,
+                    // `addr2`, which shares its place with the offset
+                    off: bind.addr.len() as u64,
+                    ..Default::default()
+                }
+                .build()
+            },
+        )
+    }
+}
+
+impl Completable for Bind {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
+
+pub(crate) struct Listen {
+    /// Holds a strong ref to the FD, preventing the socket from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+}
+
+impl Op<Listen> {
+    /// Marks `fd` as accepting connections, with room for `backlog` pending
+    /// connections.
+    pub(crate) fn listen(fd: &SharedFd, backlog: libc::c_int) -> io::Result<Op<Listen>> {
+        Op::submit_with(Listen { fd: fd.clone() }, |listen| {
+            RawSqe {
+                opcode: IORING_OP_LISTEN,
+                fd: listen.fd.raw_fd(),
+                len: backlog as u32,
+                ..Default::default()
+            }
+            .build()
+        })
+    }
+}
+
+impl Completable for Listen {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
@@ -0,0 +1,175 @@
+use crate::driver::op::{self, Completable};
+use crate::driver::util::RawSqe;
+use crate::driver::{Op, SharedFd};
+use crate::runtime::CONTEXT;
+
+use std::cell::Cell;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+
+/// A handle to cancel an in-flight operation.
+///
+/// Unlike dropping the future awaiting an operation, canceling it with its
+/// token leaves the future in place: it still resolves, with the buffer of
+/// the operation, once the kernel is done with it. The token can be cloned and
+/// moved to other tasks of the runtime, e.g. a watchdog.
+///
+/// A canceled operation fails with [`ErrorKind::Interrupted`]. Canceling is
+/// best effort: an operation which completed before the kernel could cancel
+/// it resolves with its result as usual.
+///
+/// [`ErrorKind::Interrupted`]: std::io::ErrorKind::Interrupted
+#[derive(Clone)]
+pub struct CancelToken {
+    state: Rc<State>,
+}
+
+struct State {
+    /// Index of the operation, while it is in flight
+    index: Cell<Option<usize>>,
+
+    /// Whether cancellation was requested
+    canceled: Cell<bool>,
+}
+
+/// Detaches the operation from its token once completed or dropped, as its
+/// index may then be reused by another operation.
+pub(crate) struct Attached(Rc<State>);
+
+impl CancelToken {
+    pub(crate) fn new() -> CancelToken {
+        CancelToken {
+            state: Rc::new(State {
+                index: Cell::new(None),
+                canceled: Cell::new(false),
+            }),
+        }
+    }
+
+    /// Requests the cancellation of the operation.
+    ///
+    /// If the operation was not submitted yet, it is canceled as soon as it
+    /// is. Canceling an operation more than once, or once it completed, has
+    /// no effect.
+    pub fn cancel(&self) {
+        if self.state.canceled.replace(true) {
+            return;
+        }
+
+        if let Some(index) = self.state.index.get() {
+            submit_cancel(index);
+        }
+    }
+
+    /// Returns `true` if [`cancel`] was called.
+    ///
+    /// [`cancel`]: CancelToken::cancel
+    pub fn is_canceled(&self) -> bool {
+        self.state.canceled.get()
+    }
+
+    /// Attach the in-flight `op` to the token, until the returned guard is
+    /// dropped.
+    pub(crate) fn attach<T, CqeType>(&self, op: &Op<T, CqeType>) -> Attached {
+        self.state.index.set(Some(op.index));
+        if self.is_canceled() {
+            submit_cancel(op.index);
+        }
+        Attached(self.state.clone())
+    }
+
+    /// Report a failure of a canceled operation as interrupted.
+    pub(crate) fn map_err(&self, e: io::Error) -> io::Error {
+        match e.raw_os_error() {
+            Some(libc::ECANCELED) | Some(libc::EINTR) if self.is_canceled() => {
+                io::Error::new(io::ErrorKind::Interrupted, "operation canceled")
+            }
+            _ => e,
+        }
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("canceled", &self.is_canceled())
+            .finish()
+    }
+}
+
+impl Drop for Attached {
+    fn drop(&mut self) {
+        self.0.index.set(None);
+    }
+}
+
+fn submit_cancel(index: usize) {
+    CONTEXT.with(|cx| {
+        cx.with_driver_mut(|driver| {
+            // If the cancellation cannot be submitted, the operation simply
+            // runs to completion.
+            let _ = driver.cancel(index);
+        })
+    });
+}
+
+/// Match every operation, rather than only the first one.
+const IORING_ASYNC_CANCEL_ALL: u32 = 1 << 0;
+
+/// Match operations by file descriptor, rather than by user data.
+const IORING_ASYNC_CANCEL_FD: u32 = 1 << 1;
+
+pub(crate) struct CancelFd {
+    /// Holds a strong ref to the FD, so its number is not reused by another
+    /// file while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+}
+
+impl Op<CancelFd> {
+    /// Submit the cancellation of every in-flight operation on `fd`.
+    fn cancel_fd(fd: &SharedFd) -> io::Result<Op<CancelFd>> {
+        Op::submit_with(CancelFd { fd: fd.clone() }, |cancel| {
+            RawSqe {
+                opcode: io_uring::opcode::AsyncCancel::CODE,
+                fd: cancel.fd.raw_fd(),
+                op_flags: IORING_ASYNC_CANCEL_ALL | IORING_ASYNC_CANCEL_FD,
+                ..Default::default()
+            }
+            .build()
+        })
+    }
+}
+
+impl Completable for CancelFd {
+    type Output = io::Result<usize>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|n| n as usize)
+    }
+}
+
+/// Cancel every in-flight operation on `fd`, returning how many were found.
+///
+/// Kernels older than 5.19 reject the flags matching by file descriptor,
+/// which is reported as unsupported.
+pub(crate) async fn cancel_all(fd: &SharedFd) -> io::Result<usize> {
+    let unsupported = || {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "canceling operations by file descriptor is not supported by the kernel",
+        )
+    };
+
+    let native =
+        crate::probe().is_ok_and(|probe| probe.is_supported(io_uring::opcode::AsyncCancel::CODE));
+    if !native {
+        return Err(unsupported());
+    }
+
+    match Op::cancel_fd(fd)?.await {
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => Err(unsupported()),
+        res => res,
+    }
+}
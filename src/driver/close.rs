@@ -1,4 +1,4 @@
-use crate::driver::Op;
+use crate::driver::{FixedSlot, Op};
 
 use crate::driver::op::{self, Completable};
 use std::io;
@@ -27,3 +27,30 @@ impl Completable for Close {
         Ok(())
     }
 }
+
+/// Close a direct descriptor
+pub(crate) struct CloseDirect {
+    /// Returned to the fixed file table once the slot is empty
+    #[allow(dead_code)]
+    slot: FixedSlot,
+}
+
+impl Op<CloseDirect> {
+    pub(crate) fn close_direct(slot: FixedSlot) -> io::Result<Op<CloseDirect>> {
+        use io_uring::{opcode, types};
+
+        Op::submit_with(CloseDirect { slot }, |close| {
+            opcode::Close::new(types::Fixed(close.slot.index())).build()
+        })
+    }
+}
+
+impl Completable for CloseDirect {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        let _ = cqe.result?;
+
+        Ok(())
+    }
+}
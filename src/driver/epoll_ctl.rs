@@ -0,0 +1,41 @@
+use crate::driver::Op;
+
+use crate::driver::op::{self, Completable};
+use std::io;
+use std::os::unix::io::RawFd;
+
+/// Modify an epoll set
+pub(crate) struct EpollCtl {
+    /// The event registered for the FD. The kernel reads it during the
+    /// operation, so it is boxed to keep a stable address.
+    event: Box<libc::epoll_event>,
+}
+
+impl Op<EpollCtl> {
+    pub(crate) fn epoll_ctl(
+        epfd: RawFd,
+        op: libc::c_int,
+        fd: RawFd,
+        event: libc::epoll_event,
+    ) -> io::Result<Op<EpollCtl>> {
+        use io_uring::{opcode, types};
+
+        Op::submit_with(
+            EpollCtl {
+                event: Box::new(event),
+            },
+            |epoll_ctl| {
+                let ev = epoll_ctl.event.as_ref() as *const _ as *const types::epoll_event;
+                opcode::EpollCtl::new(types::Fd(epfd), types::Fd(fd), op, ev).build()
+            },
+        )
+    }
+}
+
+impl Completable for EpollCtl {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(drop)
+    }
+}
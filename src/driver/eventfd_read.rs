@@ -0,0 +1,42 @@
+use crate::driver::Op;
+
+use std::fs::File;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::sync::Arc;
+
+use crate::driver::op::{self, Completable};
+use io_uring::{opcode, types};
+
+pub(crate) struct EventfdRead {
+    /// Holds a strong ref to the eventfd, preventing it from being closed
+    /// while the operation is in-flight.
+    fd: Arc<File>,
+
+    /// Counter read from the eventfd
+    count: Box<u64>,
+}
+
+impl Op<EventfdRead> {
+    /// Submit a read of the counter of the eventfd `fd`, resetting it.
+    pub(crate) fn eventfd_read(fd: &Arc<File>) -> io::Result<Op<EventfdRead>> {
+        Op::submit_with(
+            EventfdRead {
+                fd: fd.clone(),
+                count: Box::new(0),
+            },
+            |read| {
+                let ptr = &mut *read.count as *mut u64 as *mut u8;
+                opcode::Read::new(types::Fd(read.fd.as_raw_fd()), ptr, 8).build()
+            },
+        )
+    }
+}
+
+impl Completable for EventfdRead {
+    type Output = io::Result<u64>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| *self.count)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::driver::{Op, SharedFd};
+
+use std::io;
+
+use crate::driver::op::{self, Completable};
+use io_uring::{opcode, types};
+
+pub(crate) struct Fadvise {
+    fd: SharedFd,
+}
+
+impl Op<Fadvise> {
+    pub(crate) fn fadvise(
+        fd: &SharedFd,
+        offset: u64,
+        len: u64,
+        advice: i32,
+    ) -> io::Result<Op<Fadvise>> {
+        Op::submit_with(Fadvise { fd: fd.clone() }, |fadvise| {
+            opcode::Fadvise::new(types::Fd(fadvise.fd.raw_fd()), len as _, advice)
+                .offset(offset as _)
+                .build()
+        })
+    }
+}
+
+impl Completable for Fadvise {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
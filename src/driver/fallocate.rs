@@ -0,0 +1,38 @@
+use crate::driver::{Op, SharedFd};
+
+use std::io;
+
+use crate::driver::op::{self, Completable};
+use io_uring::{opcode, types};
+
+pub(crate) struct Fallocate {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    fd: SharedFd,
+}
+
+impl Op<Fallocate> {
+    /// Submit a request to manipulate `len` bytes of space at `offset`, as
+    /// described by the `FALLOC_FL_*` flags of `mode`.
+    pub(crate) fn fallocate(
+        fd: &SharedFd,
+        offset: u64,
+        len: u64,
+        mode: i32,
+    ) -> io::Result<Op<Fallocate>> {
+        Op::submit_with(Fallocate { fd: fd.clone() }, |fallocate| {
+            opcode::Fallocate64::new(types::Fd(fallocate.fd.raw_fd()), len as _)
+                .offset64(offset as _)
+                .mode(mode)
+                .build()
+        })
+    }
+}
+
+impl Completable for Fallocate {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
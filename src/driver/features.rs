@@ -0,0 +1,120 @@
+use io_uring::Parameters;
+use std::fmt;
+
+/// The io_uring features supported by the kernel, as reported when the ring
+/// was set up.
+///
+/// Obtained through [`tokio_uring::features`]. See its documentation for more
+/// details. Each method reports whether the corresponding `IORING_FEAT_*`
+/// flag is set.
+///
+/// [`tokio_uring::features`]: crate::features
+#[derive(Clone, Copy, Default)]
+pub struct Features {
+    // One bit per method, in declaration order
+    bits: u32,
+}
+
+macro_rules! features {
+    ($($(#[$doc:meta])* $name:ident => $param:ident,)*) => {
+        impl Features {
+            pub(crate) fn new(params: &Parameters) -> Features {
+                let mut bits = 0;
+                let mut bit = 1;
+                $(
+                    if params.$param() {
+                        bits |= bit;
+                    }
+                    bit <<= 1;
+                )*
+                let _ = bit;
+                Features { bits }
+            }
+
+            features!(@methods 1, $($(#[$doc])* $name,)*);
+        }
+
+        impl fmt::Debug for Features {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut set = f.debug_set();
+                $(
+                    if self.$name() {
+                        set.entry(&stringify!($name));
+                    }
+                )*
+                set.finish()
+            }
+        }
+    };
+
+    (@methods $bit:expr, $(#[$doc:meta])* $name:ident, $($rest:tt)*) => {
+        $(#[$doc])*
+        pub fn $name(&self) -> bool {
+            self.bits & $bit != 0
+        }
+
+        features!(@methods $bit << 1, $($rest)*);
+    };
+
+    (@methods $bit:expr,) => {};
+}
+
+features! {
+    /// `IORING_FEAT_SINGLE_MMAP`: the submission and completion queues share
+    /// a single mapping.
+    single_mmap => is_feature_single_mmap,
+
+    /// `IORING_FEAT_NODROP`: completions are never dropped when the completion
+    /// queue overflows, but kept until there is room for them.
+    nodrop => is_feature_nodrop,
+
+    /// `IORING_FEAT_SUBMIT_STABLE`: the data of an operation, such as its
+    /// `iovec` array, need only remain valid until it is submitted, rather than
+    /// until it completes. `tokio-uring` keeps that data alive until completion
+    /// regardless, so this only matters for the entries passed to
+    /// [`submit_raw`].
+    ///
+    /// [`submit_raw`]: crate::submit_raw
+    submit_stable => is_feature_submit_stable,
+
+    /// `IORING_FEAT_RW_CUR_POS`: reads and writes at offset `-1` use and update
+    /// the current file position.
+    rw_cur_pos => is_feature_rw_cur_pos,
+
+    /// `IORING_FEAT_CUR_PERSONALITY`: operations run with the credentials of
+    /// the task submitting them, rather than of the task which set up the
+    /// ring.
+    cur_personality => is_feature_cur_personality,
+
+    /// `IORING_FEAT_FAST_POLL`: operations on sockets and pipes which are not
+    /// ready are retried through internal polling, rather than a blocking
+    /// worker thread.
+    fast_poll => is_feature_fast_poll,
+
+    /// `IORING_FEAT_POLL_32BITS`: poll operations support the full 32-bit
+    /// event mask.
+    poll_32bits => is_feature_poll_32bits,
+
+    /// `IORING_FEAT_SQPOLL_NONFIXED`: submission queue polling does not require
+    /// registered files.
+    sqpoll_nonfixed => is_feature_sqpoll_nonfixed,
+
+    /// `IORING_FEAT_EXT_ARG`: waiting for completions supports a timeout.
+    ext_arg => is_feature_ext_arg,
+
+    /// `IORING_FEAT_NATIVE_WORKERS`: asynchronous work runs on threads of the
+    /// process which set up the ring.
+    native_workers => is_feature_native_workers,
+
+    /// `IORING_FEAT_RSRC_TAGS`: registered resources can be tagged.
+    resource_tagging => is_feature_resource_tagging,
+
+    /// `IORING_FEAT_CQE_SKIP`: completions of successful operations can be
+    /// skipped.
+    skip_cqe_on_success => is_feature_skip_cqe_on_success,
+
+    /// `IORING_FEAT_LINKED_FILE`: the file of an operation in a chain is only
+    /// resolved once the previous operations completed, so it can be opened
+    /// by one of them.
+    linked_file => is_feature_linked_file,
+}
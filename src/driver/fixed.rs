@@ -0,0 +1,107 @@
+use crate::runtime::CONTEXT;
+
+use io_uring::IoUring;
+use std::cell::RefCell;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+
+/// Number of slots in the fixed file table, registered on first use.
+const FIXED_FILES: u32 = 64;
+
+/// The ring's fixed file table, used to open files as direct descriptors.
+///
+/// Direct descriptors are only known to the ring, which saves looking up the
+/// file on each operation and allows opening a file and using it within a
+/// single linked chain.
+pub(crate) struct FixedFiles {
+    /// Unused slots
+    free: Rc<RefCell<Vec<u32>>>,
+}
+
+impl FixedFiles {
+    /// Register an empty fixed file table with the ring.
+    pub(crate) fn register(uring: &IoUring) -> io::Result<FixedFiles> {
+        uring.submitter().register_files_sparse(FIXED_FILES)?;
+
+        Ok(FixedFiles {
+            free: Rc::new(RefCell::new((0..FIXED_FILES).rev().collect())),
+        })
+    }
+
+    /// Whether no slot is in use.
+    pub(crate) fn is_unused(&self) -> bool {
+        Rc::strong_count(&self.free) == 1
+    }
+
+    /// Take an unused slot, if any.
+    pub(crate) fn alloc(&self) -> Option<FixedSlot> {
+        let index = self.free.borrow_mut().pop()?;
+
+        Some(FixedSlot {
+            index,
+            free: self.free.clone(),
+        })
+    }
+}
+
+/// Exclusive use of a slot of the fixed file table.
+///
+/// The slot is returned to the table when dropped. It must be empty by then,
+/// so the guard is held by the operation closing the direct descriptor.
+pub(crate) struct FixedSlot {
+    index: u32,
+    free: Rc<RefCell<Vec<u32>>>,
+}
+
+impl FixedSlot {
+    pub(crate) fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The slot, as the target of an operation creating a direct descriptor.
+    pub(crate) fn destination(&self) -> io_uring::types::DestinationSlot {
+        io_uring::types::DestinationSlot::try_from_slot_target(self.index)
+            .expect("slot is within the fixed file table")
+    }
+}
+
+impl Drop for FixedSlot {
+    fn drop(&mut self) {
+        self.free.borrow_mut().push(self.index);
+    }
+}
+
+/// Take an unused slot of the current runtime's fixed file table.
+///
+/// Returns `None` if the kernel does not support sparse fixed file tables,
+/// which also implies direct descriptors are not supported, if all slots are
+/// in use, or on a runtime without io_uring.
+pub(crate) fn fixed_slot() -> Option<FixedSlot> {
+    CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return None;
+        }
+        cx.with_driver_mut(|driver| driver.fixed_slot())
+    })
+}
+
+/// Register a copy of `fd` in an unused slot of the current runtime's fixed
+/// file table.
+///
+/// `fd` itself is left open, the slot refers to the same file.
+pub(crate) fn register_fd(fd: RawFd) -> io::Result<FixedSlot> {
+    let slot = fixed_slot()
+        .ok_or_else(|| io::Error::other("no slot of the fixed file table is available"))?;
+
+    CONTEXT.with(|cx| {
+        cx.with_driver_mut(|driver| {
+            driver
+                .uring()?
+                .submitter()
+                .register_files_update(slot.index(), &[fd])
+        })
+    })?;
+
+    Ok(slot)
+}
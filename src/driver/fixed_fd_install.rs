@@ -0,0 +1,44 @@
+use crate::driver::util::RawSqe;
+use crate::driver::{FixedSlot, Op};
+
+use io_uring::squeue;
+use std::io;
+use std::os::unix::io::RawFd;
+
+use crate::driver::op::{self, Completable};
+
+/// `IORING_OP_FIXED_FD_INSTALL`, added in Linux 6.8 and not known to the
+/// `io-uring` crate.
+pub(crate) const IORING_OP_FIXED_FD_INSTALL: u8 = 54;
+
+/// Install a direct descriptor into the process file table
+pub(crate) struct FixedFdInstall;
+
+impl Op<FixedFdInstall> {
+    /// Submit a request to install the direct descriptor in `slot` as a
+    /// regular file descriptor, with `O_CLOEXEC` set.
+    ///
+    /// The slot is not held by the operation, it must be kept in use until
+    /// the installation completes. The kernel must support
+    /// `IORING_OP_FIXED_FD_INSTALL`, as reported by the probe.
+    pub(crate) fn fixed_fd_install(slot: &FixedSlot) -> io::Result<Op<FixedFdInstall>> {
+        Op::submit_with(FixedFdInstall, |_| {
+            RawSqe {
+                opcode: IORING_OP_FIXED_FD_INSTALL,
+                // The kernel only installs direct descriptors.
+                flags: squeue::Flags::FIXED_FILE.bits(),
+                fd: slot.index() as i32,
+                ..Default::default()
+            }
+            .build()
+        })
+    }
+}
+
+impl Completable for FixedFdInstall {
+    type Output = io::Result<RawFd>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|fd| fd as RawFd)
+    }
+}
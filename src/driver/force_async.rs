@@ -0,0 +1,80 @@
+use crate::runtime::CONTEXT;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Extension trait forcing operations onto the kernel's async workers.
+///
+/// Implemented for all futures, and meant for the futures returned by the
+/// operations of this crate, e.g. [`File::read_at`].
+///
+/// [`File::read_at`]: crate::fs::File::read_at
+pub trait ForceAsyncExt: Future + Sized {
+    /// Submits the operations of this future with `IOSQE_ASYNC`.
+    ///
+    /// The kernel first tries to run an operation inline, while submitting
+    /// it, and only punts it to an async worker if it would block. Against a
+    /// slow device, the inline attempt may stall the submission of the other
+    /// operations in the queue. With this flag, the operation goes straight
+    /// to a worker: this avoids the head-of-line blocking, at the cost of the
+    /// added latency of the handoff, and of the worker threads. It is best
+    /// kept for operations known to block.
+    ///
+    /// All the operations submitted by the future are flagged, e.g. every
+    /// write of [`File::write_all_at`]. Without io_uring, this has no effect.
+    ///
+    /// [`File::write_all_at`]: crate::fs::File::write_all_at
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::fs::File;
+    /// use tokio_uring::ForceAsyncExt;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let file = File::open("/mnt/nfs/hello.txt").await?;
+    ///         let (res, _buf) = file.read_at(vec![0; 4096], 0).force_async().await;
+    ///         println!("read {} bytes", res?);
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    fn force_async(self) -> ForceAsync<Self> {
+        ForceAsync { future: self }
+    }
+}
+
+impl<F: Future> ForceAsyncExt for F {}
+
+/// Future returned by [`ForceAsyncExt::force_async`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ForceAsync<F> {
+    future: F,
+}
+
+impl<F: Future> Future for ForceAsync<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // Safety: `future` is never moved out of `self`.
+        let me = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut me.future) };
+
+        if !CONTEXT.with(|cx| cx.is_set()) {
+            return future.poll(cx);
+        }
+
+        // The flag is set while the future is polled, for every operation it
+        // submits.
+        let prev = CONTEXT.with(|cx| {
+            cx.with_driver_mut(|driver| std::mem::replace(&mut driver.force_async, true))
+        });
+        let res = future.poll(cx);
+        CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.force_async = prev));
+
+        res
+    }
+}
@@ -1,4 +1,4 @@
-use crate::driver::{Op, SharedFd};
+use crate::driver::{FixedSlot, Op, SharedFd};
 
 use std::io;
 
@@ -6,20 +6,66 @@ use crate::driver::op::{self, Completable};
 use io_uring::{opcode, types};
 
 pub(crate) struct Fsync {
-    fd: SharedFd,
+    /// Not set when syncing a direct descriptor.
+    #[allow(dead_code)]
+    fd: Option<SharedFd>,
 }
 
 impl Op<Fsync> {
     pub(crate) fn fsync(fd: &SharedFd) -> io::Result<Op<Fsync>> {
-        Op::submit_with(Fsync { fd: fd.clone() }, |fsync| {
-            opcode::Fsync::new(types::Fd(fsync.fd.raw_fd())).build()
-        })
+        Op::submit_with(
+            Fsync {
+                fd: Some(fd.clone()),
+            },
+            |_| opcode::Fsync::new(types::Fd(fd.raw_fd())).build(),
+        )
     }
 
     pub(crate) fn datasync(fd: &SharedFd) -> io::Result<Op<Fsync>> {
-        Op::submit_with(Fsync { fd: fd.clone() }, |fsync| {
-            opcode::Fsync::new(types::Fd(fsync.fd.raw_fd()))
-                .flags(types::FsyncFlags::DATASYNC)
+        Op::submit_with(
+            Fsync {
+                fd: Some(fd.clone()),
+            },
+            |_| {
+                opcode::Fsync::new(types::Fd(fd.raw_fd()))
+                    .flags(types::FsyncFlags::DATASYNC)
+                    .build()
+            },
+        )
+    }
+
+    /// Submit a request to write out the dirty pages of `len` bytes at
+    /// `offset`, with the `SYNC_FILE_RANGE_*` `flags`.
+    pub(crate) fn sync_file_range(
+        fd: &SharedFd,
+        offset: u64,
+        len: u32,
+        flags: u32,
+    ) -> io::Result<Op<Fsync>> {
+        Op::submit_with(
+            Fsync {
+                fd: Some(fd.clone()),
+            },
+            |_| {
+                opcode::SyncFileRange::new(types::Fd(fd.raw_fd()), len)
+                    .offset(offset as _)
+                    .flags(flags)
+                    .build()
+            },
+        )
+    }
+
+    /// Submit a request to sync the direct descriptor in `slot`.
+    ///
+    /// The slot is not held by the operation, it must be kept in use until
+    /// the sync completes.
+    pub(crate) fn fsync_direct(
+        slot: &FixedSlot,
+        flags: types::FsyncFlags,
+    ) -> io::Result<Op<Fsync>> {
+        Op::submit_with(Fsync { fd: None }, |_| {
+            opcode::Fsync::new(types::Fixed(slot.index()))
+                .flags(flags)
                 .build()
         })
     }
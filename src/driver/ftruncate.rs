@@ -0,0 +1,42 @@
+use crate::driver::util::RawSqe;
+use crate::driver::{Op, SharedFd};
+
+use std::io;
+
+use crate::driver::op::{self, Completable};
+
+/// `IORING_OP_FTRUNCATE`, added in Linux 6.9 and not known to the `io-uring`
+/// crate.
+pub(crate) const IORING_OP_FTRUNCATE: u8 = 55;
+
+pub(crate) struct Ftruncate {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+}
+
+impl Op<Ftruncate> {
+    /// Submit a request to truncate or extend the file to `len` bytes.
+    ///
+    /// The kernel must support `IORING_OP_FTRUNCATE`, as reported by the probe.
+    pub(crate) fn ftruncate(fd: &SharedFd, len: u64) -> io::Result<Op<Ftruncate>> {
+        Op::submit_with(Ftruncate { fd: fd.clone() }, |ftruncate| {
+            RawSqe {
+                opcode: IORING_OP_FTRUNCATE,
+                fd: ftruncate.fd.raw_fd(),
+                off: len,
+                ..Default::default()
+            }
+            .build()
+        })
+    }
+}
+
+impl Completable for Ftruncate {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::driver::op::{self, Completable};
+use crate::driver::util::RawSqe;
+use crate::driver::Op;
+
+use std::io;
+
+/// `IORING_OP_FUTEX_WAIT`, added in Linux 6.7 and not known to the `io-uring`
+/// crate.
+pub(crate) const IORING_OP_FUTEX_WAIT: u8 = 51;
+
+/// `IORING_OP_FUTEX_WAKE`, added in Linux 6.7 and not known to the `io-uring`
+/// crate.
+pub(crate) const IORING_OP_FUTEX_WAKE: u8 = 52;
+
+/// The futex is 32 bits wide, the only size the kernel supports.
+const FUTEX2_SIZE_U32: i32 = 0x02;
+
+/// The futex is only shared by the threads of the process.
+const FUTEX2_PRIVATE: i32 = 128;
+
+pub(crate) struct FutexWait;
+
+impl Op<FutexWait> {
+    /// Waits on the futex at `addr` for a wakeup matching `mask`, unless it
+    /// does not hold `expected`.
+    ///
+    /// The kernel only reads the futex when the operation is submitted, and
+    /// then uses its address as a key.
+    pub(crate) fn futex_wait(
+        addr: *const u32,
+        expected: u32,
+        mask: u32,
+    ) -> io::Result<Op<FutexWait>> {
+        Op::submit_with(FutexWait, |_| {
+            RawSqe {
+                opcode: IORING_OP_FUTEX_WAIT,
+                fd: FUTEX2_SIZE_U32 | FUTEX2_PRIVATE,
+                addr: addr as u64,
+                // `addr2`, which shares its place with the offset
+                off: expected as u64,
+                addr3: mask as u64,
+                ..Default::default()
+            }
+            .build()
+        })
+    }
+}
+
+impl Completable for FutexWait {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
+
+pub(crate) struct FutexWake;
+
+impl Op<FutexWake> {
+    /// Wakes up to `n` waiters of the futex at `addr` whose mask intersects
+    /// `mask`.
+    pub(crate) fn futex_wake(addr: *const u32, n: u32, mask: u32) -> io::Result<Op<FutexWake>> {
+        Op::submit_with(FutexWake, |_| {
+            RawSqe {
+                opcode: IORING_OP_FUTEX_WAKE,
+                fd: FUTEX2_SIZE_U32 | FUTEX2_PRIVATE,
+                addr: addr as u64,
+                // `addr2`, which shares its place with the offset
+                off: n as u64,
+                addr3: mask as u64,
+                ..Default::default()
+            }
+            .build()
+        })
+    }
+}
+
+impl Completable for FutexWake {
+    type Output = io::Result<usize>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|n| n as usize)
+    }
+}
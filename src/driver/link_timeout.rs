@@ -0,0 +1,96 @@
+use crate::runtime::CONTEXT;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Extension trait bounding operations by a timeout enforced by the kernel.
+///
+/// Implemented for all futures, and meant for the futures returned by the
+/// operations of this crate, e.g. [`File::read_at`] or [`TcpStream::connect`].
+///
+/// [`File::read_at`]: crate::fs::File::read_at
+/// [`TcpStream::connect`]: crate::net::TcpStream::connect
+pub trait LinkTimeoutExt: Future + Sized {
+    /// Bounds the operation submitted by this future by `timeout`.
+    ///
+    /// The operation is submitted along with an `IORING_OP_LINK_TIMEOUT`
+    /// linked to it. If it does not complete within `timeout`, the kernel
+    /// cancels it, and it fails with [`ErrorKind::TimedOut`]. Unlike a timer
+    /// racing the future, this needs no wakeup of the runtime to take effect,
+    /// and the operation still returns its buffer.
+    ///
+    /// Only the first operation submitted by the future is bounded, e.g. the
+    /// first write of [`File::write_all_at`]. Operations submitted as part of
+    /// a linked chain, such as the sync of [`File::close_sync`], are not
+    /// bounded at all. Without io_uring, the timeout is ignored.
+    ///
+    /// [`ErrorKind::TimedOut`]: std::io::ErrorKind::TimedOut
+    /// [`File::write_all_at`]: crate::fs::File::write_all_at
+    /// [`File::close_sync`]: crate::fs::File::close_sync
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tokio_uring::fs::File;
+    /// use tokio_uring::LinkTimeoutExt;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let file = File::open("/dev/ttyS0").await?;
+    ///         let (res, _buf) = file
+    ///             .read_at(vec![0; 64], 0)
+    ///             .link_timeout(Duration::from_secs(1))
+    ///             .await;
+    ///         println!("read {:?}", res);
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    fn link_timeout(self, timeout: Duration) -> LinkTimeout<Self> {
+        LinkTimeout {
+            future: self,
+            timeout: Some(timeout),
+        }
+    }
+}
+
+impl<F: Future> LinkTimeoutExt for F {}
+
+/// Future returned by [`LinkTimeoutExt::link_timeout`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct LinkTimeout<F> {
+    future: F,
+
+    /// The timeout, until an operation was submitted with it
+    timeout: Option<Duration>,
+}
+
+impl<F: Future> Future for LinkTimeout<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // Safety: `future` is never moved out of `self`.
+        let me = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut me.future) };
+
+        let timeout = match me.timeout {
+            Some(timeout) if CONTEXT.with(|cx| cx.is_set()) => timeout,
+            _ => return future.poll(cx),
+        };
+
+        // The timeout is armed while the future is polled, to be taken by
+        // the first operation it submits.
+        CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.link_timeout = Some(timeout)));
+        let res = future.poll(cx);
+        let unused = CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.link_timeout.take()));
+
+        if unused.is_none() {
+            me.timeout = None;
+        }
+        res
+    }
+}
@@ -1,61 +1,240 @@
 mod accept;
 
+mod bind;
+pub(crate) use bind::{IORING_OP_BIND, IORING_OP_LISTEN};
+
+mod cancel;
+pub(crate) use cancel::cancel_all;
+pub use cancel::CancelToken;
+
 mod close;
 pub(crate) use close::Close;
 
 mod connect;
 
+mod epoll_ctl;
+pub(crate) use epoll_ctl::EpollCtl;
+
+mod eventfd_read;
+pub(crate) use eventfd_read::EventfdRead;
+
+mod fadvise;
+
+mod fallocate;
+
+mod features;
+pub use features::Features;
+
+mod fixed;
+use fixed::FixedFiles;
+pub(crate) use fixed::{fixed_slot, register_fd, FixedSlot};
+
+mod fixed_fd_install;
+pub(crate) use fixed_fd_install::IORING_OP_FIXED_FD_INSTALL;
+
+mod force_async;
+pub use force_async::{ForceAsync, ForceAsyncExt};
+
 mod fsync;
 
+mod ftruncate;
+pub(crate) use ftruncate::IORING_OP_FTRUNCATE;
+
+mod futex;
+pub(crate) use futex::{IORING_OP_FUTEX_WAIT, IORING_OP_FUTEX_WAKE};
+
+mod link_timeout;
+pub use link_timeout::{LinkTimeout, LinkTimeoutExt};
+
+mod msg_ring;
+pub use msg_ring::RingHandle;
+
 mod noop;
 pub(crate) use noop::NoOp;
 
-mod op;
+pub(crate) mod op;
 pub(crate) use op::Op;
 
 mod open;
+pub(crate) use open::open_flags;
+
+mod poll;
+pub use poll::PollFd;
+
+mod probe;
+pub use probe::Probe;
+
+mod provide_buffers;
+
+mod raw;
 
 mod read;
 
+mod read_multi;
+pub(crate) use read_multi::{ReadMultiStream, IORING_OP_READ_MULTISHOT};
+
+mod read_ring;
+
 mod readv;
 
+mod recv;
+
 mod recv_from;
 
+mod recvmsg;
+
+mod release;
+pub(crate) use release::Releaser;
+use release::Releases;
+
 mod rename_at;
 
+mod send;
+
 mod send_to;
 
 mod send_zc;
 
+mod sendmsg;
+
 mod shared_fd;
+#[cfg(all(debug_assertions, feature = "tracing"))]
+pub(crate) use shared_fd::open_fds;
 pub(crate) use shared_fd::SharedFd;
 
+mod shutdown;
+
 mod socket;
 pub(crate) use socket::Socket;
 
+mod splice;
+
+mod sq_reservation;
+pub use sq_reservation::SqReservation;
+
+mod sq_stats;
+pub use sq_stats::SqStats;
+
+mod statx;
+
+mod tag;
+use tag::Tag;
+pub use tag::TaggedCompletion;
+
+#[cfg(feature = "tracing")]
+mod trace;
+
 mod unlink_at;
 
 mod util;
 
+mod wait_strategy;
+pub use wait_strategy::WaitStrategy;
+
 mod write;
 
 mod writev;
 
 use crate::driver::op::Lifecycle;
 use io_uring::opcode::AsyncCancel;
-use io_uring::IoUring;
+use io_uring::{squeue, types, IoUring};
 use slab::Slab;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
 use std::os::unix::io::{AsRawFd, RawFd};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
 pub(crate) struct Driver {
     /// In-flight operations
     ops: Ops,
 
-    /// IoUring bindings
-    pub(crate) uring: IoUring,
+    /// IoUring bindings, `None` once torn down for being idle, see
+    /// `Builder::idle_timeout`
+    ring: Option<IoUring>,
+
+    /// What the ring is built from, to rebuild it after a teardown
+    ring_config: RingConfig,
+
+    /// Task driving the ring, woken once the ring is rebuilt
+    ring_waker: Option<Waker>,
+
+    /// Features of the ring, as reported when first built
+    features: Features,
+
+    /// Number of entries of the submission queue
+    sq_entries: usize,
+
+    /// How long the ring stays idle before being torn down
+    idle_timeout: Option<Duration>,
+
+    /// When the ring was last used
+    last_active: Instant,
+
+    /// Whether buffers are registered with the ring
+    pub(crate) buffers_registered: bool,
+
+    /// Whether an `eventfd` is registered with the ring
+    pub(crate) eventfd_registered: bool,
+
+    /// Whether handles to the ring were taken, through which other rings post
+    /// messages
+    pub(crate) handles_taken: bool,
+
+    /// Identifiers of the groups of buffers provided to the ring, until the
+    /// kernel is done with them
+    buf_groups: HashSet<u16>,
+
+    /// Identifier tried first for the next group of provided buffers
+    next_buf_group: u16,
+
+    /// Releases queued while the driver was in use, see `Releaser`
+    releases: Rc<Releases>,
+
+    /// Callback invoked on the messages posted by other rings
+    on_message: Option<Arc<dyn Fn(u64) + Send + Sync>>,
+
+    /// Supported operations, queried on first use
+    probe: Option<Probe>,
+
+    /// Whether completions are only posted when reaped, see
+    /// `Builder::defer_taskrun`
+    defer_taskrun: bool,
+
+    /// Number of operations left to submit in the current linked chain
+    link: usize,
+
+    /// Flag linking the operations of the current chain
+    link_flag: io_uring::squeue::Flags,
+
+    /// Indices of the operations of the current chain, if linked with
+    /// `IOSQE_IO_LINK`
+    chain_ops: Vec<usize>,
+
+    /// Timeout to link to the next operation, armed by a `LinkTimeout`
+    /// future while it is polled
+    pub(crate) link_timeout: Option<Duration>,
+
+    /// Whether to submit operations with `IOSQE_ASYNC`, set by a
+    /// `ForceAsync` future while it is polled
+    pub(crate) force_async: bool,
+
+    /// Maximum number of completions reaped by a single tick
+    max_cqe_per_tick: usize,
+
+    /// Fixed file table, registered on first use
+    fixed_files: Option<FixedFiles>,
+
+    /// How to wait for completions before the thread parks
+    wait_strategy: WaitStrategy,
 }
 
+/// Callback passed the completions of a dropped operation
+type Discard = Box<dyn FnMut(&op::CqeResult)>;
+
 struct Ops {
     // When dropping the driver, all in-flight operations must have completed. This
     // type wraps the slab and ensures that, on drop, the slab is empty.
@@ -63,20 +242,361 @@ struct Ops {
 
     /// Received but unserviced Op completions
     completions: Slab<op::Completion>,
+
+    /// Maximum number of in-flight operations holding a buffer
+    max_in_flight: usize,
+
+    /// In-flight operations holding a buffer, by index
+    in_flight: HashSet<usize>,
+
+    /// Tasks waiting for an in-flight operation holding a buffer to complete
+    in_flight_waiters: Vec<Waker>,
+
+    /// Tags attached to in-flight operations, by index
+    tags: HashMap<usize, Tag>,
+
+    /// Callback invoked on the completion of tagged operations
+    on_tagged_completion: Option<Arc<dyn Fn(TaggedCompletion) + Send + Sync>>,
+
+    /// Number of operations yet to complete, by epoch, the first one being
+    /// `first_epoch`. A new epoch starts whenever quiescence is awaited.
+    pending: VecDeque<usize>,
+
+    /// Oldest epoch with operations yet to complete, or the current one
+    first_epoch: u64,
+
+    /// Epoch each operation was submitted in, by index
+    epochs: Vec<u64>,
+
+    /// Tasks waiting for the operations of past epochs to complete
+    quiesce_waiters: Vec<Waker>,
+
+    /// First error of each chain of linked operations, shared by the
+    /// operations of the chain, by index
+    chains: HashMap<usize, Rc<Cell<Option<i32>>>>,
+
+    /// Timeouts linked to in-flight operations, by index. The kernel reads
+    /// them when the operations are submitted.
+    link_timeouts: HashMap<usize, Box<types::Timespec>>,
+
+    /// Scope operations are submitted in, while an `OpSet` polls one of its
+    /// futures
+    scope: Option<u64>,
+
+    /// Identifier of the next scope
+    next_scope: u64,
+
+    /// Scope of the in-flight operations submitted in one, by index
+    scopes: HashMap<usize, u64>,
+
+    /// Callbacks passed the completions of dropped operations, by index, see
+    /// `Driver::on_discard`
+    discards: HashMap<usize, Discard>,
+
+    /// Spans of the in-flight operations, by index
+    #[cfg(feature = "tracing")]
+    spans: HashMap<usize, trace::OpSpan>,
+
+    /// Indices of the operations queued since the last submission
+    #[cfg(feature = "tracing")]
+    unsubmitted: Vec<usize>,
 }
 
 impl Driver {
     pub(crate) fn new(b: &crate::Builder) -> io::Result<Driver> {
-        let uring = b.urb.build(b.entries)?;
+        let ring_config = RingConfig {
+            entries: b.entries,
+            cq_entries: b.cq_entries,
+            sqpoll_cpu: b.sqpoll_cpu,
+            coop_taskrun: b.coop_taskrun,
+            defer_taskrun: b.defer_taskrun,
+            urb: b.urb.clone(),
+        };
+        if b.raise_memlock {
+            raise_memlock();
+        }
+        let uring = build_uring(&ring_config)?;
 
         Ok(Driver {
-            ops: Ops::new(),
-            uring,
+            ops: Ops::new(b.max_in_flight, b.on_tagged_completion.clone()),
+            features: Features::new(uring.params()),
+            sq_entries: uring.params().sq_entries() as usize,
+            ring: Some(uring),
+            ring_config,
+            ring_waker: None,
+            idle_timeout: b.idle_timeout,
+            last_active: Instant::now(),
+            buffers_registered: false,
+            eventfd_registered: false,
+            handles_taken: false,
+            buf_groups: HashSet::new(),
+            next_buf_group: 0,
+            releases: Rc::new(Releases::default()),
+            on_message: b.on_message.clone(),
+            probe: None,
+            defer_taskrun: b.defer_taskrun,
+            link: 0,
+            link_flag: io_uring::squeue::Flags::IO_LINK,
+            chain_ops: Vec::new(),
+            link_timeout: None,
+            force_async: false,
+            max_cqe_per_tick: b.max_cqe_per_tick,
+            fixed_files: None,
+            wait_strategy: b.wait_strategy,
         })
     }
 
-    fn wait(&self) -> io::Result<usize> {
-        self.uring.submit_and_wait(1)
+    /// Returns the ring, rebuilding it if it was torn down for being idle.
+    pub(crate) fn uring(&mut self) -> io::Result<&mut IoUring> {
+        self.last_active = Instant::now();
+
+        if self.ring.is_none() {
+            self.ring = Some(build_uring(&self.ring_config)?);
+            if let Some(waker) = self.ring_waker.take() {
+                waker.wake();
+            }
+        }
+
+        Ok(self.ring.as_mut().unwrap())
+    }
+
+    /// Returns the ring, which must not be torn down, e.g. as operations are
+    /// in flight.
+    pub(crate) fn live_uring(&mut self) -> &mut IoUring {
+        self.ring
+            .as_mut()
+            .expect("the ring is only torn down while idle")
+    }
+
+    /// Returns the features of the ring.
+    pub(crate) fn features(&self) -> Features {
+        self.features
+    }
+
+    /// Returns the occupancy of the submission queue.
+    pub(crate) fn sq_stats(&mut self) -> SqStats {
+        match self.ring.as_mut() {
+            Some(uring) => {
+                let sq = uring.submission();
+                SqStats::new(sq.len(), sq.capacity())
+            }
+            None => SqStats::new(0, self.sq_entries),
+        }
+    }
+
+    /// Poll for the ring to be built, returning its file descriptor.
+    pub(crate) fn poll_ring(&mut self, cx: &mut Context<'_>) -> Poll<RawFd> {
+        match &self.ring {
+            Some(uring) => Poll::Ready(uring.as_raw_fd()),
+            None => {
+                self.ring_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Returns when the ring may be torn down for being idle, if
+    /// `Builder::idle_timeout` is set.
+    ///
+    /// While the ring is in use, the deadline is a whole timeout away.
+    pub(crate) fn idle_deadline(&self) -> Option<Instant> {
+        let timeout = self.idle_timeout?;
+
+        if self.is_idle() {
+            Some(self.last_active + timeout)
+        } else {
+            Some(Instant::now() + timeout)
+        }
+    }
+
+    /// Whether tearing down the ring loses nothing: no operations are in
+    /// flight or queued, and nothing is registered with the ring.
+    fn is_idle(&self) -> bool {
+        let uring = match &self.ring {
+            Some(uring) => uring,
+            None => return false,
+        };
+
+        // Safety: the submission queue is only read.
+        let queued = unsafe { uring.submission_shared().len() };
+
+        self.ops.lifecycle.is_empty()
+            && queued == 0
+            && self.link == 0
+            && !self.buffers_registered
+            && !self.eventfd_registered
+            && !self.handles_taken
+            && self.buf_groups.is_empty()
+            && self.releases.borrow().is_empty()
+            && self.fixed_files.as_ref().is_none_or(FixedFiles::is_unused)
+    }
+
+    /// Tear down the ring if it is idle, returning whether it was. It is
+    /// rebuilt on next use.
+    pub(crate) fn release_ring(&mut self) -> bool {
+        if !self.is_idle() {
+            return false;
+        }
+
+        self.fixed_files = None;
+        self.ring = None;
+        true
+    }
+
+    /// Returns the operations supported by the kernel.
+    ///
+    /// The kernel is only queried the first time this is called.
+    pub(crate) fn probe(&mut self) -> io::Result<Probe> {
+        match self.probe {
+            Some(probe) => Ok(probe),
+            None => {
+                let probe = Probe::register(self.uring()?)?;
+                self.probe = Some(probe);
+                Ok(probe)
+            }
+        }
+    }
+
+    /// Take an unused slot of the fixed file table, registering the table
+    /// first if needed.
+    pub(crate) fn fixed_slot(&mut self) -> Option<FixedSlot> {
+        if self.fixed_files.is_none() {
+            self.fixed_files = Some(FixedFiles::register(self.uring().ok()?).ok()?);
+        }
+
+        self.fixed_files.as_ref().unwrap().alloc()
+    }
+
+    /// Start waiting for the operations submitted so far to complete,
+    /// returning the epoch to pass to `poll_quiesced`.
+    pub(crate) fn start_quiesce(&mut self) -> u64 {
+        self.ops.start_epoch()
+    }
+
+    /// Poll for the completion of the operations submitted before the call
+    /// to `start_quiesce` which returned `epoch`.
+    pub(crate) fn poll_quiesced(&mut self, epoch: u64, cx: &mut Context<'_>) -> Poll<()> {
+        self.ops.poll_quiesced(epoch, cx)
+    }
+
+    /// Queue the cancellation of the in-flight operation at `index`.
+    ///
+    /// The completion of the cancellation itself is ignored, the operation
+    /// completes as usual, likely with `ECANCELED`.
+    pub(crate) fn cancel(&mut self, index: usize) -> io::Result<()> {
+        self.push_untracked(AsyncCancel::new(index as u64).build())
+    }
+
+    /// Pass the completions of the operation at `index` to `f` once the
+    /// operation is dropped, rather than discarding them, e.g. to recover the
+    /// buffers they selected.
+    pub(crate) fn on_discard(&mut self, index: usize, f: Discard) {
+        self.ops.discards.insert(index, f);
+    }
+
+    /// Allocate the identifier of a new scope of operations.
+    pub(crate) fn new_scope(&mut self) -> u64 {
+        let scope = self.ops.next_scope;
+        self.ops.next_scope += 1;
+        scope
+    }
+
+    /// Set the scope operations are submitted in from now on, returning the
+    /// previous one.
+    pub(crate) fn set_scope(&mut self, scope: Option<u64>) -> Option<u64> {
+        std::mem::replace(&mut self.ops.scope, scope)
+    }
+
+    /// Queue the cancellation of the in-flight operations submitted in
+    /// `scope` whose `Op` was dropped.
+    ///
+    /// Operations still owned, e.g. by the output of a future of the scope,
+    /// are left alone.
+    pub(crate) fn cancel_scope(&mut self, scope: u64) {
+        let lifecycle = &self.ops.lifecycle;
+        let indices: Vec<_> = self
+            .ops
+            .scopes
+            .iter()
+            .filter(|&(&index, &s)| {
+                s == scope && matches!(lifecycle.get(index), Some(Lifecycle::Ignored(..)))
+            })
+            .map(|(&index, _)| index)
+            .collect();
+
+        for index in indices {
+            // If the cancellation cannot be submitted, the operation simply
+            // runs to completion.
+            let _ = self.cancel(index);
+        }
+    }
+
+    /// Queue the removal of the poll armed by the dropped operation at
+    /// `index`, unless it already posted its final completion.
+    ///
+    /// As with `cancel`, the completion of the removal is ignored.
+    pub(crate) fn remove_poll(&mut self, index: usize) -> io::Result<()> {
+        // Dropped operations are only kept while more completions are expected.
+        if !matches!(self.ops.lifecycle.get(index), Some(Lifecycle::Ignored(..))) {
+            return Ok(());
+        }
+        self.push_untracked(io_uring::opcode::PollRemove::new(index as u64).build())
+    }
+
+    /// Push `sqe`, whose completion is ignored, flushing the submission queue
+    /// as needed.
+    pub(crate) fn push_untracked(&mut self, sqe: squeue::Entry) -> io::Result<()> {
+        let sqe = sqe.user_data(u64::MAX);
+        while unsafe { self.uring()?.submission().push(&sqe).is_err() } {
+            self.submit()?;
+        }
+        Ok(())
+    }
+
+    /// Allocate the identifier of a new group of provided buffers, live until
+    /// passed to `free_buf_group`.
+    ///
+    /// Identifiers the kernel may still use are never handed out again.
+    pub(crate) fn new_buf_group(&mut self) -> io::Result<u16> {
+        if self.buf_groups.len() > u16::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                "no buffer group identifier left",
+            ));
+        }
+
+        let mut id = self.next_buf_group;
+        while self.buf_groups.contains(&id) {
+            id = id.wrapping_add(1);
+        }
+        self.buf_groups.insert(id);
+        self.next_buf_group = id.wrapping_add(1);
+        Ok(id)
+    }
+
+    /// Free the identifier of a group of provided buffers, once the kernel is
+    /// done with the group.
+    pub(crate) fn free_buf_group(&mut self, id: u16) {
+        self.buf_groups.remove(&id);
+    }
+
+    fn wait(&mut self) -> io::Result<usize> {
+        self.live_uring().submit_and_wait(1)
+    }
+
+    /// Wait for a completion, giving up at `deadline` if the kernel supports
+    /// waiting with a timeout.
+    fn wait_until(&mut self, deadline: Instant) -> io::Result<usize> {
+        if !self.features.ext_arg() {
+            return self.wait();
+        }
+
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let ts = types::Timespec::new()
+            .sec(timeout.as_secs())
+            .nsec(timeout.subsec_nanos());
+        let args = types::SubmitArgs::new().timespec(&ts);
+        self.live_uring().submitter().submit_with_args(1, &args)
     }
 
     // only used in tests rn
@@ -85,11 +605,32 @@ impl Driver {
         self.ops.lifecycle.len()
     }
 
-    pub(crate) fn tick(&mut self) {
-        let mut cq = self.uring.completion();
+    /// Reap completions, returning whether the completion queue was drained.
+    ///
+    /// At most `max_cqe_per_tick` completions are processed.
+    pub(crate) fn tick(&mut self) -> bool {
+        let uring = match self.ring.as_mut() {
+            Some(uring) => uring,
+            None => return true,
+        };
+
+        if self.defer_taskrun {
+            // Completions are only posted once the kernel is entered to
+            // get events.
+            let _ = unsafe {
+                uring
+                    .submitter()
+                    .enter::<libc::sigset_t>(0, 0, IORING_ENTER_GETEVENTS, None)
+            };
+        }
+
+        let mut cq = uring.completion();
         cq.sync();
+        if !cq.is_empty() {
+            self.last_active = Instant::now();
+        }
 
-        for cqe in cq {
+        for cqe in cq.by_ref().take(self.max_cqe_per_tick) {
             if cqe.user_data() == u64::MAX {
                 // Result of the cancellation action. There isn't anything we
                 // need to do here. We must wait for the CQE for the operation
@@ -97,18 +638,110 @@ impl Driver {
                 continue;
             }
 
+            if cqe.user_data() & msg_ring::MESSAGE_BIT != 0 {
+                // A message posted by another ring
+                if let Some(on_message) = &self.on_message {
+                    on_message(msg_ring::decode(cqe.user_data(), cqe.result()));
+                }
+                continue;
+            }
+
+            if cqe.user_data() & provide_buffers::GROUP_REMOVED_BIT != 0 {
+                // The buffers of a dropped group were removed
+                self.buf_groups.remove(&(cqe.user_data() as u16));
+                continue;
+            }
+
             let index = cqe.user_data() as _;
 
             self.ops.complete(index, cqe.into());
         }
+
+        let drained = cq.is_empty();
+        drop(cq);
+
+        self.run_releases();
+        drained
+    }
+
+    /// Ensure at least `n` entries are free in the submission queue, flushing
+    /// it to the kernel as needed.
+    pub(crate) fn reserve(&mut self, n: usize) -> io::Result<()> {
+        if n > self.uring()?.submission().capacity() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "more entries requested than the submission queue holds",
+            ));
+        }
+
+        loop {
+            let sq = self.uring()?.submission();
+            if sq.capacity() - sq.len() >= n {
+                return Ok(());
+            }
+            drop(sq);
+
+            self.submit()?;
+        }
     }
 
     pub(crate) fn submit(&mut self) -> io::Result<()> {
+        self.run_releases();
+        self.submit_queued().map(|_| ())
+    }
+
+    /// Spin until a completion is posted, as configured by
+    /// `Builder::wait_strategy`, before the thread parks.
+    ///
+    /// Spinning is pointless with no operation in flight, and with `IORING_SETUP_DEFER_TASKRUN`, as
+    /// completions are then only posted when entering the kernel.
+    pub(crate) fn spin(&mut self) {
+        let max_spins = match self.wait_strategy {
+            WaitStrategy::Spin { max_spins } => max_spins,
+            WaitStrategy::Block => return,
+        };
+        if self.defer_taskrun || self.ops.lifecycle.is_empty() {
+            return;
+        }
+        let uring = match self.ring.as_mut() {
+            Some(uring) => uring,
+            None => return,
+        };
+
+        let mut cq = uring.completion();
+        for _ in 0..max_spins {
+            cq.sync();
+            if !cq.is_empty() {
+                return;
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Submit the queued SQEs, if any, returning how many were submitted.
+    pub(crate) fn flush(&mut self) -> io::Result<usize> {
+        let queued = match self.ring.as_mut() {
+            Some(uring) => !uring.submission().is_empty(),
+            None => false,
+        };
+        if !queued {
+            return Ok(0);
+        }
+        self.submit_queued()
+    }
+
+    fn submit_queued(&mut self) -> io::Result<usize> {
         loop {
-            match self.uring.submit() {
-                Ok(_) => {
-                    self.uring.submission().sync();
-                    return Ok(());
+            let uring = match self.ring.as_mut() {
+                Some(uring) => uring,
+                None => return Ok(0),
+            };
+            match uring.submit() {
+                Ok(n) => {
+                    uring.submission().sync();
+                    #[cfg(feature = "tracing")]
+                    self.ops.trace_submitted();
+                    return Ok(n);
                 }
                 Err(ref e) if e.raw_os_error() == Some(libc::EBUSY) => {
                     self.tick();
@@ -122,9 +755,83 @@ impl Driver {
     }
 }
 
-impl AsRawFd for Driver {
-    fn as_raw_fd(&self) -> RawFd {
-        self.uring.as_raw_fd()
+/// How long dropping the driver waits for the operations still in flight.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `IORING_ENTER_GETEVENTS`, not exported by the `io-uring` crate.
+const IORING_ENTER_GETEVENTS: u32 = 1;
+
+/// The configuration of the ring, as set on the builder.
+struct RingConfig {
+    entries: u32,
+    cq_entries: Option<u32>,
+    sqpoll_cpu: Option<u32>,
+    coop_taskrun: bool,
+    defer_taskrun: bool,
+    urb: io_uring::Builder,
+}
+
+/// Create the ring, as configured by the builder.
+fn build_uring(b: &RingConfig) -> io::Result<IoUring> {
+    let mut urb = b.urb.clone();
+
+    if let Some(cpu) = b.sqpoll_cpu {
+        let cpus = syscall!(sysconf(libc::_SC_NPROCESSORS_CONF))?;
+        if i64::from(cpu) >= cpus as i64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid SQPOLL CPU {}, the system has {} CPUs", cpu, cpus),
+            ));
+        }
+        urb.setup_sqpoll_cpu(cpu);
+    }
+    if let Some(cq_entries) = b.cq_entries {
+        if cq_entries < b.entries {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} completion queue entries is fewer than the {} submission queue entries",
+                    cq_entries, b.entries
+                ),
+            ));
+        }
+        urb.setup_cqsize(cq_entries);
+    }
+    if b.coop_taskrun {
+        urb.setup_coop_taskrun();
+    }
+    if b.defer_taskrun {
+        urb.setup_single_issuer().setup_defer_taskrun();
+    }
+
+    urb.build(b.entries).map_err(|e| {
+        if e.raw_os_error() == Some(libc::EINVAL) && (b.coop_taskrun || b.defer_taskrun) {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "cooperative and deferred task running require Linux 5.19 and 6.1",
+            )
+        } else if let Some(cpu) = b.sqpoll_cpu {
+            io::Error::new(
+                e.kind(),
+                format!("failed to pin the SQPOLL thread to CPU {}: {}", cpu, e),
+            )
+        } else {
+            e
+        }
+    })
+}
+
+/// Raise the soft `RLIMIT_MEMLOCK` to the hard limit, ignoring failures.
+fn raise_memlock() {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) } == 0
+        && limit.rlim_cur < limit.rlim_max
+    {
+        limit.rlim_cur = limit.rlim_max;
+        unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &limit) };
     }
 }
 
@@ -140,8 +847,16 @@ impl AsRawFd for Driver {
 /// an op is finished MUST be added, otherwise our shutdown process is unsound.
 impl Drop for Driver {
     fn drop(&mut self) {
+        // A torn down ring had nothing in flight.
+        if self.ring.is_none() {
+            return;
+        }
+
+        // Release what was dropped while the driver was in use.
+        self.run_releases();
+
         // get all ops in flight for cancellation
-        while !self.uring.submission().is_empty() {
+        while !self.live_uring().submission().is_empty() {
             self.submit().expect("Internal error when dropping driver");
         }
 
@@ -177,12 +892,16 @@ impl Drop for Driver {
             if let Lifecycle::Ignored(..) = cycle {
                 unsafe {
                     while self
-                        .uring
+                        .ring
+                        .as_mut()
+                        .unwrap()
                         .submission()
                         .push(&AsyncCancel::new(id as u64).build().user_data(u64::MAX))
                         .is_err()
                     {
-                        self.uring
+                        self.ring
+                            .as_mut()
+                            .unwrap()
                             .submit_and_wait(1)
                             .expect("Internal error when dropping driver");
                     }
@@ -196,6 +915,12 @@ impl Drop for Driver {
         // by the complete logic called by `tick()`
         //
         // Completed Entries are removed here directly
+        //
+        // A stuck operation, such as a close blocked on an unresponsive
+        // network filesystem, must not hang the shutdown forever. Once
+        // `DRAIN_TIMEOUT` elapsed, the remaining operations are leaked: their
+        // resources are never freed, as the kernel may still use them.
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
         let mut id = 0;
         loop {
             if self.ops.lifecycle.is_empty() {
@@ -204,10 +929,25 @@ impl Drop for Driver {
             // Cycles are either all ignored or complete
             // If there is at least one Ignored still to process, call wait
             match self.ops.lifecycle.get(id) {
+                Some(Lifecycle::Ignored(..)) if Instant::now() >= deadline => {
+                    #[cfg(all(debug_assertions, feature = "tracing"))]
+                    tracing::warn!(
+                        operations = self.ops.lifecycle.len(),
+                        "operations still in flight after the shutdown timeout, leaking them"
+                    );
+
+                    for cycle in self.ops.lifecycle.drain() {
+                        if let Lifecycle::Ignored(data) = cycle {
+                            std::mem::forget(data);
+                        }
+                    }
+                    break;
+                }
+
                 Some(Lifecycle::Ignored(..)) => {
                     // If waiting fails, ignore the error. The wait will be attempted
                     // again on the next loop.
-                    let _ = self.wait();
+                    let _ = self.wait_until(deadline);
                     self.tick();
                 }
 
@@ -226,10 +966,114 @@ impl Drop for Driver {
 }
 
 impl Ops {
-    fn new() -> Ops {
+    fn new(
+        max_in_flight: usize,
+        on_tagged_completion: Option<Arc<dyn Fn(TaggedCompletion) + Send + Sync>>,
+    ) -> Ops {
         Ops {
             lifecycle: Slab::with_capacity(64),
             completions: Slab::with_capacity(64),
+            max_in_flight,
+            in_flight: HashSet::new(),
+            in_flight_waiters: Vec::new(),
+            tags: HashMap::new(),
+            on_tagged_completion,
+            pending: VecDeque::from(vec![0]),
+            first_epoch: 0,
+            epochs: Vec::new(),
+            quiesce_waiters: Vec::new(),
+            link_timeouts: HashMap::new(),
+            chains: HashMap::new(),
+            scope: None,
+            next_scope: 0,
+            scopes: HashMap::new(),
+            discards: HashMap::new(),
+            #[cfg(feature = "tracing")]
+            spans: HashMap::new(),
+            #[cfg(feature = "tracing")]
+            unsubmitted: Vec::new(),
+        }
+    }
+
+    /// Open the span of the operation at `index`, submitted with `sqe`.
+    #[cfg(feature = "tracing")]
+    fn trace(&mut self, index: usize, sqe: &squeue::Entry) {
+        self.spans.insert(index, trace::OpSpan::new(sqe));
+        self.unsubmitted.push(index);
+    }
+
+    /// Record the submission of the queued operations.
+    #[cfg(feature = "tracing")]
+    fn trace_submitted(&mut self) {
+        let now = Instant::now();
+        for index in self.unsubmitted.drain(..) {
+            if let Some(span) = self.spans.get_mut(&index) {
+                span.submit(now);
+            }
+        }
+    }
+
+    /// Start a new epoch, returning the previous one, whose operations must
+    /// complete for the runtime to be quiesced.
+    fn start_epoch(&mut self) -> u64 {
+        let epoch = self.first_epoch + self.pending.len() as u64 - 1;
+        self.pending.push_back(0);
+        self.drain_epochs();
+        epoch
+    }
+
+    /// Poll for the completion of all operations submitted up to `epoch`.
+    fn poll_quiesced(&mut self, epoch: u64, cx: &mut Context<'_>) -> Poll<()> {
+        if self.first_epoch > epoch {
+            Poll::Ready(())
+        } else {
+            self.quiesce_waiters.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    /// Account for the operation at `index` no longer being in flight.
+    fn settle(&mut self, index: usize) {
+        let epoch = self.epochs[index];
+        self.pending[(epoch - self.first_epoch) as usize] -= 1;
+        self.drain_epochs();
+    }
+
+    /// Forget the past epochs without operations in flight.
+    fn drain_epochs(&mut self) {
+        let mut drained = false;
+        while self.pending.len() > 1 && self.pending[0] == 0 {
+            self.pending.pop_front();
+            self.first_epoch += 1;
+            drained = true;
+        }
+
+        if drained {
+            for waker in self.quiesce_waiters.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Attach a tag to the operation at `index`, if tags are reported.
+    fn tag(&mut self, index: usize, tag: u64) {
+        if self.on_tagged_completion.is_some() {
+            self.tags.insert(index, Tag::new(tag));
+        }
+    }
+
+    /// Returns whether no in-flight operations limit is configured.
+    fn is_unlimited(&self) -> bool {
+        self.max_in_flight == usize::MAX
+    }
+
+    /// Poll for room for one more in-flight operation holding a buffer.
+    fn poll_in_flight_room(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.in_flight.len() < self.max_in_flight {
+            Poll::Ready(())
+        } else {
+            self.in_flight_waiters.push(cx.waker().clone());
+            Poll::Pending
         }
     }
 
@@ -242,18 +1086,112 @@ impl Ops {
 
     // Insert a new operation
     fn insert(&mut self) -> usize {
-        self.lifecycle.insert(op::Lifecycle::Submitted)
+        let index = self.lifecycle.insert(op::Lifecycle::Submitted);
+
+        if self.epochs.len() <= index {
+            self.epochs.resize(index + 1, 0);
+        }
+        self.epochs[index] = self.first_epoch + self.pending.len() as u64 - 1;
+        *self.pending.back_mut().unwrap() += 1;
+
+        if let Some(scope) = self.scope {
+            self.scopes.insert(index, scope);
+        }
+
+        index
     }
 
     // Remove an operation
     fn remove(&mut self, index: usize) {
+        self.discards.remove(&index);
         self.lifecycle.remove(index);
     }
 
-    fn complete(&mut self, index: usize, cqe: op::CqeResult) {
+    /// Track the operations at `indices` as a chain, so operations canceled
+    /// by the failure of a previous one report its error.
+    fn chain(&mut self, indices: &[usize]) {
+        let first_error = Rc::new(Cell::new(None));
+        for &index in indices {
+            self.chains.insert(index, first_error.clone());
+        }
+    }
+
+    // Remove an operation which never reached the kernel
+    fn discard(&mut self, index: usize) {
+        #[cfg(feature = "tracing")]
+        self.spans.remove(&index);
+        self.settle(index);
+        self.chains.remove(&index);
+        self.scopes.remove(&index);
+        self.lifecycle.remove(index);
+    }
+
+    fn complete(&mut self, index: usize, mut cqe: op::CqeResult) {
+        // The buffer is released by the kernel with the last completion,
+        // whether or not the operation is still awaited.
+        if !io_uring::cqueue::more(cqe.flags) && self.in_flight.remove(&index) {
+            for waker in self.in_flight_waiters.drain(..) {
+                waker.wake();
+            }
+        }
+
+        if !io_uring::cqueue::more(cqe.flags) {
+            self.settle(index);
+            self.scopes.remove(&index);
+
+            // The operations following a failed one in a chain are canceled,
+            // which is reported as the failure itself. The kernel completes
+            // the failed operation first.
+            if let Some(first_error) = self.chains.remove(&index) {
+                if let Err(e) = &cqe.result {
+                    match (e.raw_os_error(), first_error.get()) {
+                        (Some(libc::ECANCELED), Some(errno)) => {
+                            cqe.result = Err(io::Error::from_raw_os_error(errno));
+                        }
+                        (Some(libc::ECANCELED), None) => {}
+                        (errno, None) => first_error.set(errno),
+                        _ => {}
+                    }
+                }
+            }
+
+            // An operation canceled by its linked timeout timed out.
+            if self.link_timeouts.remove(&index).is_some() {
+                if let Err(e) = &cqe.result {
+                    if e.raw_os_error() == Some(libc::ECANCELED) {
+                        cqe.result = Err(io::Error::from_raw_os_error(libc::ETIMEDOUT));
+                    }
+                }
+            }
+
+            #[cfg(feature = "tracing")]
+            if let Some(span) = self.spans.remove(&index) {
+                span.complete(match &cqe.result {
+                    Ok(n) => *n as i32,
+                    Err(e) => -e.raw_os_error().unwrap_or(0),
+                });
+            }
+
+            if let Some(tag) = self.tags.remove(&index) {
+                let result = match &cqe.result {
+                    Ok(n) => *n as i32,
+                    Err(e) => -e.raw_os_error().unwrap_or(0),
+                };
+                if let Some(f) = &self.on_tagged_completion {
+                    f(tag.complete(result));
+                }
+            }
+        }
+
+        if let Lifecycle::Ignored(..) = self.lifecycle[index] {
+            if let Some(discard) = self.discards.get_mut(&index) {
+                discard(&cqe);
+            }
+        }
+
         let completions = &mut self.completions;
         if self.lifecycle[index].complete(completions, cqe) {
-            self.lifecycle.remove(index);
+            self.remove(index);
         }
     }
 }
@@ -266,3 +1204,86 @@ impl Drop for Ops {
             .all(|(_, cycle)| matches!(cycle, Lifecycle::Completed(_))))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::op::Lifecycle;
+    use super::Op;
+    use crate::runtime::CONTEXT;
+
+    fn is_torn_down() -> bool {
+        CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.ring.is_none()))
+    }
+
+    #[test]
+    fn max_cqe_per_tick() {
+        crate::builder().max_cqe_per_tick(1).start(async {
+            let ops: Vec<_> = (0..3).map(|_| Op::no_op().unwrap()).collect();
+
+            CONTEXT.with(|cx| {
+                cx.with_driver_mut(|driver| {
+                    let completed = |driver: &super::Driver| {
+                        let lifecycles = driver.ops.lifecycle.iter();
+                        lifecycles
+                            .filter(|(_, lifecycle)| matches!(lifecycle, Lifecycle::Completed(_)))
+                            .count()
+                    };
+
+                    // All the completions are posted before the first tick.
+                    let submitter = driver.uring().unwrap().submitter();
+                    submitter.submit_and_wait(3).unwrap();
+
+                    // A single completion is reaped per tick.
+                    assert!(!driver.tick());
+                    assert_eq!(completed(driver), 1);
+                    assert!(!driver.tick());
+                    assert_eq!(completed(driver), 2);
+                    assert!(driver.tick());
+                    assert_eq!(completed(driver), 3);
+                })
+            });
+
+            for op in ops {
+                op.await.unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn idle_timeout() {
+        crate::builder()
+            .idle_timeout(Duration::from_millis(20))
+            .start(async {
+                crate::no_op().await.unwrap();
+                assert!(!is_torn_down());
+
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                assert!(is_torn_down());
+
+                // The ring is rebuilt on demand, and keeps working.
+                crate::no_op().await.unwrap();
+                assert!(!is_torn_down());
+                for _ in 0..3 {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    crate::no_op().await.unwrap();
+                }
+
+                // An operation in flight keeps the ring alive.
+                let mut fds = [0; 2];
+                assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+                let rx = crate::driver::SharedFd::new(fds[0]);
+                let read = crate::driver::Op::read_at(&rx, Vec::with_capacity(8), 0)
+                    .map_err(|(e, _)| e)
+                    .unwrap();
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                assert!(!is_torn_down());
+
+                assert_eq!(unsafe { libc::write(fds[1], b"x".as_ptr().cast(), 1) }, 1);
+                let (res, _) = read.await;
+                assert_eq!(res.unwrap(), 1);
+                unsafe { libc::close(fds[1]) };
+            });
+    }
+}
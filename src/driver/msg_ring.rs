@@ -0,0 +1,121 @@
+use crate::driver::Op;
+use crate::runtime::CONTEXT;
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::Arc;
+
+use crate::driver::op::{self, Completable};
+use io_uring::{opcode, types};
+
+/// Bit set in the `user_data` of the completions posted by
+/// `IORING_OP_MSG_RING`, which operation indices never have.
+pub(crate) const MESSAGE_BIT: u64 = 1 << 63;
+
+/// A handle to the ring of a runtime, through which other runtimes post it
+/// messages.
+///
+/// Obtained with [`tokio_uring::ring_handle`] on the thread of the target
+/// runtime, and passed to [`tokio_uring::post_to`] on the threads of others.
+/// The handle can be sent to and shared with other threads.
+///
+/// [`tokio_uring::ring_handle`]: crate::ring_handle
+/// [`tokio_uring::post_to`]: crate::post_to
+#[derive(Clone)]
+pub struct RingHandle {
+    /// Duplicate of the ring's file descriptor
+    fd: Arc<OwnedFd>,
+}
+
+impl RingHandle {
+    /// Create a handle to the current thread's ring, which is then kept for
+    /// the lifetime of the runtime.
+    pub(crate) fn current() -> io::Result<RingHandle> {
+        CONTEXT.with(|cx| {
+            if cx.is_fallback() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "messages require io_uring",
+                ));
+            }
+
+            cx.with_driver_mut(|driver| {
+                let ring_fd = driver.uring()?.as_raw_fd();
+                let fd = syscall!(fcntl(ring_fd, libc::F_DUPFD_CLOEXEC, 0))?;
+                // The handle refers to this ring, which must not be torn down
+                // for being idle.
+                driver.handles_taken = true;
+
+                Ok(RingHandle {
+                    fd: Arc::new(unsafe { OwnedFd::from_raw_fd(fd) }),
+                })
+            })
+        })
+    }
+}
+
+impl fmt::Debug for RingHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingHandle")
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
+
+/// Split `data` into the `user_data` and result of the completion posted on
+/// the target ring.
+fn encode(data: u64) -> (u64, i32) {
+    (MESSAGE_BIT | (data >> 32), data as u32 as i32)
+}
+
+/// Reassemble the data of a message from its completion.
+pub(crate) fn decode(user_data: u64, result: i32) -> u64 {
+    ((user_data & !MESSAGE_BIT) << 32) | result as u32 as u64
+}
+
+pub(crate) struct MsgRing {
+    /// Holds the target ring open while the operation is in flight.
+    #[allow(dead_code)]
+    fd: Arc<OwnedFd>,
+}
+
+impl Op<MsgRing> {
+    /// Submit a request to post a message carrying `data` to the ring of
+    /// `target`.
+    pub(crate) fn msg_ring(target: &RingHandle, data: u64) -> io::Result<Op<MsgRing>> {
+        let (user_data, result) = encode(data);
+
+        Op::submit_with(
+            MsgRing {
+                fd: target.fd.clone(),
+            },
+            |msg| {
+                opcode::MsgRingData::new(types::Fd(msg.fd.as_raw_fd()), result, user_data, None)
+                    .build()
+            },
+        )
+    }
+}
+
+impl Completable for MsgRing {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        for data in [0, 1, 0xdead_beef_0123_4567, u32::MAX as u64, u64::MAX] {
+            let (user_data, result) = encode(data);
+            assert_ne!(user_data, u64::MAX);
+            assert_eq!(decode(user_data, result), data);
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll, Waker};
 
-use io_uring::{cqueue, squeue};
+use io_uring::{cqueue, opcode, squeue, types};
 
 mod slab_list;
 
@@ -12,8 +12,10 @@ use slab::Slab;
 use slab_list::{SlabListEntry, SlabListIndices};
 
 use crate::driver;
+use crate::driver::CancelToken;
 use crate::runtime::CONTEXT;
 use crate::util::PhantomUnsendUnsync;
+use crate::BufResult;
 
 /// A SlabList is used to hold unserved completions.
 ///
@@ -44,6 +46,10 @@ pub(crate) struct SingleCQE;
 /// which combined resolve to a single Future value
 pub(crate) struct MultiCQEFuture;
 
+/// A Marker for Operations whose completion events are consumed one by one,
+/// as a stream
+pub(crate) struct MultiCQEStream;
+
 pub(crate) trait Completable {
     type Output;
     /// `complete` will be called for cqe's do not have the `more` flag set
@@ -98,6 +104,11 @@ impl<T, CqeType> Op<T, CqeType>
 where
     T: Completable,
 {
+    /// Returns the index of the operation, while it is in flight.
+    pub(crate) fn index(&self) -> Option<usize> {
+        (self.index != usize::MAX).then_some(self.index)
+    }
+
     /// Create a new operation
     fn new(data: T, inner: &mut driver::Driver) -> Self {
         Op {
@@ -113,21 +124,91 @@ where
     /// `state` is stored during the operation tracking any state submitted to
     /// the kernel.
     pub(super) fn submit_with<F>(data: T, f: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut T) -> squeue::Entry,
+    {
+        Op::try_submit_with(data, f).map_err(|(e, _)| e)
+    }
+
+    /// Like [`submit_with`], but `data` is returned with the error if the
+    /// operation could not be submitted, so its buffers can be recovered.
+    ///
+    /// [`submit_with`]: Op::submit_with
+    pub(super) fn try_submit_with<F>(data: T, f: F) -> Result<Self, (io::Error, T)>
     where
         F: FnOnce(&mut T) -> squeue::Entry,
     {
         CONTEXT.with(|cx| {
+            if cx.is_fallback() {
+                return Err((unsupported(), data));
+            }
+
             cx.with_driver_mut(|driver| {
+                if let Err(e) = driver.uring() {
+                    return Err((e, data));
+                }
+
                 // Create the operation
                 let mut op = Op::new(data, driver);
 
                 // Configure the SQE
-                let sqe = f(op.data.as_mut().unwrap()).user_data(op.index as _);
+                let mut sqe = f(op.data.as_mut().unwrap()).user_data(op.index as _);
+                if driver.force_async {
+                    sqe = sqe.flags(squeue::Flags::ASYNC);
+                }
+
+                #[cfg(feature = "tracing")]
+                driver.ops.trace(op.index, &sqe);
+
+                // Link to the next operation if part of a chain
+                let chained = driver.link > 0;
+                if chained && driver.link_flag == squeue::Flags::IO_LINK {
+                    driver.chain_ops.push(op.index);
+                }
+                if driver.link > 0 {
+                    driver.link -= 1;
+                    if driver.link > 0 {
+                        sqe = sqe.flags(driver.link_flag);
+                    }
+                }
+
+                // Link to a timeout if one is armed
+                if let Some(timeout) = driver.link_timeout.filter(|_| !chained) {
+                    driver.link_timeout = None;
+                    if let Err(e) = driver.reserve(2) {
+                        driver.ops.discard(op.index);
+                        op.index = usize::MAX;
+                        return Err((e, op.data.take().unwrap()));
+                    }
+
+                    let ts = Box::new(
+                        types::Timespec::new()
+                            .sec(timeout.as_secs())
+                            .nsec(timeout.subsec_nanos()),
+                    );
+                    let timeout = opcode::LinkTimeout::new(&*ts).build().user_data(u64::MAX);
+                    unsafe {
+                        let mut sq = driver.live_uring().submission();
+                        sq.push(&sqe.flags(squeue::Flags::IO_LINK))
+                            .expect("submission queue space was reserved");
+                        sq.push(&timeout)
+                            .expect("submission queue space was reserved");
+                    }
+                    driver.ops.link_timeouts.insert(op.index, ts);
+
+                    return Ok(op);
+                }
 
                 // Push the new operation
-                while unsafe { driver.uring.submission().push(&sqe).is_err() } {
+                while unsafe { driver.live_uring().submission().push(&sqe).is_err() } {
                     // If the submission queue is full, flush it to the kernel
-                    driver.submit()?;
+                    if let Err(e) = driver.submit() {
+                        // The operation never reached the kernel, so its data
+                        // can be taken back.
+                        driver.ops.discard(op.index);
+                        op.index = usize::MAX;
+                        return Err((e, op.data.take().unwrap()));
+                    }
                 }
 
                 Ok(op)
@@ -136,6 +217,196 @@ where
     }
 }
 
+/// Submit the `n` operations created by `f` as a chain linked with
+/// `IOSQE_IO_LINK`.
+///
+/// Operations in the chain are started in the order they are created, each one
+/// only once the previous one completed successfully. If an operation fails,
+/// the remaining operations complete with its error, and if it completes
+/// short, with `ECANCELED`.
+///
+/// Space for the whole chain is reserved in the submission queue up front, so
+/// it is guaranteed to reach the kernel in a single submission. If `f` creates
+/// fewer than `n` operations, e.g. because one of them failed to be created,
+/// the chain is terminated after the last created operation.
+pub(crate) fn link<F, R>(n: usize, f: F) -> io::Result<R>
+where
+    F: FnOnce() -> R,
+{
+    chain(n, squeue::Flags::IO_LINK, f)
+}
+
+/// Like [`link`], but the chain is linked with `IOSQE_IO_HARDLINK`.
+///
+/// Operations in the chain are still started in order, but a failed or short
+/// operation does not cancel the remaining ones.
+pub(crate) fn hard_link<F, R>(n: usize, f: F) -> io::Result<R>
+where
+    F: FnOnce() -> R,
+{
+    chain(n, squeue::Flags::IO_HARDLINK, f)
+}
+
+fn chain<F, R>(n: usize, flag: squeue::Flags, f: F) -> io::Result<R>
+where
+    F: FnOnce() -> R,
+{
+    struct Guard(usize);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            CONTEXT.with(|cx| {
+                cx.with_driver_mut(|driver| {
+                    if driver.link > 0 && driver.link < self.0 {
+                        // The last pushed operation is linked to the next one,
+                        // terminate the chain with a no-op that is ignored on
+                        // completion. Space for it remains reserved.
+                        let nop = opcode::Nop::new().build().user_data(u64::MAX);
+                        unsafe {
+                            driver
+                                .live_uring()
+                                .submission()
+                                .push(&nop)
+                                .expect("submission queue space was reserved");
+                        }
+                    }
+                    driver.link = 0;
+
+                    let indices = std::mem::take(&mut driver.chain_ops);
+                    if indices.len() > 1 {
+                        driver.ops.chain(&indices);
+                    }
+                })
+            })
+        }
+    }
+
+    CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Err(unsupported());
+        }
+
+        cx.with_driver_mut(|driver| {
+            assert_eq!(driver.link, 0, "operation chains cannot be nested");
+            driver.reserve(n)?;
+            driver.link = n;
+            driver.link_flag = flag;
+            Ok::<_, io::Error>(())
+        })
+    })?;
+
+    let _guard = Guard(n);
+    Ok(f())
+}
+
+/// The error of operations submitted on a runtime without io_uring.
+pub(crate) fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "operation not supported without io_uring",
+    )
+}
+
+/// Create, with `f`, an operation holding a buffer, once the number of such
+/// operations in flight is below the limit set with
+/// [`Builder::max_in_flight`].
+///
+/// Slots are released as the kernel completes operations, regardless of
+/// whether they are still awaited, so a task awaiting a slot never depends on
+/// another task being polled.
+///
+/// [`Builder::max_in_flight`]: crate::Builder::max_in_flight
+pub(crate) async fn limited<T, E, F>(f: F) -> Result<Op<T>, E>
+where
+    T: Completable + 'static,
+    F: FnOnce() -> Result<Op<T>, E>,
+{
+    in_flight_room().await;
+
+    // Nothing was awaited since room was found, so it is still available.
+    let op = f()?;
+    hold_in_flight(&op);
+    Ok(op)
+}
+
+/// Submit, with `f`, an operation holding a buffer and await its result.
+///
+/// The operation is subject to the in-flight operations limit. If it cannot
+/// be submitted, the error is returned along with the buffer.
+pub(crate) async fn submit_buf<T, B, R, F>(f: F) -> BufResult<R, B>
+where
+    T: Completable<Output = BufResult<R, B>> + Unpin + 'static,
+    F: FnOnce() -> Result<Op<T>, (io::Error, B)>,
+{
+    match limited(f).await {
+        Ok(op) => op.await,
+        Err((e, buf)) => (Err(e), buf),
+    }
+}
+
+/// Like [`submit_buf`], but the operation can be canceled with the returned
+/// token while the future is awaited.
+pub(crate) fn submit_cancelable<T, B, R, F>(
+    f: F,
+) -> (impl Future<Output = BufResult<R, B>>, CancelToken)
+where
+    T: Completable<Output = BufResult<R, B>> + Unpin + 'static,
+    F: FnOnce() -> Result<Op<T>, (io::Error, B)>,
+{
+    let token = CancelToken::new();
+    let t = token.clone();
+
+    let fut = async move {
+        let op = match limited(f).await {
+            Ok(op) => op,
+            Err((e, buf)) => return (Err(e), buf),
+        };
+
+        let _attached = t.attach(&op);
+        let (res, buf) = op.await;
+        (res.map_err(|e| t.map_err(e)), buf)
+    };
+
+    (fut, token)
+}
+
+/// Wait until another operation holding a buffer can be submitted.
+///
+/// The operation must be submitted, and passed to [`hold_in_flight`], without
+/// awaiting anything in between. Prefer [`limited`] where possible.
+pub(crate) async fn in_flight_room() {
+    if CONTEXT.with(|cx| cx.is_fallback() || cx.with_driver_mut(|driver| driver.ops.is_unlimited()))
+    {
+        return;
+    }
+
+    crate::future::poll_fn(|cx| {
+        CONTEXT.with(|runtime_context| {
+            runtime_context.with_driver_mut(|driver| driver.ops.poll_in_flight_room(cx))
+        })
+    })
+    .await
+}
+
+/// Attach `tag` to `op`, to be reported on completion to the callback set with
+/// [`Builder::on_tagged_completion`].
+///
+/// [`Builder::on_tagged_completion`]: crate::Builder::on_tagged_completion
+pub(crate) fn tag<T, CqeType>(op: &Op<T, CqeType>, tag: u64) {
+    CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.ops.tag(op.index, tag)));
+}
+
+/// Count `op` against the in-flight operations limit until it completes.
+pub(crate) fn hold_in_flight<T, CqeType>(op: &Op<T, CqeType>) {
+    CONTEXT.with(|cx| {
+        cx.with_driver_mut(|driver| {
+            if !driver.ops.is_unlimited() {
+                driver.ops.in_flight.insert(op.index);
+            }
+        })
+    });
+}
+
 impl<T> Future for Op<T, SingleCQE>
 where
     T: Unpin + 'static + Completable,
@@ -254,6 +525,65 @@ where
     }
 }
 
+impl<T> Op<T, MultiCQEStream>
+where
+    T: Unpin + 'static + Completable,
+{
+    /// Poll the next completion event of the operation, in the order they
+    /// were posted. Returns `None` once the final event, without the `more`
+    /// flag, was returned.
+    pub(crate) fn poll_next_cqe(&mut self, cx: &mut Context<'_>) -> Poll<Option<CqeResult>> {
+        use std::mem;
+
+        if self.index == usize::MAX {
+            return Poll::Ready(None);
+        }
+
+        CONTEXT.with(|runtime_context| {
+            runtime_context.with_driver_mut(|driver| {
+                let (lifecycle, completions) = driver
+                    .ops
+                    .get_mut(self.index)
+                    .expect("invalid internal state");
+
+                let (cqe, last) = match mem::replace(lifecycle, Lifecycle::Submitted) {
+                    Lifecycle::Submitted => {
+                        *lifecycle = Lifecycle::Waiting(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                    Lifecycle::Waiting(waker) if !waker.will_wake(cx.waker()) => {
+                        *lifecycle = Lifecycle::Waiting(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                    Lifecycle::Waiting(waker) => {
+                        *lifecycle = Lifecycle::Waiting(waker);
+                        return Poll::Pending;
+                    }
+                    Lifecycle::Ignored(..) => unreachable!(),
+                    Lifecycle::Completed(cqe) => (cqe, true),
+                    Lifecycle::CompletionList(indices) => {
+                        let mut list = indices.into_list(completions);
+                        let cqe = list.pop().expect("completion lists are never empty");
+                        let more = io_uring::cqueue::more(cqe.flags);
+                        if !list.is_empty() {
+                            *lifecycle = Lifecycle::CompletionList(list.into_indices());
+                        } else if more {
+                            *lifecycle = Lifecycle::Waiting(cx.waker().clone());
+                        }
+                        (cqe, !more)
+                    }
+                };
+
+                if last {
+                    driver.ops.remove(self.index);
+                    self.index = usize::MAX;
+                }
+                Poll::Ready(Some(cqe))
+            })
+        })
+    }
+}
+
 /// The operation may have pending cqe's not yet processed.
 /// To manage this, the lifecycle associated with the Op may if required
 /// be placed in LifeCycle::Ignored state to handle cqe's which arrive after
@@ -262,29 +592,48 @@ impl<T, CqeType> Drop for Op<T, CqeType> {
     fn drop(&mut self) {
         use std::mem;
 
+        if self.index == usize::MAX {
+            // The operation completed, or was never submitted
+            return;
+        }
+
         CONTEXT.with(|runtime_context| {
             runtime_context.with_driver_mut(|driver| {
                 // Get the Op Lifecycle state from the driver
-                let (lifecycle, completions) = match driver.ops.get_mut(self.index) {
+                let ops = &mut driver.ops;
+                let lifecycle = match ops.lifecycle.get_mut(self.index) {
                     Some(val) => val,
                     None => {
                         // Op dropped after the driver
                         return;
                     }
                 };
+                let completions = &mut ops.completions;
 
+                // Completions not taken by the Op are passed to the discard
+                // callback, if any.
+                let discard = ops.discards.get_mut(&self.index);
                 match mem::replace(lifecycle, Lifecycle::Submitted) {
                     Lifecycle::Submitted | Lifecycle::Waiting(_) => {
                         *lifecycle = Lifecycle::Ignored(Box::new(self.data.take()));
                     }
-                    Lifecycle::Completed(..) => {
+                    Lifecycle::Completed(cqe) => {
+                        if let Some(discard) = discard {
+                            discard(&cqe);
+                        }
                         driver.ops.remove(self.index);
                     }
                     Lifecycle::CompletionList(indices) => {
                         // Deallocate list entries, recording if more CQE's are expected
                         let more = {
                             let mut list = indices.into_list(completions);
-                            io_uring::cqueue::more(list.peek_end().unwrap().flags)
+                            let more = io_uring::cqueue::more(list.peek_end().unwrap().flags);
+                            if let Some(discard) = discard {
+                                for cqe in list {
+                                    discard(&cqe);
+                                }
+                            }
+                            more
                             // Dropping list deallocates the list entries
                         };
                         if more {
@@ -381,6 +730,18 @@ mod test {
         }
     }
 
+    #[test]
+    fn chain_reports_first_error() {
+        crate::start(async {
+            let (close, nop) = link(2, || (Op::close(-1), Op::no_op())).unwrap();
+            let (closed, nop) = futures_util::future::join(close.unwrap(), nop.unwrap()).await;
+
+            // The no-op is canceled by the failed close.
+            assert_eq!(closed.unwrap_err().raw_os_error(), Some(libc::EBADF));
+            assert_eq!(nop.unwrap_err().raw_os_error(), Some(libc::EBADF));
+        });
+    }
+
     #[test]
     fn op_stays_in_slab_on_drop() {
         let (op, data) = init();
@@ -1,4 +1,4 @@
-use crate::driver::{self, Op, SharedFd};
+use crate::driver::{self, FixedSlot, Op, SharedFd};
 use crate::fs::{File, OpenOptions};
 
 use crate::driver::op::{self, Completable};
@@ -18,10 +18,7 @@ impl Op<Open> {
     pub(crate) fn open(path: &Path, options: &OpenOptions) -> io::Result<Op<Open>> {
         use io_uring::{opcode, types};
         let path = driver::util::cstr(path)?;
-        let flags = libc::O_CLOEXEC
-            | options.access_mode()?
-            | options.creation_mode()?
-            | (options.custom_flags & !libc::O_ACCMODE);
+        let flags = libc::O_CLOEXEC | open_flags(options)?;
 
         Op::submit_with(Open { path, flags }, |open| {
             // Get a reference to the memory. The string will be held by the
@@ -37,6 +34,51 @@ impl Op<Open> {
     }
 }
 
+/// Open a file as a direct descriptor
+#[allow(dead_code)]
+pub(crate) struct OpenDirect {
+    pub(crate) path: CString,
+}
+
+impl Op<OpenDirect> {
+    /// Submit a request to open a file into `slot` of the fixed file table.
+    pub(crate) fn open_direct(
+        path: &Path,
+        options: &OpenOptions,
+        slot: &FixedSlot,
+    ) -> io::Result<Op<OpenDirect>> {
+        use io_uring::{opcode, types};
+        let path = driver::util::cstr(path)?;
+        // Direct descriptors are not part of the process file table, so
+        // `O_CLOEXEC` is meaningless and rejected.
+        let flags = open_flags(options)?;
+
+        Op::submit_with(OpenDirect { path }, |open| {
+            let p_ref = open.path.as_c_str().as_ptr();
+
+            opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), p_ref)
+                .flags(flags)
+                .mode(options.mode)
+                .file_index(Some(slot.destination()))
+                .build()
+        })
+    }
+}
+
+pub(crate) fn open_flags(options: &OpenOptions) -> io::Result<libc::c_int> {
+    Ok(options.access_mode()?
+        | options.creation_mode()?
+        | (options.custom_flags & !libc::O_ACCMODE))
+}
+
+impl Completable for OpenDirect {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(drop)
+    }
+}
+
 impl Completable for Open {
     type Output = io::Result<File>;
 
@@ -0,0 +1,189 @@
+use crate::driver::op::{self, Completable, MultiCQEStream};
+use crate::driver::Op;
+use crate::runtime::CONTEXT;
+
+use futures_util::future::poll_fn;
+use futures_util::Stream;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub(crate) struct PollAdd;
+
+impl Op<PollAdd, MultiCQEStream> {
+    /// Arm a multishot poll of `fd` for `events`, posting a completion each
+    /// time the file becomes ready.
+    pub(crate) fn poll_add(fd: RawFd, events: u32) -> io::Result<Self> {
+        use io_uring::{opcode, types};
+
+        Op::submit_with(PollAdd, |_| {
+            opcode::PollAdd::new(types::Fd(fd), events)
+                .multi(true)
+                .build()
+        })
+    }
+}
+
+impl Completable for PollAdd {
+    type Output = ();
+
+    fn complete(self, _cqe: op::CqeResult) -> Self::Output {}
+}
+
+pub(crate) struct PollRemove;
+
+impl Op<PollRemove> {
+    /// Remove the poll armed by the operation at `index`.
+    pub(crate) fn poll_remove(index: usize) -> io::Result<Op<PollRemove>> {
+        use io_uring::opcode;
+
+        Op::submit_with(PollRemove, |_| {
+            opcode::PollRemove::new(index as u64).build()
+        })
+    }
+}
+
+impl Completable for PollRemove {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(drop)
+    }
+}
+
+/// Stream of the readiness events of a file, returned by [`poll_fd`].
+///
+/// Each item is the mask of the events the file is ready for, as in the
+/// `revents` field of `poll(2)`. The poll stays armed in the kernel until
+/// [`remove`] is awaited or the stream is dropped.
+///
+/// [`poll_fd`]: crate::poll_fd
+/// [`remove`]: PollFd::remove
+pub struct PollFd {
+    fd: RawFd,
+    events: u32,
+
+    /// The armed poll, if any
+    op: Option<Op<PollAdd, MultiCQEStream>>,
+
+    /// Whether the stream ended
+    done: bool,
+}
+
+impl PollFd {
+    pub(crate) fn new(fd: RawFd, events: u32) -> io::Result<PollFd> {
+        Ok(PollFd {
+            fd,
+            events,
+            op: Some(Op::poll_add(fd, events)?),
+            done: false,
+        })
+    }
+
+    /// Removes the poll from the kernel, with `IORING_OP_POLL_REMOVE`.
+    ///
+    /// This waits for both the removal and the poll to complete, discarding
+    /// the events posted in the meantime. Once it returns, the kernel posts
+    /// no more completions for the poll, and the file may be closed.
+    ///
+    /// Dropping the stream also removes the poll, but without waiting for it.
+    ///
+    /// # Errors
+    ///
+    /// Errors are those of the removal. The poll may then still be armed,
+    /// until the stream is dropped.
+    pub async fn remove(mut self) -> io::Result<()> {
+        let index = match self.op.as_ref().and_then(|op| op.index()) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+
+        match Op::poll_remove(index)?.await {
+            Ok(()) => {}
+            // The poll already completed, its last completion is on its way.
+            Err(e) if e.raw_os_error() == Some(libc::ENOENT) => {}
+            Err(e) => return Err(e),
+        }
+
+        // Reap the completions of the poll up to its last one, so that none
+        // is posted after the removal.
+        let op = self.op.as_mut().unwrap();
+        poll_fn(|cx| loop {
+            match op.poll_next_cqe(cx) {
+                Poll::Ready(Some(_)) => continue,
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        })
+        .await;
+
+        self.op = None;
+        Ok(())
+    }
+}
+
+impl Stream for PollFd {
+    type Item = io::Result<u32>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let me = self.get_mut();
+
+        loop {
+            if me.done {
+                return Poll::Ready(None);
+            }
+
+            let op = match &mut me.op {
+                Some(op) => op,
+                None => match Op::poll_add(me.fd, me.events) {
+                    Ok(op) => me.op.insert(op),
+                    Err(e) => {
+                        me.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                },
+            };
+
+            let cqe = match op.poll_next_cqe(cx) {
+                Poll::Ready(Some(cqe)) => cqe,
+                Poll::Ready(None) => {
+                    me.op = None;
+                    continue;
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+
+            // Without the `more` flag, the poll is no longer armed, and is
+            // re-armed on the next call.
+            if !io_uring::cqueue::more(cqe.flags) {
+                me.op = None;
+            }
+
+            return match cqe.result {
+                Ok(events) => Poll::Ready(Some(Ok(events))),
+                Err(e) => {
+                    me.done = true;
+                    Poll::Ready(Some(Err(e)))
+                }
+            };
+        }
+    }
+}
+
+impl Drop for PollFd {
+    fn drop(&mut self) {
+        // The poll stays armed until removed. The operation is dropped first,
+        // which forgets it if its final completion was already posted.
+        let index = self.op.take().and_then(|op| op.index());
+        if let Some(index) = index {
+            CONTEXT.with(|cx| {
+                if cx.is_set() {
+                    cx.with_driver_mut(|driver| {
+                        let _ = driver.remove_poll(index);
+                    })
+                }
+            });
+        }
+    }
+}
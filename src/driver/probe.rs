@@ -0,0 +1,49 @@
+use io_uring::IoUring;
+use std::fmt;
+use std::io;
+
+/// The set of operations supported by the running kernel.
+///
+/// Obtained through [`tokio_uring::probe`]. See its documentation for more
+/// details.
+///
+/// [`tokio_uring::probe`]: crate::probe
+#[derive(Clone, Copy)]
+pub struct Probe {
+    // One bit per opcode
+    supported: [u64; 4],
+}
+
+impl Probe {
+    /// Query the kernel for the operations it supports.
+    pub(crate) fn register(uring: &IoUring) -> io::Result<Probe> {
+        let mut probe = io_uring::Probe::new();
+        uring.submitter().register_probe(&mut probe)?;
+
+        let mut supported = [0; 4];
+        for opcode in 0..=u8::MAX {
+            if probe.is_supported(opcode) {
+                supported[opcode as usize / 64] |= 1 << (opcode % 64);
+            }
+        }
+
+        Ok(Probe { supported })
+    }
+
+    /// Returns `true` if the kernel supports the operation identified by
+    /// `opcode`.
+    ///
+    /// Opcodes are most easily obtained through the `CODE` constant of the
+    /// operation types in the [`io_uring::opcode`] module.
+    pub fn is_supported(&self, opcode: u8) -> bool {
+        self.supported[opcode as usize / 64] & (1 << (opcode % 64)) != 0
+    }
+}
+
+impl fmt::Debug for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries((0..=u8::MAX).filter(|op| self.is_supported(*op)))
+            .finish()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::driver::{Driver, Op};
+
+use std::io;
+
+use crate::driver::op::{self, Completable};
+use io_uring::opcode;
+
+/// Bit set in the `user_data` of the removal of a dropped group, along with
+/// the identifier of the group, which operation indices never have.
+pub(crate) const GROUP_REMOVED_BIT: u64 = 1 << 62;
+
+/// Provide buffers to a group, for operations selecting their buffer
+pub(crate) struct ProvideBuffers;
+
+impl Op<ProvideBuffers> {
+    /// Submit a request to provide the `nbufs` buffers of `len` bytes each at
+    /// `addr` to the group `bgid`, numbered from `bid`.
+    ///
+    /// The memory is not held by the operation, it must outlive the group.
+    pub(crate) fn provide_buffers(
+        addr: *mut u8,
+        len: usize,
+        nbufs: u16,
+        bgid: u16,
+        bid: u16,
+    ) -> io::Result<Op<ProvideBuffers>> {
+        Op::submit_with(ProvideBuffers, |_| {
+            opcode::ProvideBuffers::new(addr, len as _, nbufs, bgid, bid).build()
+        })
+    }
+}
+
+impl Completable for ProvideBuffers {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
+
+impl Driver {
+    /// Like [`Op::provide_buffers`], without waiting for the buffers to be
+    /// provided.
+    pub(crate) fn provide_buffers_untracked(
+        &mut self,
+        addr: *mut u8,
+        len: usize,
+        nbufs: u16,
+        bgid: u16,
+        bid: u16,
+    ) -> io::Result<()> {
+        self.push_untracked(opcode::ProvideBuffers::new(addr, len as _, nbufs, bgid, bid).build())
+    }
+
+    /// Remove the `nbufs` buffers of the dropped group `bgid`, whose
+    /// identifier is freed once they are removed.
+    ///
+    /// If the removal can't be submitted, the identifier is never freed, as
+    /// the group is left behind.
+    pub(crate) fn remove_buf_group(&mut self, nbufs: u16, bgid: u16) -> io::Result<()> {
+        let sqe = opcode::RemoveBuffers::new(nbufs, bgid)
+            .build()
+            .user_data(GROUP_REMOVED_BIT | bgid as u64);
+        while unsafe { self.uring()?.submission().push(&sqe).is_err() } {
+            self.submit()?;
+        }
+        Ok(())
+    }
+}
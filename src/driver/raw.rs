@@ -0,0 +1,32 @@
+use crate::driver::op::{self, Completable};
+use crate::driver::Op;
+use crate::BufResult;
+
+use io_uring::squeue;
+
+/// An operation built by the user, holding the resources its SQE refers to.
+pub(crate) struct Raw<T> {
+    data: T,
+}
+
+impl<T> Op<Raw<T>> {
+    /// Submit the SQE built by `f`.
+    ///
+    /// # Safety
+    ///
+    /// See [`crate::submit_raw`].
+    pub(crate) unsafe fn raw<F>(data: T, f: F) -> Result<Op<Raw<T>>, (std::io::Error, T)>
+    where
+        F: FnOnce(&mut T) -> squeue::Entry,
+    {
+        Op::try_submit_with(Raw { data }, |raw| f(&mut raw.data)).map_err(|(e, raw)| (e, raw.data))
+    }
+}
+
+impl<T> Completable for Raw<T> {
+    type Output = BufResult<i32, T>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        (cqe.result.map(|v| v as i32), self.data)
+    }
+}
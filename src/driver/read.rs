@@ -1,5 +1,5 @@
 use crate::buf::IoBufMut;
-use crate::driver::{Op, SharedFd};
+use crate::driver::{FixedSlot, Op, SharedFd};
 use crate::BufResult;
 
 use crate::driver::op::{self, Completable};
@@ -8,20 +8,25 @@ use std::io;
 pub(crate) struct Read<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
+    /// Not set when reading from a direct descriptor.
     #[allow(dead_code)]
-    fd: SharedFd,
+    fd: Option<SharedFd>,
 
     /// Reference to the in-flight buffer.
     pub(crate) buf: T,
 }
 
 impl<T: IoBufMut> Op<Read<T>> {
-    pub(crate) fn read_at(fd: &SharedFd, buf: T, offset: u64) -> io::Result<Op<Read<T>>> {
+    pub(crate) fn read_at(
+        fd: &SharedFd,
+        buf: T,
+        offset: u64,
+    ) -> Result<Op<Read<T>>, (io::Error, T)> {
         use io_uring::{opcode, types};
 
-        Op::submit_with(
+        Op::try_submit_with(
             Read {
-                fd: fd.clone(),
+                fd: Some(fd.clone()),
                 buf,
             },
             |read| {
@@ -33,6 +38,30 @@ impl<T: IoBufMut> Op<Read<T>> {
                     .build()
             },
         )
+        .map_err(|(e, op)| (e, op.buf))
+    }
+}
+
+impl<T: IoBufMut> Op<Read<T>> {
+    /// Submit a request to read from the direct descriptor in `slot`.
+    ///
+    /// The slot is not held by the operation, it must be kept in use until
+    /// the read completes, e.g. by a linked close.
+    pub(crate) fn read_direct(
+        slot: &FixedSlot,
+        buf: T,
+        offset: u64,
+    ) -> Result<Op<Read<T>>, (io::Error, T)> {
+        use io_uring::{opcode, types};
+
+        Op::try_submit_with(Read { fd: None, buf }, |read| {
+            let ptr = read.buf.stable_mut_ptr();
+            let len = read.buf.bytes_total();
+            opcode::Read::new(types::Fixed(slot.index()), ptr, len as _)
+                .offset(offset as _)
+                .build()
+        })
+        .map_err(|(e, op)| (e, op.buf))
     }
 }
 
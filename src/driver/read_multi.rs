@@ -0,0 +1,190 @@
+use crate::buf::{ProvidedBuf, ProvidedBufPool};
+use crate::driver::op::{self, Completable, MultiCQEStream};
+use crate::driver::util::RawSqe;
+use crate::driver::{Op, SharedFd};
+use crate::runtime::CONTEXT;
+
+use futures_util::Stream;
+use io_uring::squeue;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// `IORING_OP_READ_MULTISHOT`, added in Linux 6.7 and not known to the
+/// `io-uring` crate.
+pub(crate) const IORING_OP_READ_MULTISHOT: u8 = 49;
+
+pub(crate) struct ReadMulti {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    /// Holds the pool, whose buffers the kernel writes to while the
+    /// operation is in-flight.
+    #[allow(dead_code)]
+    pool: ProvidedBufPool,
+}
+
+impl Op<ReadMulti, MultiCQEStream> {
+    /// Submit a multishot read from `fd` into the buffers of `pool`, posting a
+    /// completion for each buffer filled.
+    pub(crate) fn read_multi(fd: &SharedFd, pool: &ProvidedBufPool) -> io::Result<Self> {
+        Op::submit_with(
+            ReadMulti {
+                fd: fd.clone(),
+                pool: pool.clone(),
+            },
+            |read| {
+                RawSqe {
+                    opcode: IORING_OP_READ_MULTISHOT,
+                    flags: squeue::Flags::BUFFER_SELECT.bits(),
+                    fd: read.fd.raw_fd(),
+                    buf_index: read.pool.group(),
+                    ..Default::default()
+                }
+                .build()
+            },
+        )
+    }
+}
+
+impl Completable for ReadMulti {
+    type Output = ();
+
+    fn complete(self, _cqe: op::CqeResult) -> Self::Output {}
+}
+
+/// Stream of the buffers filled by multishot reads, re-armed as long as the
+/// file is not at its end.
+pub(crate) struct ReadMultiStream {
+    fd: SharedFd,
+    pool: ProvidedBufPool,
+
+    /// The armed read, if any
+    op: Option<Op<ReadMulti, MultiCQEStream>>,
+
+    /// Whether the kernel ran out of buffers, until some are returned
+    starved: bool,
+
+    /// Whether buffers were provided again since the read was armed
+    replenished: bool,
+
+    /// Whether the stream ended
+    done: bool,
+}
+
+impl ReadMultiStream {
+    pub(crate) fn new(fd: &SharedFd, pool: &ProvidedBufPool) -> ReadMultiStream {
+        ReadMultiStream {
+            fd: fd.clone(),
+            pool: pool.clone(),
+            op: None,
+            starved: false,
+            replenished: false,
+            done: false,
+        }
+    }
+
+    fn fail(&mut self, e: io::Error) -> Poll<Option<io::Result<ProvidedBuf>>> {
+        self.done = true;
+        Poll::Ready(Some(Err(e)))
+    }
+}
+
+impl Stream for ReadMultiStream {
+    type Item = io::Result<ProvidedBuf>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let me = self.get_mut();
+
+        loop {
+            if me.done {
+                return Poll::Ready(None);
+            }
+
+            match me.pool.provide_returned() {
+                Ok(true) => {
+                    me.starved = false;
+                    me.replenished = true;
+                }
+                Ok(false) => {}
+                Err(e) => return me.fail(e),
+            }
+
+            let op = match &mut me.op {
+                Some(op) => op,
+                None if me.starved => {
+                    // Re-arming now would fail again, wait for a buffer to be
+                    // returned.
+                    me.pool.wait_returned(cx.waker());
+                    return Poll::Pending;
+                }
+                None => match Op::read_multi(&me.fd, &me.pool) {
+                    Ok(op) => {
+                        me.replenished = false;
+                        me.op.insert(op)
+                    }
+                    Err(e) => return me.fail(e),
+                },
+            };
+
+            let cqe = match op.poll_next_cqe(cx) {
+                Poll::Ready(Some(cqe)) => cqe,
+                Poll::Ready(None) => {
+                    me.op = None;
+                    continue;
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+
+            // Without the `more` flag, the read is no longer armed.
+            if !io_uring::cqueue::more(cqe.flags) {
+                me.op = None;
+            }
+
+            match cqe.result {
+                Ok(0) => {
+                    // End of file
+                    me.done = true;
+                }
+                Ok(n) => {
+                    let bid = io_uring::cqueue::buffer_select(cqe.flags)
+                        .expect("data read without a selected buffer");
+                    return Poll::Ready(Some(Ok(me.pool.take(bid, n as usize))));
+                }
+                Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                    // Buffers provided after the kernel ran out are not
+                    // returned again, so the read is re-armed right away.
+                    me.starved = !me.replenished;
+                }
+                Err(e) => return me.fail(e),
+            }
+        }
+    }
+}
+
+impl Drop for ReadMultiStream {
+    fn drop(&mut self) {
+        // The read stays armed until canceled. The buffers selected for the
+        // reads no longer awaited are returned to the pool.
+        if let Some(index) = self.op.as_ref().and_then(|op| op.index()) {
+            CONTEXT.with(|cx| {
+                if cx.is_set() {
+                    cx.with_driver_mut(|driver| {
+                        let pool = self.pool.clone();
+                        driver.on_discard(
+                            index,
+                            Box::new(move |cqe| {
+                                if let Some(bid) = io_uring::cqueue::buffer_select(cqe.flags) {
+                                    drop(pool.take(bid, 0));
+                                }
+                            }),
+                        );
+                        let _ = driver.cancel(index);
+                    })
+                }
+            });
+        }
+    }
+}
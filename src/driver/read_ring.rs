@@ -0,0 +1,68 @@
+use crate::buf::{BufRing, RingBuf};
+use crate::driver::op::{self, Completable};
+use crate::driver::{Op, SharedFd};
+
+use io_uring::squeue;
+use std::io;
+
+pub(crate) struct ReadRing {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    /// Holds the ring, whose buffers the kernel writes to while the
+    /// operation is in-flight.
+    ring: BufRing,
+}
+
+impl Op<ReadRing> {
+    /// Submit a read at `offset` from `fd` into a buffer selected by the
+    /// kernel from `ring`.
+    pub(crate) fn read_ring(
+        fd: &SharedFd,
+        ring: &BufRing,
+        offset: u64,
+    ) -> io::Result<Op<ReadRing>> {
+        use io_uring::{opcode, types};
+
+        ring.start_read();
+        let read = ReadRing {
+            fd: fd.clone(),
+            ring: ring.clone(),
+        };
+
+        Op::submit_with(read, |read| {
+            opcode::Read::new(
+                types::Fd(read.fd.raw_fd()),
+                std::ptr::null_mut(),
+                read.ring.buf_size() as _,
+            )
+            .offset(offset as _)
+            .buf_group(read.ring.group())
+            .build()
+            .flags(squeue::Flags::BUFFER_SELECT)
+        })
+    }
+}
+
+impl Completable for ReadRing {
+    type Output = io::Result<Option<RingBuf>>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        let n = cqe.result?;
+
+        // The kernel may select a buffer even at the end of the file, which
+        // is then returned to the ring right away.
+        let buf =
+            io_uring::cqueue::buffer_select(cqe.flags).map(|bid| self.ring.take(bid, n as usize));
+        Ok(buf.filter(|buf| !buf.is_empty()))
+    }
+}
+
+impl Drop for ReadRing {
+    fn drop(&mut self) {
+        // Dropped once the read completed, even if its result was discarded.
+        self.ring.end_read();
+    }
+}
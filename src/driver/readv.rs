@@ -23,7 +23,7 @@ impl<T: IoBufMut> Op<Readv<T>> {
         fd: &SharedFd,
         mut bufs: Vec<T>,
         offset: u64,
-    ) -> io::Result<Op<Readv<T>>> {
+    ) -> Result<Op<Readv<T>>, (io::Error, Vec<T>)> {
         use io_uring::{opcode, types};
 
         // Build `iovec` objects referring the provided `bufs` for `io_uring::opcode::Readv`.
@@ -36,7 +36,7 @@ impl<T: IoBufMut> Op<Readv<T>> {
             })
             .collect();
 
-        Op::submit_with(
+        Op::try_submit_with(
             Readv {
                 fd: fd.clone(),
                 bufs,
@@ -52,6 +52,7 @@ impl<T: IoBufMut> Op<Readv<T>> {
                 .build()
             },
         )
+        .map_err(|(e, op)| (e, op.bufs))
     }
 }
 
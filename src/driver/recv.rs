@@ -0,0 +1,62 @@
+use crate::buf::IoBufMut;
+use crate::driver::{Op, SharedFd};
+use crate::BufResult;
+
+use crate::driver::op::{self, Completable};
+use std::io;
+
+pub(crate) struct Recv<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    /// Reference to the in-flight buffer.
+    pub(crate) buf: T,
+}
+
+impl<T: IoBufMut> Op<Recv<T>> {
+    pub(crate) fn recv(fd: &SharedFd, buf: T, flags: i32) -> Result<Op<Recv<T>>, (io::Error, T)> {
+        use io_uring::{opcode, types};
+
+        Op::try_submit_with(
+            Recv {
+                fd: fd.clone(),
+                buf,
+            },
+            |recv| {
+                // Get raw buffer info
+                let ptr = recv.buf.stable_mut_ptr();
+                let len = recv.buf.bytes_total();
+                opcode::Recv::new(types::Fd(fd.raw_fd()), ptr, len as _)
+                    .flags(flags)
+                    .build()
+            },
+        )
+        .map_err(|(e, op)| (e, op.buf))
+    }
+}
+
+impl<T> Completable for Recv<T>
+where
+    T: IoBufMut,
+{
+    type Output = BufResult<usize, T>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        // Convert the operation result to `usize`
+        let res = cqe.result.map(|v| v as usize);
+        // Recover the buffer
+        let mut buf = self.buf;
+
+        // If the operation was successful, advance the initialized cursor.
+        if let Ok(n) = res {
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            unsafe {
+                buf.set_init(n);
+            }
+        }
+
+        (res, buf)
+    }
+}
@@ -20,14 +20,17 @@ pub(crate) struct RecvFrom<T> {
 }
 
 impl<T: IoBufMut> Op<RecvFrom<T>> {
-    pub(crate) fn recv_from(fd: &SharedFd, mut buf: T) -> io::Result<Op<RecvFrom<T>>> {
+    pub(crate) fn recv_from(fd: &SharedFd, mut buf: T) -> Result<Op<RecvFrom<T>>, (io::Error, T)> {
         use io_uring::{opcode, types};
 
         let mut io_slices = vec![IoSliceMut::new(unsafe {
             std::slice::from_raw_parts_mut(buf.stable_mut_ptr(), buf.bytes_total())
         })];
 
-        let socket_addr = Box::new(unsafe { SockAddr::init(|_, _| Ok(()))?.1 });
+        let socket_addr = match unsafe { SockAddr::init(|_, _| Ok(())) } {
+            Ok((_, socket_addr)) => Box::new(socket_addr),
+            Err(e) => return Err((e, buf)),
+        };
 
         let mut msghdr: Box<libc::msghdr> = Box::new(unsafe { std::mem::zeroed() });
         msghdr.msg_iov = io_slices.as_mut_ptr().cast();
@@ -35,7 +38,7 @@ impl<T: IoBufMut> Op<RecvFrom<T>> {
         msghdr.msg_name = socket_addr.as_ptr() as *mut libc::c_void;
         msghdr.msg_namelen = socket_addr.len();
 
-        Op::submit_with(
+        Op::try_submit_with(
             RecvFrom {
                 fd: fd.clone(),
                 buf,
@@ -51,6 +54,7 @@ impl<T: IoBufMut> Op<RecvFrom<T>> {
                 .build()
             },
         )
+        .map_err(|(e, op)| (e, op.buf))
     }
 }
 
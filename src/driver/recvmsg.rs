@@ -0,0 +1,173 @@
+use crate::buf::IoBufMut;
+use crate::driver::op::{self, Completable};
+use crate::driver::{Op, SharedFd};
+use crate::BufResult;
+use libc::iovec;
+use std::os::unix::io::RawFd;
+use std::{boxed::Box, io, mem};
+
+pub(crate) struct RecvMsg<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    /// Reference to the in-flight buffers.
+    pub(crate) bufs: Vec<T>,
+
+    /// Referred to by `msghdr`, referring `bufs`.
+    #[allow(dead_code)]
+    iovs: Vec<iovec>,
+
+    /// Room for received control messages, if file descriptors are accepted.
+    /// Held as words to keep it suitably aligned. Emptied once the received
+    /// file descriptors are handed out.
+    control: Vec<u64>,
+
+    /// Parameter for `io_uring::opcode::RecvMsg`. Boxed, so it stays put
+    /// until the operation completes, as the kernel writes back to it.
+    msghdr: Box<libc::msghdr>,
+}
+
+impl<T: IoBufMut> Op<RecvMsg<T>> {
+    /// Receives into `bufs`, accepting up to `max_fds` file descriptors passed
+    /// with `SCM_RIGHTS`.
+    pub(crate) fn recvmsg(
+        fd: &SharedFd,
+        mut bufs: Vec<T>,
+        max_fds: usize,
+    ) -> Result<Op<RecvMsg<T>>, (io::Error, Vec<T>)> {
+        use io_uring::{opcode, types};
+
+        let mut iovs: Vec<iovec> = bufs
+            .iter_mut()
+            .map(|b| iovec {
+                // Safety guaranteed by `IoBufMut`.
+                iov_base: unsafe { b.stable_mut_ptr().add(b.bytes_init()) as *mut libc::c_void },
+                iov_len: b.bytes_total() - b.bytes_init(),
+            })
+            .collect();
+
+        let mut msghdr: Box<libc::msghdr> = Box::new(unsafe { mem::zeroed() });
+        msghdr.msg_iov = iovs.as_mut_ptr();
+        msghdr.msg_iovlen = iovs.len() as _;
+
+        let mut control = vec![];
+        if max_fds > 0 {
+            let data_len = (max_fds * mem::size_of::<RawFd>()) as u32;
+            let space = unsafe { libc::CMSG_SPACE(data_len) } as usize;
+            control = vec![0u64; space.div_ceil(8)];
+            msghdr.msg_control = control.as_mut_ptr().cast();
+            msghdr.msg_controllen = space as _;
+        }
+
+        Op::try_submit_with(
+            RecvMsg {
+                fd: fd.clone(),
+                bufs,
+                iovs,
+                control,
+                msghdr,
+            },
+            |recv| {
+                opcode::RecvMsg::new(types::Fd(recv.fd.raw_fd()), recv.msghdr.as_mut() as *mut _)
+                    .flags(libc::MSG_CMSG_CLOEXEC as u32)
+                    .build()
+            },
+        )
+        .map_err(|(e, mut op)| (e, mem::take(&mut op.bufs)))
+    }
+}
+
+impl<T> RecvMsg<T> {
+    /// Collects the file descriptors passed in the received control messages.
+    fn received_fds(&self) -> Vec<RawFd> {
+        let mut fds = vec![];
+        if self.control.is_empty() {
+            return fds;
+        }
+
+        // Safety: the kernel wrote `msg_controllen` bytes of well-formed
+        // control messages to the control buffer.
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(self.msghdr.as_ref());
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                    let data = libc::CMSG_DATA(cmsg);
+                    let len = (*cmsg).cmsg_len as usize - (data as usize - cmsg as usize);
+                    let n = len / mem::size_of::<RawFd>();
+                    for i in 0..n {
+                        fds.push(std::ptr::read_unaligned(data.cast::<RawFd>().add(i)));
+                    }
+                }
+                cmsg = libc::CMSG_NXTHDR(self.msghdr.as_ref(), cmsg);
+            }
+        }
+
+        fds
+    }
+}
+
+impl<T> Completable for RecvMsg<T>
+where
+    T: IoBufMut,
+{
+    type Output = BufResult<(usize, Vec<RawFd>), Vec<T>>;
+
+    fn complete(mut self, cqe: op::CqeResult) -> Self::Output {
+        // Convert the operation result to `usize`
+        let res = cqe.result.map(|v| v as usize);
+        let received = *res.as_ref().unwrap_or(&0);
+
+        let res = res.and_then(|n| {
+            let fds = self.received_fds();
+
+            // Some control messages were dropped for lack of room. Rather than
+            // hand out a partial set of file descriptors, close them all.
+            if self.msghdr.msg_flags & libc::MSG_CTRUNC != 0 {
+                for fd in fds {
+                    unsafe { libc::close(fd) };
+                }
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "control message truncated, received file descriptors were closed",
+                ));
+            }
+
+            Ok((n, fds))
+        });
+        self.control = vec![];
+
+        // Recover the buffers
+        let mut bufs = mem::take(&mut self.bufs);
+
+        // Advance the initialized cursors by the bytes received, which are
+        // not lost even if the control messages were truncated.
+        let mut count = received;
+        if count > 0 {
+            for b in bufs.iter_mut() {
+                let sz = std::cmp::min(count, b.bytes_total() - b.bytes_init());
+                let pos = b.bytes_init() + sz;
+                // Safety: the kernel returns bytes received, and we have ensured that `pos` is
+                // valid for current buffer.
+                unsafe { b.set_init(pos) };
+                count -= sz;
+                if count == 0 {
+                    break;
+                }
+            }
+        }
+
+        (res, bufs)
+    }
+}
+
+impl<T> Drop for RecvMsg<T> {
+    fn drop(&mut self) {
+        // The file descriptors received for a message whose result was
+        // discarded are closed, rather than leaked.
+        for fd in self.received_fds() {
+            unsafe { libc::close(fd) };
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::driver::Driver;
+use crate::runtime::CONTEXT;
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+/// Releases of kernel resources queued while the driver was in use.
+pub(crate) type Releases = RefCell<Vec<Box<dyn FnOnce(&mut Driver)>>>;
+
+/// Runs the release of a kernel resource, such as a registration, on the
+/// driver it belongs to.
+///
+/// Resources may be dropped while the driver is in use, e.g. by an operation
+/// completing during `tick`. The release is then queued, and run by the driver
+/// once done.
+#[derive(Clone)]
+pub(crate) struct Releaser(Weak<Releases>);
+
+impl Releaser {
+    pub(super) fn new(releases: &Rc<Releases>) -> Releaser {
+        Releaser(Rc::downgrade(releases))
+    }
+
+    /// Run `f` on the driver, now if it is not in use, or else once it is
+    /// done. Nothing is run once the driver is gone, as the resources went
+    /// with the ring.
+    pub(crate) fn release<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Driver) + 'static,
+    {
+        let releases = match self.0.upgrade() {
+            Some(releases) => releases,
+            None => return,
+        };
+
+        match CONTEXT.try_with(|cx| cx.is_set()) {
+            Ok(true) => CONTEXT.with(|cx| cx.with_driver_mut(f)),
+            _ => releases.borrow_mut().push(Box::new(f)),
+        }
+    }
+}
+
+impl Driver {
+    /// Returns a handle through which resources are released on this driver.
+    pub(crate) fn releaser(&self) -> Releaser {
+        Releaser::new(&self.releases)
+    }
+
+    /// Run the releases queued while the driver was in use.
+    pub(crate) fn run_releases(&mut self) {
+        loop {
+            // The borrow ends before the release runs, which may queue more.
+            let release = self.releases.borrow_mut().pop();
+            match release {
+                Some(release) => release(self),
+                None => break,
+            }
+        }
+    }
+}
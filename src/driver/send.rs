@@ -0,0 +1,55 @@
+use crate::buf::IoBuf;
+use crate::driver::{Op, SharedFd};
+use crate::BufResult;
+
+use crate::driver::op::{self, Completable};
+use std::io;
+
+pub(crate) struct Send<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    /// Reference to the in-flight buffer.
+    pub(crate) buf: T,
+}
+
+impl<T: IoBuf> Op<Send<T>> {
+    /// Submit a request to send `buf` on the connected socket `fd`.
+    pub(crate) fn send(fd: &SharedFd, buf: T, flags: i32) -> Result<Op<Send<T>>, (io::Error, T)> {
+        use io_uring::{opcode, types};
+
+        Op::try_submit_with(
+            Send {
+                fd: fd.clone(),
+                buf,
+            },
+            |send| {
+                // Get raw buffer info
+                let ptr = send.buf.stable_ptr();
+                let len = send.buf.bytes_init();
+                opcode::Send::new(types::Fd(fd.raw_fd()), ptr, len as _)
+                    .flags(flags)
+                    .build()
+            },
+        )
+        .map_err(|(e, op)| (e, op.buf))
+    }
+}
+
+impl<T> Completable for Send<T>
+where
+    T: IoBuf,
+{
+    type Output = BufResult<usize, T>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        // Convert the operation result to `usize`
+        let res = cqe.result.map(|v| v as usize);
+        // Recover the buffer
+        let buf = self.buf;
+
+        (res, buf)
+    }
+}
@@ -22,7 +22,7 @@ impl<T: IoBuf> Op<SendTo<T>> {
         fd: &SharedFd,
         buf: T,
         socket_addr: SocketAddr,
-    ) -> io::Result<Op<SendTo<T>>> {
+    ) -> Result<Op<SendTo<T>>, (io::Error, T)> {
         use io_uring::{opcode, types};
 
         let io_slices = vec![IoSlice::new(unsafe {
@@ -37,7 +37,7 @@ impl<T: IoBuf> Op<SendTo<T>> {
         msghdr.msg_name = socket_addr.as_ptr() as *mut libc::c_void;
         msghdr.msg_namelen = socket_addr.len();
 
-        Op::submit_with(
+        Op::try_submit_with(
             SendTo {
                 fd: fd.clone(),
                 buf,
@@ -53,6 +53,7 @@ impl<T: IoBuf> Op<SendTo<T>> {
                 .build()
             },
         )
+        .map_err(|(e, op)| (e, op.buf))
     }
 }
 
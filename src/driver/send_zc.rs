@@ -19,10 +19,10 @@ pub(crate) struct SendZc<T> {
 }
 
 impl<T: IoBuf> Op<SendZc<T>> {
-    pub(crate) fn send_zc(fd: &SharedFd, buf: T) -> io::Result<Op<SendZc<T>>> {
+    pub(crate) fn send_zc(fd: &SharedFd, buf: T) -> Result<Op<SendZc<T>>, (io::Error, T)> {
         use io_uring::{opcode, types};
 
-        Op::submit_with(
+        Op::try_submit_with(
             SendZc {
                 fd: fd.clone(),
                 buf,
@@ -36,6 +36,7 @@ impl<T: IoBuf> Op<SendZc<T>> {
                 opcode::SendZc::new(types::Fd(fd.raw_fd()), ptr, len as _).build()
             },
         )
+        .map_err(|(e, op)| (e, op.buf))
     }
 }
 
@@ -0,0 +1,110 @@
+use crate::buf::IoBuf;
+use crate::driver::op::{self, Completable};
+use crate::driver::{Op, SharedFd};
+use crate::BufResult;
+use libc::iovec;
+use std::os::unix::io::RawFd;
+use std::{boxed::Box, io, mem};
+
+pub(crate) struct SendMsg<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+
+    pub(crate) bufs: Vec<T>,
+
+    /// Referred to by `msghdr`, referring `bufs`.
+    #[allow(dead_code)]
+    iovs: Vec<iovec>,
+
+    /// `SCM_RIGHTS` control message referred to by `msghdr`, if any file
+    /// descriptors are passed. Held as words to keep it suitably aligned.
+    #[allow(dead_code)]
+    control: Vec<u64>,
+
+    /// Parameter for `io_uring::opcode::SendMsg`. Boxed, so it stays put
+    /// until the operation completes.
+    msghdr: Box<libc::msghdr>,
+}
+
+impl<T: IoBuf> Op<SendMsg<T>> {
+    pub(crate) fn sendmsg(
+        fd: &SharedFd,
+        bufs: Vec<T>,
+        fds: &[RawFd],
+    ) -> Result<Op<SendMsg<T>>, (io::Error, Vec<T>)> {
+        use io_uring::{opcode, types};
+
+        // Empty buffers are skipped, as for `Writev`.
+        let iovs: Vec<iovec> = bufs
+            .iter()
+            .filter(|b| b.bytes_init() > 0)
+            .map(|b| iovec {
+                iov_base: b.stable_ptr() as *mut libc::c_void,
+                iov_len: b.bytes_init(),
+            })
+            .collect();
+
+        let mut msghdr: Box<libc::msghdr> = Box::new(unsafe { std::mem::zeroed() });
+        msghdr.msg_iov = iovs.as_ptr() as *mut _;
+        msghdr.msg_iovlen = iovs.len() as _;
+
+        let mut control = vec![];
+        if !fds.is_empty() {
+            let data_len = mem::size_of_val(fds) as u32;
+            let space = unsafe { libc::CMSG_SPACE(data_len) } as usize;
+            control = vec![0u64; space.div_ceil(8)];
+            msghdr.msg_control = control.as_mut_ptr().cast();
+            msghdr.msg_controllen = space as _;
+
+            // Safety: the control buffer has room for one control message
+            // holding `fds`.
+            unsafe {
+                let cmsg = libc::CMSG_FIRSTHDR(msghdr.as_ref());
+                (*cmsg).cmsg_level = libc::SOL_SOCKET;
+                (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+                (*cmsg).cmsg_len = libc::CMSG_LEN(data_len) as _;
+                std::ptr::copy_nonoverlapping(
+                    fds.as_ptr(),
+                    libc::CMSG_DATA(cmsg).cast::<RawFd>(),
+                    fds.len(),
+                );
+            }
+        }
+
+        Op::try_submit_with(
+            SendMsg {
+                fd: fd.clone(),
+                bufs,
+                iovs,
+                control,
+                msghdr,
+            },
+            |send| {
+                opcode::SendMsg::new(
+                    types::Fd(send.fd.raw_fd()),
+                    send.msghdr.as_ref() as *const _,
+                )
+                .build()
+            },
+        )
+        .map_err(|(e, op)| (e, op.bufs))
+    }
+}
+
+impl<T> Completable for SendMsg<T>
+where
+    T: IoBuf,
+{
+    type Output = BufResult<usize, Vec<T>>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        // Convert the operation result to `usize`
+        let res = cqe.result.map(|v| v as usize);
+        // Recover the buffers
+        let bufs = self.bufs;
+
+        (res, bufs)
+    }
+}
@@ -1,13 +1,39 @@
-use crate::driver::{Close, Op};
+use crate::driver::{op, Close, Op};
 use crate::future::poll_fn;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::io;
 use std::os::unix::io::{FromRawFd, RawFd};
+use std::pin::Pin;
 use std::rc::Rc;
-use std::task::Waker;
+use std::task::{Context, Poll, Waker};
 
 use crate::runtime::CONTEXT;
 
+#[cfg(all(debug_assertions, feature = "tracing"))]
+thread_local! {
+    /// The FDs owned by a `SharedFd` on this thread and not yet closed, so
+    /// leaks can be reported when the runtime shuts down.
+    static OPEN_FDS: RefCell<std::collections::HashSet<RawFd>> = Default::default();
+}
+
+/// Returns the FDs owned by a `SharedFd` on this thread which were not closed.
+#[cfg(all(debug_assertions, feature = "tracing"))]
+pub(crate) fn open_fds() -> Vec<RawFd> {
+    OPEN_FDS.with(|fds| fds.borrow().iter().copied().collect())
+}
+
+fn track_open(_fd: RawFd) {
+    #[cfg(all(debug_assertions, feature = "tracing"))]
+    let _ = OPEN_FDS.try_with(|fds| fds.borrow_mut().insert(_fd));
+}
+
+fn track_closed(_fd: RawFd) {
+    #[cfg(all(debug_assertions, feature = "tracing"))]
+    let _ = OPEN_FDS.try_with(|fds| fds.borrow_mut().remove(&_fd));
+}
+
 // Tracks in-flight operations on a file descriptor. Ensures all in-flight
 // operations complete before submitting the close.
 //
@@ -24,14 +50,22 @@ struct Inner {
 
     // Waker to notify when the close operation completes.
     state: RefCell<State>,
+
+    // Number of references waiting to close the FD. Once all remaining
+    // references are, the close is submitted.
+    closers: Cell<usize>,
+
+    // Tasks waiting to close the FD
+    wakers: RefCell<Vec<Waker>>,
 }
 
 enum State {
     /// Initial state
     Init,
 
-    /// Waiting for all in-flight operation to complete.
-    Waiting(Option<Waker>),
+    /// Waiting for all in-flight operations, and other references, to
+    /// complete or be dropped.
+    Waiting,
 
     /// The FD is closing
     Closing(Op<Close>),
@@ -42,10 +76,13 @@ enum State {
 
 impl SharedFd {
     pub(crate) fn new(fd: RawFd) -> SharedFd {
+        track_open(fd);
         SharedFd {
             inner: Rc::new(Inner {
                 fd,
                 state: RefCell::new(State::Init),
+                closers: Cell::new(0),
+                wakers: RefCell::new(Vec::new()),
             }),
         }
     }
@@ -59,24 +96,110 @@ impl SharedFd {
     /// This prevents bugs where in-flight reads could operate on the incorrect
     /// file descriptor.
     ///
-    /// TO model this, if there are no in-flight operations, then
-    pub(crate) async fn close(mut self) {
-        // Get a mutable reference to Inner, indicating there are no
-        // in-flight operations on the FD.
-        if let Some(inner) = Rc::get_mut(&mut self.inner) {
-            // Submit the close operation
+    /// Other references to the FD, such as clones of a `File`, are waited for
+    /// as well: the FD is closed once each of them is either dropped or
+    /// closing too, and all closing references complete together.
+    pub(crate) async fn close(self) {
+        self.inner.closers.set(self.inner.closers.get() + 1);
+        let closer = Closer(self);
+
+        poll_fn(|cx| closer.0.poll_close(cx)).await;
+    }
+
+    fn poll_close(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let inner = &*self.inner;
+
+        let unused = Rc::strong_count(&self.inner) == inner.closers.get();
+        if unused && matches!(*inner.state.borrow(), State::Init | State::Waiting) {
             inner.submit_close_op();
         }
 
-        self.inner.closed().await;
+        let mut state = inner.state.borrow_mut();
+        match &mut *state {
+            State::Init | State::Waiting => {
+                *state = State::Waiting;
+                inner.register(cx.waker());
+                Poll::Pending
+            }
+            State::Closing(op) => {
+                // The operation only wakes the last task polling it, which
+                // wakes the others once closed.
+                inner.register(cx.waker());
+
+                // Nothing to do if the close operation failed.
+                let _ = ready!(Pin::new(op).poll(cx));
+                *state = State::Closed;
+                inner
+                    .wakers
+                    .borrow_mut()
+                    .drain(..)
+                    .filter(|waker| !waker.will_wake(cx.waker()))
+                    .for_each(Waker::wake);
+                Poll::Ready(())
+            }
+            State::Closed => Poll::Ready(()),
+        }
+    }
+
+    /// Sync all data and metadata, then close the FD.
+    ///
+    /// If there are no in-flight operations, the close is hard linked to the
+    /// sync, so both reach the kernel in a single submission and the close
+    /// runs even if the sync fails. Otherwise, the close is only submitted once
+    /// the sync and all other in-flight operations complete.
+    ///
+    /// The sync error is returned first, then the close error.
+    pub(crate) async fn sync_and_close(mut self) -> io::Result<()> {
+        if Rc::get_mut(&mut self.inner).is_none() {
+            let synced = Op::fsync(&self)?.await;
+            self.close().await;
+            return synced;
+        }
+
+        let fd = self.raw_fd();
+        let (sync, close) = op::hard_link(2, || {
+            Ok::<_, io::Error>((Op::fsync(&self)?, Op::close(fd)?))
+        })??;
+
+        // The close is in flight, it must not be submitted again.
+        *self.inner.state.borrow_mut() = State::Closed;
+        track_closed(fd);
+
+        let (synced, closed) = futures_util::future::join(sync, close).await;
+        synced?;
+        closed
+    }
+}
+
+/// A reference to an FD waiting to close it.
+struct Closer(SharedFd);
+
+impl Drop for Closer {
+    fn drop(&mut self) {
+        let inner = &self.0.inner;
+        inner.closers.set(inner.closers.get() - 1);
+    }
+}
+
+impl Drop for SharedFd {
+    fn drop(&mut self) {
+        // Once only closing references remain, they can close the FD.
+        let inner = &self.inner;
+        let closers = inner.closers.get();
+        if closers > 0 && Rc::strong_count(inner) - 1 == closers {
+            if let State::Waiting = *inner.state.borrow() {
+                inner.wakers.borrow_mut().drain(..).for_each(Waker::wake);
+            }
+        }
     }
 }
 
 impl Inner {
     /// If there are no in-flight operations, submit the operation.
-    fn submit_close_op(&mut self) {
+    fn submit_close_op(&self) {
         // Close the FD
-        let state = RefCell::get_mut(&mut self.state);
+        let mut state = self.state.borrow_mut();
+        track_closed(self.fd);
 
         // Submit a close operation
         // If either:
@@ -106,41 +229,11 @@ impl Inner {
         };
     }
 
-    /// Completes when the FD has been closed.
-    async fn closed(&self) {
-        use std::future::Future;
-        use std::pin::Pin;
-        use std::task::Poll;
-
-        poll_fn(|cx| {
-            let mut state = self.state.borrow_mut();
-
-            match &mut *state {
-                State::Init => {
-                    *state = State::Waiting(Some(cx.waker().clone()));
-                    Poll::Pending
-                }
-                State::Waiting(Some(waker)) => {
-                    if !waker.will_wake(cx.waker()) {
-                        *waker = cx.waker().clone();
-                    }
-
-                    Poll::Pending
-                }
-                State::Waiting(None) => {
-                    *state = State::Waiting(Some(cx.waker().clone()));
-                    Poll::Pending
-                }
-                State::Closing(op) => {
-                    // Nothing to do if the close opeation failed.
-                    let _ = ready!(Pin::new(op).poll(cx));
-                    *state = State::Closed;
-                    Poll::Ready(())
-                }
-                State::Closed => Poll::Ready(()),
-            }
-        })
-        .await;
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.borrow_mut();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
     }
 }
 
@@ -148,7 +241,7 @@ impl Drop for Inner {
     fn drop(&mut self) {
         // Submit the close operation, if needed
         match RefCell::get_mut(&mut self.state) {
-            State::Init | State::Waiting(..) => {
+            State::Init | State::Waiting => {
                 self.submit_close_op();
             }
             _ => {}
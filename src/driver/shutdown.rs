@@ -0,0 +1,30 @@
+use crate::driver::{Op, SharedFd};
+
+use std::io;
+
+use crate::driver::op::{self, Completable};
+use io_uring::{opcode, types};
+
+pub(crate) struct Shutdown {
+    /// Holds a strong ref to the FD, preventing the socket from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd: SharedFd,
+}
+
+impl Op<Shutdown> {
+    /// Shuts down `fd` as `how`, one of the `SHUT_*` constants.
+    pub(crate) fn shutdown(fd: &SharedFd, how: libc::c_int) -> io::Result<Op<Shutdown>> {
+        Op::submit_with(Shutdown { fd: fd.clone() }, |_| {
+            opcode::Shutdown::new(types::Fd(fd.raw_fd()), how).build()
+        })
+    }
+}
+
+impl Completable for Shutdown {
+    type Output = io::Result<()>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| ())
+    }
+}
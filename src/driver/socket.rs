@@ -1,14 +1,17 @@
 use crate::{
     buf::{IoBuf, IoBufMut},
-    driver::{Op, SharedFd},
+    driver::{op, Op, SharedFd},
 };
 use std::{
-    io,
+    cmp, io,
     net::SocketAddr,
     os::unix::io::{AsRawFd, IntoRawFd, RawFd},
     path::Path,
 };
 
+/// The most file descriptors the kernel passes in a single message.
+const SCM_MAX_FD: usize = 253;
+
 #[derive(Clone)]
 pub(crate) struct Socket {
     /// Open file descriptor
@@ -22,6 +25,11 @@ pub(crate) fn get_domain(socket_addr: SocketAddr) -> libc::c_int {
     }
 }
 
+/// Whether the ring of the current runtime supports `opcode`.
+fn is_supported(opcode: u8) -> bool {
+    crate::probe().is_ok_and(|probe| probe.is_supported(opcode))
+}
+
 impl Socket {
     pub(crate) fn new(socket_addr: SocketAddr, socket_type: libc::c_int) -> io::Result<Socket> {
         let socket_type = socket_type | libc::SOCK_CLOEXEC;
@@ -40,13 +48,22 @@ impl Socket {
     }
 
     pub(crate) async fn write<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
-        let op = Op::write_at(&self.fd, buf, 0).unwrap();
-        op.await
+        op::submit_buf(|| Op::write_at(&self.fd, buf, 0, 0)).await
     }
 
     pub async fn writev<T: IoBuf>(&self, buf: Vec<T>) -> crate::BufResult<usize, Vec<T>> {
-        let op = Op::writev_at(&self.fd, buf, 0).unwrap();
-        op.await
+        op::submit_buf(|| Op::writev_at(&self.fd, buf, 0)).await
+    }
+
+    pub(crate) async fn send<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
+        op::submit_buf(|| Op::send(&self.fd, buf, 0)).await
+    }
+
+    pub(crate) async fn send_vectored<T: IoBuf>(
+        &self,
+        bufs: Vec<T>,
+    ) -> crate::BufResult<usize, Vec<T>> {
+        op::submit_buf(|| Op::sendmsg(&self.fd, bufs, &[])).await
     }
 
     pub(crate) async fn send_to<T: IoBuf>(
@@ -54,26 +71,129 @@ impl Socket {
         buf: T,
         socket_addr: SocketAddr,
     ) -> crate::BufResult<usize, T> {
-        let op = Op::send_to(&self.fd, buf, socket_addr).unwrap();
-        op.await
+        op::submit_buf(|| Op::send_to(&self.fd, buf, socket_addr)).await
     }
 
     pub(crate) async fn send_zc<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
-        let op = Op::send_zc(&self.fd, buf).unwrap();
-        op.await
+        op::submit_buf(|| Op::send_zc(&self.fd, buf)).await
+    }
+
+    pub(crate) async fn send_file(
+        &self,
+        file: &SharedFd,
+        offset: u64,
+        len: usize,
+    ) -> io::Result<usize> {
+        use futures_util::future::join;
+
+        match offset.checked_add(len as u64) {
+            Some(end) if end <= i64::MAX as u64 => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "range too large for file",
+                ))
+            }
+        }
+
+        let mut fds = [0; 2];
+        syscall!(pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC))?;
+        let (pipe_rd, pipe_wr) = (SharedFd::new(fds[0]), SharedFd::new(fds[1]));
+
+        // Never splice more than the pipe holds, or the splice into it blocks.
+        let pipe_size = syscall!(fcntl(pipe_wr.raw_fd(), libc::F_GETPIPE_SZ))? as usize;
+
+        let mut sent = 0;
+        while sent < len {
+            let chunk = cmp::min(len - sent, pipe_size) as u32;
+            let pos = (offset + sent as u64) as i64;
+
+            let (to_pipe, from_pipe) = op::link(2, || {
+                (
+                    Op::splice(file, pos, &pipe_wr, -1, chunk),
+                    Op::splice(&pipe_rd, -1, &self.fd, -1, chunk),
+                )
+            })?;
+            let (res_in, res_out) = join(to_pipe?, from_pipe?).await;
+
+            let n_in = match res_in? {
+                // End of file
+                0 => break,
+                n => n,
+            };
+
+            // A short splice into the pipe cancels the splice out of it.
+            let mut n_out = match res_out {
+                Ok(n) => n,
+                Err(e) if e.raw_os_error() == Some(libc::ECANCELED) => 0,
+                Err(e) => return Err(e),
+            };
+
+            // Drain whatever is left in the pipe.
+            while n_out < n_in {
+                match Op::splice(&pipe_rd, -1, &self.fd, -1, (n_in - n_out) as u32)?.await? {
+                    0 => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "failed to send whole file range",
+                        ))
+                    }
+                    n => n_out += n,
+                }
+            }
+
+            sent += n_in;
+        }
+
+        Ok(sent)
     }
 
     pub(crate) async fn read<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
-        let op = Op::read_at(&self.fd, buf, 0).unwrap();
-        op.await
+        op::submit_buf(|| Op::read_at(&self.fd, buf, 0)).await
+    }
+
+    pub(crate) async fn recv<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        op::submit_buf(|| Op::recv(&self.fd, buf, 0)).await
+    }
+
+    pub(crate) async fn recv_vectored<T: IoBufMut>(
+        &self,
+        bufs: Vec<T>,
+    ) -> crate::BufResult<usize, Vec<T>> {
+        let (res, bufs) = op::submit_buf(|| Op::recvmsg(&self.fd, bufs, 0)).await;
+        (res.map(|(n, _)| n), bufs)
+    }
+
+    pub(crate) async fn send_with_fds<T: IoBuf>(
+        &self,
+        buf: T,
+        fds: &[RawFd],
+    ) -> crate::BufResult<usize, T> {
+        if fds.len() > SCM_MAX_FD {
+            let err = io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "too many file descriptors to pass at once",
+            );
+            return (Err(err), buf);
+        }
+
+        let (res, mut bufs) = op::submit_buf(|| Op::sendmsg(&self.fd, vec![buf], fds)).await;
+        (res, bufs.pop().unwrap())
+    }
+
+    pub(crate) async fn recv_with_fds<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> crate::BufResult<(usize, Vec<RawFd>), T> {
+        let (res, mut bufs) = op::submit_buf(|| Op::recvmsg(&self.fd, vec![buf], SCM_MAX_FD)).await;
+        (res, bufs.pop().unwrap())
     }
 
     pub(crate) async fn recv_from<T: IoBufMut>(
         &self,
         buf: T,
     ) -> crate::BufResult<(usize, SocketAddr), T> {
-        let op = Op::recv_from(&self.fd, buf).unwrap();
-        op.await
+        op::submit_buf(|| Op::recv_from(&self.fd, buf)).await
     }
 
     pub(crate) async fn accept(&self) -> io::Result<(Socket, Option<SocketAddr>)> {
@@ -94,6 +214,17 @@ impl Socket {
         )
     }
 
+    /// Like `bind`, binding the socket through the ring when supported.
+    pub(crate) async fn bind_async(
+        socket_addr: SocketAddr,
+        socket_type: libc::c_int,
+    ) -> io::Result<Socket> {
+        let sys_socket = Self::new_reusable(get_domain(socket_addr).into(), socket_type.into())?;
+        let socket = Self::from_std(sys_socket);
+        socket.bind_to(socket_addr.into()).await?;
+        Ok(socket)
+    }
+
     pub(crate) fn bind_unix<P: AsRef<Path>>(
         path: P,
         socket_type: libc::c_int,
@@ -116,6 +247,19 @@ impl Socket {
         domain: socket2::Domain,
         socket_type: socket2::Type,
     ) -> io::Result<Socket> {
+        let sys_listener = Self::new_reusable(domain, socket_type)?;
+
+        sys_listener.bind(&socket_addr)?;
+
+        let fd = SharedFd::new(sys_listener.into_raw_fd());
+
+        Ok(Self { fd })
+    }
+
+    fn new_reusable(
+        domain: socket2::Domain,
+        socket_type: socket2::Type,
+    ) -> io::Result<socket2::Socket> {
         let sys_listener = socket2::Socket::new(domain, socket_type, None)?;
 
         sys_listener.set_reuse_port(true)?;
@@ -125,11 +269,18 @@ impl Socket {
         // sys_listener.set_send_buffer_size(send_buf_size)?;
         // sys_listener.set_recv_buffer_size(recv_buf_size)?;
 
-        sys_listener.bind(&socket_addr)?;
+        Ok(sys_listener)
+    }
 
-        let fd = SharedFd::new(sys_listener.into_raw_fd());
+    /// Binds the socket to `addr`, through the ring on Linux 6.11 and later.
+    pub(crate) async fn bind_to(&self, addr: socket2::SockAddr) -> io::Result<()> {
+        if is_supported(crate::driver::IORING_OP_BIND) {
+            return Op::bind(&self.fd, addr)?.await;
+        }
 
-        Ok(Self { fd })
+        // Binding does not block, so older kernels do it inline.
+        syscall!(bind(self.as_raw_fd(), addr.as_ptr(), addr.len()))?;
+        Ok(())
     }
 
     pub(crate) fn listen(&self, backlog: libc::c_int) -> io::Result<()> {
@@ -137,6 +288,15 @@ impl Socket {
         Ok(())
     }
 
+    /// Like `listen`, through the ring on Linux 6.11 and later.
+    pub(crate) async fn listen_async(&self, backlog: libc::c_int) -> io::Result<()> {
+        if is_supported(crate::driver::IORING_OP_LISTEN) {
+            return Op::listen(&self.fd, backlog)?.await;
+        }
+
+        self.listen(backlog)
+    }
+
     /// Shuts down the read, write, or both halves of this connection.
     ///
     /// This function will cause all pending and future I/O on the specified portions to return
@@ -154,6 +314,27 @@ impl Socket {
         std::mem::forget(s);
         result
     }
+
+    /// Like `shutdown`, submitting `IORING_OP_SHUTDOWN` where supported.
+    pub(crate) async fn shutdown_async(&self, how: std::net::Shutdown) -> io::Result<()> {
+        let how = match how {
+            std::net::Shutdown::Read => libc::SHUT_RD,
+            std::net::Shutdown::Write => libc::SHUT_WR,
+            std::net::Shutdown::Both => libc::SHUT_RDWR,
+        };
+
+        if is_supported(io_uring::opcode::Shutdown::CODE) {
+            return Op::shutdown(&self.fd, how)?.await;
+        }
+
+        // Shutting down does not block, so older kernels do it inline.
+        syscall!(shutdown(self.as_raw_fd(), how))?;
+        Ok(())
+    }
+
+    pub(crate) async fn cancel_all(&self) -> io::Result<usize> {
+        crate::driver::cancel_all(&self.fd).await
+    }
 }
 
 impl AsRawFd for Socket {
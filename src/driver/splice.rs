@@ -0,0 +1,55 @@
+use crate::driver::op::{self, Completable};
+use crate::driver::{Op, SharedFd};
+use std::io;
+
+/// Move data between two file descriptors, one of which must be a pipe.
+pub(crate) struct Splice {
+    /// Holds a strong ref to the FDs, preventing them from being closed
+    /// while the operation is in-flight.
+    #[allow(dead_code)]
+    fd_in: SharedFd,
+
+    #[allow(dead_code)]
+    fd_out: SharedFd,
+}
+
+impl Op<Splice> {
+    /// Submit a request to move `len` bytes from `fd_in` to `fd_out`.
+    ///
+    /// An offset of `-1` must be given for a pipe, and makes other file
+    /// descriptors use and update their file offset.
+    pub(crate) fn splice(
+        fd_in: &SharedFd,
+        off_in: i64,
+        fd_out: &SharedFd,
+        off_out: i64,
+        len: u32,
+    ) -> io::Result<Op<Splice>> {
+        use io_uring::{opcode, types};
+
+        Op::submit_with(
+            Splice {
+                fd_in: fd_in.clone(),
+                fd_out: fd_out.clone(),
+            },
+            |_| {
+                opcode::Splice::new(
+                    types::Fd(fd_in.raw_fd()),
+                    off_in,
+                    types::Fd(fd_out.raw_fd()),
+                    off_out,
+                    len,
+                )
+                .build()
+            },
+        )
+    }
+}
+
+impl Completable for Splice {
+    type Output = io::Result<usize>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|v| v as usize)
+    }
+}
@@ -0,0 +1,36 @@
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Room reserved in the submission queue for a chain of operations.
+///
+/// Obtained through [`tokio_uring::reserve`]. See its documentation for more
+/// details.
+///
+/// [`tokio_uring::reserve`]: crate::reserve
+#[must_use = "the reservation only holds until the task yields"]
+#[derive(Debug)]
+pub struct SqReservation {
+    len: usize,
+
+    /// The reservation is bound to the ring of the current thread.
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl SqReservation {
+    pub(crate) fn new(len: usize) -> SqReservation {
+        SqReservation {
+            len,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries reserved.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no entries are reserved.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
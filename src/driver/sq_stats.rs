@@ -0,0 +1,50 @@
+use std::fmt;
+
+/// A snapshot of the submission queue occupancy.
+///
+/// Obtained through [`tokio_uring::sq_stats`]. See its documentation for more
+/// details.
+///
+/// [`tokio_uring::sq_stats`]: crate::sq_stats
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SqStats {
+    len: usize,
+    capacity: usize,
+}
+
+impl SqStats {
+    pub(crate) fn new(len: usize, capacity: usize) -> SqStats {
+        SqStats { len, capacity }
+    }
+
+    /// Returns the number of entries queued but not yet consumed by the
+    /// kernel.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no entries are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of entries the submission queue holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the submission queue is full, in which case the next
+    /// operation first submits the queued entries to the kernel.
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity
+    }
+}
+
+impl fmt::Debug for SqStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqStats")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
@@ -0,0 +1,80 @@
+use crate::driver::{self, Op, SharedFd};
+
+use crate::driver::op::{self, Completable};
+use std::ffi::CString;
+use std::path::Path;
+use std::{boxed::Box, io};
+
+pub(crate) struct Statx {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    /// Not set when querying a path.
+    #[allow(dead_code)]
+    fd: Option<SharedFd>,
+
+    /// Path of the file, relative to `fd` or to the current directory
+    path: CString,
+
+    /// Filled in by the kernel. Boxed, so it stays put until the operation
+    /// completes.
+    statx: Box<libc::statx>,
+}
+
+impl Op<Statx> {
+    /// Submit a request to query the attributes selected by `mask` of the
+    /// file referred to by `fd`, with the `AT_*` `flags`.
+    pub(crate) fn statx(fd: &SharedFd, mask: u32, flags: i32) -> io::Result<Op<Statx>> {
+        use io_uring::{opcode, types};
+
+        Op::submit_with(
+            Statx {
+                fd: Some(fd.clone()),
+                path: CString::default(),
+                statx: Box::new(unsafe { std::mem::zeroed() }),
+            },
+            |statx| {
+                // An empty path, with `AT_EMPTY_PATH`, refers to the FD itself.
+                opcode::Statx::new(
+                    types::Fd(fd.raw_fd()),
+                    statx.path.as_ptr(),
+                    statx.statx.as_mut() as *mut libc::statx as *mut types::statx,
+                )
+                .flags(flags | libc::AT_EMPTY_PATH)
+                .mask(mask)
+                .build()
+            },
+        )
+    }
+
+    /// Submit a request to query the attributes selected by `mask` of the
+    /// file at `path`, with the `AT_*` `flags`.
+    pub(crate) fn statx_path(path: &Path, mask: u32, flags: i32) -> io::Result<Op<Statx>> {
+        use io_uring::{opcode, types};
+
+        Op::submit_with(
+            Statx {
+                fd: None,
+                path: driver::util::cstr(path)?,
+                statx: Box::new(unsafe { std::mem::zeroed() }),
+            },
+            |statx| {
+                opcode::Statx::new(
+                    types::Fd(libc::AT_FDCWD),
+                    statx.path.as_ptr(),
+                    statx.statx.as_mut() as *mut libc::statx as *mut types::statx,
+                )
+                .flags(flags)
+                .mask(mask)
+                .build()
+            },
+        )
+    }
+}
+
+impl Completable for Statx {
+    type Output = io::Result<libc::statx>;
+
+    fn complete(self, cqe: op::CqeResult) -> Self::Output {
+        cqe.result.map(|_| *self.statx)
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+/// The completion of an operation tagged for diagnostics.
+///
+/// Passed to the callback set with [`Builder::on_tagged_completion`].
+///
+/// [`Builder::on_tagged_completion`]: crate::Builder::on_tagged_completion
+#[derive(Clone, Copy, Debug)]
+pub struct TaggedCompletion {
+    tag: u64,
+    result: i32,
+    elapsed: Duration,
+}
+
+impl TaggedCompletion {
+    /// Returns the tag attached to the operation.
+    pub fn tag(&self) -> u64 {
+        self.tag
+    }
+
+    /// Returns the raw result of the operation, as reported by the kernel.
+    ///
+    /// A negative value is the negated `errno` of a failed operation.
+    pub fn result(&self) -> i32 {
+        self.result
+    }
+
+    /// Returns the time elapsed between the operation being queued for
+    /// submission and its completion being reaped.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// A tag attached to an in-flight operation.
+pub(crate) struct Tag {
+    tag: u64,
+    submitted: Instant,
+}
+
+impl Tag {
+    pub(crate) fn new(tag: u64) -> Tag {
+        Tag {
+            tag,
+            submitted: Instant::now(),
+        }
+    }
+
+    pub(crate) fn complete(self, result: i32) -> TaggedCompletion {
+        TaggedCompletion {
+            tag: self.tag,
+            result,
+            elapsed: self.submitted.elapsed(),
+        }
+    }
+}
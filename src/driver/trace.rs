@@ -0,0 +1,127 @@
+use crate::driver::util::RawSqe;
+
+use io_uring::squeue;
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::Span;
+
+/// Names of the opcodes, by code.
+const OPCODES: [&str; 58] = [
+    "nop",
+    "readv",
+    "writev",
+    "fsync",
+    "read_fixed",
+    "write_fixed",
+    "poll_add",
+    "poll_remove",
+    "sync_file_range",
+    "sendmsg",
+    "recvmsg",
+    "timeout",
+    "timeout_remove",
+    "accept",
+    "async_cancel",
+    "link_timeout",
+    "connect",
+    "fallocate",
+    "openat",
+    "close",
+    "files_update",
+    "statx",
+    "read",
+    "write",
+    "fadvise",
+    "madvise",
+    "send",
+    "recv",
+    "openat2",
+    "epoll_ctl",
+    "splice",
+    "provide_buffers",
+    "remove_buffers",
+    "tee",
+    "shutdown",
+    "renameat",
+    "unlinkat",
+    "mkdirat",
+    "symlinkat",
+    "linkat",
+    "msg_ring",
+    "fsetxattr",
+    "setxattr",
+    "fgetxattr",
+    "getxattr",
+    "socket",
+    "uring_cmd",
+    "send_zc",
+    "sendmsg_zc",
+    "read_multishot",
+    "waitid",
+    "futex_wait",
+    "futex_wake",
+    "futex_waitv",
+    "fixed_fd_install",
+    "ftruncate",
+    "bind",
+    "listen",
+];
+
+/// The span of an in-flight operation, with the times it went through the
+/// ring.
+pub(crate) struct OpSpan {
+    span: Span,
+
+    /// When the entry was pushed to the submission queue
+    queued: Instant,
+
+    /// When the entry was submitted to the kernel
+    submitted: Option<Instant>,
+}
+
+impl OpSpan {
+    /// Opens the span of the operation submitted with `sqe`.
+    pub(crate) fn new(sqe: &squeue::Entry) -> OpSpan {
+        // Safety: `Entry` is a `repr(C)` wrapper of the 64-byte SQE, which
+        // `RawSqe` lays out field by field.
+        let raw = unsafe { &*(sqe as *const squeue::Entry as *const RawSqe) };
+        let op = OPCODES
+            .get(raw.opcode as usize)
+            .copied()
+            .unwrap_or("unknown");
+
+        OpSpan {
+            span: tracing::trace_span!(
+                "io_uring_op",
+                op,
+                fd = raw.fd,
+                queue_wait_us = Empty,
+                kernel_us = Empty,
+                result = Empty,
+            ),
+            queued: Instant::now(),
+            submitted: None,
+        }
+    }
+
+    /// Records the submission of the operation to the kernel, if not already
+    /// submitted.
+    pub(crate) fn submit(&mut self, now: Instant) {
+        self.submitted.get_or_insert(now);
+    }
+
+    /// Records the completion of the operation with the raw `result`, closing
+    /// the span.
+    pub(crate) fn complete(self, result: i32) {
+        let reaped = Instant::now();
+        let submitted = self.submitted.unwrap_or(self.queued);
+        let queue_wait = submitted.duration_since(self.queued);
+        let kernel = reaped.duration_since(submitted);
+
+        self.span
+            .record("queue_wait_us", queue_wait.as_micros() as u64)
+            .record("kernel_us", kernel.as_micros() as u64)
+            .record("result", result);
+        tracing::trace!(parent: &self.span, ?queue_wait, ?kernel, result, "operation completed");
+    }
+}
@@ -1,8 +1,38 @@
+use io_uring::squeue;
 use std::ffi::CString;
 use std::io;
+use std::mem;
 use std::path::Path;
 
 pub(super) fn cstr(p: &Path) -> io::Result<CString> {
     use std::os::unix::ffi::OsStrExt;
     Ok(CString::new(p.as_os_str().as_bytes())?)
 }
+
+/// The layout of a 64-byte SQE, for opcodes the `io-uring` crate cannot build.
+#[repr(C)]
+#[derive(Default)]
+pub(crate) struct RawSqe {
+    pub(crate) opcode: u8,
+    pub(crate) flags: u8,
+    pub(crate) ioprio: u16,
+    pub(crate) fd: i32,
+    pub(crate) off: u64,
+    pub(crate) addr: u64,
+    pub(crate) len: u32,
+    pub(crate) op_flags: u32,
+    pub(crate) user_data: u64,
+    pub(crate) buf_index: u16,
+    pub(crate) personality: u16,
+    pub(crate) splice_fd_in: i32,
+    pub(crate) addr3: u64,
+    pub(crate) pad: u64,
+}
+
+impl RawSqe {
+    pub(crate) fn build(self) -> squeue::Entry {
+        // Safety: `Entry` is a `repr(C)` wrapper of the 64-byte SQE, which
+        // `RawSqe` lays out field by field.
+        unsafe { mem::transmute::<RawSqe, squeue::Entry>(self) }
+    }
+}
@@ -0,0 +1,30 @@
+/// How the runtime waits for completions once it has nothing left to run.
+///
+/// Set with [`Builder::wait_strategy`].
+///
+/// [`Builder::wait_strategy`]: crate::Builder::wait_strategy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WaitStrategy {
+    /// Park the thread until the ring signals a completion.
+    ///
+    /// This is the default. The thread sleeps while operations are in flight,
+    /// and is woken by the kernel once one completes.
+    #[default]
+    Block,
+
+    /// Check the completion queue up to `max_spins` times before parking the
+    /// thread.
+    ///
+    /// Checking the queue is a load from memory shared with the kernel, and
+    /// does not enter it. A completion posted while spinning is processed
+    /// without the thread going to sleep and being woken up again, which
+    /// saves the latency of a wakeup.
+    ///
+    /// The spin count bounds the CPU time burnt: the thread spins at most
+    /// `max_spins` times each time it is about to park, and only while
+    /// operations are in flight.
+    Spin {
+        /// Maximum number of checks of the completion queue before parking
+        max_spins: u32,
+    },
+}
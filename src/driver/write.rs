@@ -1,7 +1,7 @@
 use crate::driver::op::{self, Completable};
 use crate::{
     buf::IoBuf,
-    driver::{Op, SharedFd},
+    driver::{FixedSlot, Op, SharedFd},
     BufResult,
 };
 use std::io;
@@ -9,19 +9,27 @@ use std::io;
 pub(crate) struct Write<T> {
     /// Holds a strong ref to the FD, preventing the file from being closed
     /// while the operation is in-flight.
+    /// Not set when writing to a direct descriptor.
     #[allow(dead_code)]
-    fd: SharedFd,
+    fd: Option<SharedFd>,
 
     pub(crate) buf: T,
 }
 
 impl<T: IoBuf> Op<Write<T>> {
-    pub(crate) fn write_at(fd: &SharedFd, buf: T, offset: u64) -> io::Result<Op<Write<T>>> {
+    /// Submit a request to write `buf` to `fd` at `offset`, with the
+    /// `pwritev2(2)` flags `rw_flags`, such as `RWF_DSYNC`.
+    pub(crate) fn write_at(
+        fd: &SharedFd,
+        buf: T,
+        offset: u64,
+        rw_flags: i32,
+    ) -> Result<Op<Write<T>>, (io::Error, T)> {
         use io_uring::{opcode, types};
 
-        Op::submit_with(
+        Op::try_submit_with(
             Write {
-                fd: fd.clone(),
+                fd: Some(fd.clone()),
                 buf,
             },
             |write| {
@@ -31,9 +39,32 @@ impl<T: IoBuf> Op<Write<T>> {
 
                 opcode::Write::new(types::Fd(fd.raw_fd()), ptr, len as _)
                     .offset(offset as _)
+                    .rw_flags(rw_flags)
                     .build()
             },
         )
+        .map_err(|(e, op)| (e, op.buf))
+    }
+
+    /// Submit a request to write to the direct descriptor in `slot`.
+    ///
+    /// The slot is not held by the operation, it must be kept in use until
+    /// the write completes.
+    pub(crate) fn write_direct(
+        slot: &FixedSlot,
+        buf: T,
+        offset: u64,
+    ) -> Result<Op<Write<T>>, (io::Error, T)> {
+        use io_uring::{opcode, types};
+
+        Op::try_submit_with(Write { fd: None, buf }, |write| {
+            let ptr = write.buf.stable_ptr();
+            let len = write.buf.bytes_init();
+            opcode::Write::new(types::Fixed(slot.index()), ptr, len as _)
+                .offset(offset as _)
+                .build()
+        })
+        .map_err(|(e, op)| (e, op.buf))
     }
 }
 
@@ -21,22 +21,43 @@ pub(crate) struct Writev<T> {
 
 impl<T: IoBuf> Op<Writev<T>> {
     pub(crate) fn writev_at(
+        fd: &SharedFd,
+        bufs: Vec<T>,
+        offset: u64,
+    ) -> Result<Op<Writev<T>>, (io::Error, Vec<T>)> {
+        Op::writev_at_from(fd, bufs, 0, offset)
+    }
+
+    /// Like [`writev_at`], but the first `skip` bytes of `bufs` are left out,
+    /// e.g. to resume a write which completed short.
+    ///
+    /// [`writev_at`]: Op::writev_at
+    pub(crate) fn writev_at_from(
         fd: &SharedFd,
         mut bufs: Vec<T>,
+        mut skip: usize,
         offset: u64,
-    ) -> io::Result<Op<Writev<T>>> {
+    ) -> Result<Op<Writev<T>>, (io::Error, Vec<T>)> {
         use io_uring::{opcode, types};
 
-        // Build `iovec` objects referring the provided `bufs` for `io_uring::opcode::Readv`.
+        // Build `iovec` objects referring the provided `bufs` for `io_uring::opcode::Writev`.
+        // Empty buffers are skipped, as some kernels count them against `IOV_MAX`
+        // or reject them. They are still returned to the caller.
         let iovs: Vec<iovec> = bufs
             .iter_mut()
-            .map(|b| iovec {
-                iov_base: b.stable_ptr() as *mut libc::c_void,
-                iov_len: b.bytes_init(),
+            .filter_map(|b| {
+                let len = b.bytes_init();
+                let start = std::cmp::min(skip, len);
+                skip -= start;
+                (start < len).then(|| iovec {
+                    // Safety: `start` is within the initialized part of the buffer.
+                    iov_base: unsafe { b.stable_ptr().add(start) } as *mut libc::c_void,
+                    iov_len: len - start,
+                })
             })
             .collect();
 
-        Op::submit_with(
+        Op::try_submit_with(
             Writev {
                 fd: fd.clone(),
                 bufs,
@@ -52,6 +73,7 @@ impl<T: IoBuf> Op<Writev<T>> {
                 .build()
             },
         )
+        .map_err(|(e, op)| (e, op.bufs))
     }
 }
 
@@ -0,0 +1,166 @@
+use crate::buf::{IoBuf, IoBufMut};
+use crate::fs::{File, OpenOptions};
+
+use std::cell::Cell;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use tokio::sync::Mutex;
+
+/// A file which is only ever written to at its end, such as a write-ahead
+/// log, while earlier records remain readable by offset.
+///
+/// An `AppendFile` keeps track of the end of the file, so appending does not
+/// require an external offset counter: each [`append`] writes at the end,
+/// and advances it past the data written. Reads are positional, as with
+/// [`File::read_at`].
+///
+/// Appends are serialized: an append only starts once the previous one
+/// completed, so concurrent appends never overlap, and records follow each
+/// other without holes. If an append fails, the end is not advanced, and the
+/// next append overwrites whatever part of the failed record was written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::AppendFile;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let log = AppendFile::open("wal.log").await?;
+///
+///         let (res, _) = log.append(b"first record".to_vec()).await;
+///         let pos = res?;
+///         log.sync_data().await?;
+///
+///         // Read the record back
+///         let (res, buf) = log.read_exact_at(vec![0; 12], pos).await;
+///         res?;
+///         assert_eq!(buf, b"first record");
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// [`append`]: AppendFile::append
+pub struct AppendFile {
+    file: File,
+
+    /// The end of the file, as far as appends are concerned.
+    end: Cell<u64>,
+
+    /// Held for the duration of an append.
+    append_lock: Mutex<()>,
+}
+
+impl AppendFile {
+    /// Opens a file for appending and reading, creating it if it does not
+    /// exist.
+    ///
+    /// The end of the file is initialized to its current size.
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<AppendFile> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(path)
+            .await?;
+        AppendFile::from_file(file).await
+    }
+
+    /// Wraps an open file, which must be open for writing.
+    ///
+    /// The end of the file is initialized to its current size. The file must
+    /// not be written to other than through the `AppendFile` afterwards.
+    pub async fn from_file(file: File) -> io::Result<AppendFile> {
+        let statx = file.statx(libc::STATX_SIZE, 0).await?;
+        Ok(AppendFile {
+            file,
+            end: Cell::new(statx.stx_size),
+            append_lock: Mutex::new(()),
+        })
+    }
+
+    /// Appends the whole buffer at the end of the file, returning the offset
+    /// it was written at.
+    ///
+    /// If another append is in progress, this waits for it to complete
+    /// first. On error, the end of the file is left unchanged.
+    pub async fn append<T: IoBuf>(&self, buf: T) -> crate::BufResult<u64, T> {
+        let _guard = self.append_lock.lock().await;
+
+        let pos = self.end.get();
+        let len = buf.bytes_init() as u64;
+        let (res, buf) = self.file.write_all_at(buf, pos).await;
+        if let Err(e) = res {
+            return (Err(e), buf);
+        }
+
+        self.end.set(pos + len);
+        (Ok(pos), buf)
+    }
+
+    /// Read some bytes at the specified offset, returning how many bytes were
+    /// read.
+    ///
+    /// See [`File::read_at`] for details.
+    pub async fn read_at<T: IoBufMut>(&self, buf: T, pos: u64) -> crate::BufResult<usize, T> {
+        self.file.read_at(buf, pos).await
+    }
+
+    /// Read the exact number of bytes required to fill the buffer, at the
+    /// specified offset.
+    ///
+    /// See [`File::read_exact_at`] for details.
+    pub async fn read_exact_at<T: IoBufMut>(&self, buf: T, pos: u64) -> crate::BufResult<(), T> {
+        self.file.read_exact_at(buf, pos).await
+    }
+
+    /// Returns the end of the file, where the next append is written.
+    ///
+    /// This includes the data of the appends which completed so far.
+    pub fn len(&self) -> u64 {
+        self.end.get()
+    }
+
+    /// Returns `true` if the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Syncs all data and metadata to disk.
+    ///
+    /// See [`File::sync_all`] for details.
+    pub async fn sync_all(&self) -> io::Result<()> {
+        self.file.sync_all().await
+    }
+
+    /// Syncs the file data to disk, and the metadata needed to read it back,
+    /// such as its size.
+    ///
+    /// See [`File::sync_data`] for details.
+    pub async fn sync_data(&self) -> io::Result<()> {
+        self.file.sync_data().await
+    }
+
+    /// Returns the underlying file.
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+
+    /// Closes the file.
+    ///
+    /// See [`File::close`] for details.
+    pub async fn close(self) -> io::Result<()> {
+        self.file.close().await
+    }
+}
+
+impl fmt::Debug for AppendFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppendFile")
+            .field("file", &self.file)
+            .field("end", &self.end.get())
+            .finish()
+    }
+}
@@ -0,0 +1,134 @@
+use crate::buf::IoBuf;
+use crate::fs::{fallback, File};
+use crate::util::asyncify;
+
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::sync::Arc;
+
+/// Most bytes copied by the kernel between two calls of the progress callback
+const KERNEL_CHUNK_LEN: u64 = 8 * 1024 * 1024;
+
+/// Size of the buffer of the read and write loop
+const BUF_LEN: u64 = 1024 * 1024;
+
+/// Copies `len` bytes of `src` at `src_off` to `dst` at `dst_off`, calling
+/// `progress` with the number of bytes copied so far, and returns the number
+/// of bytes copied.
+///
+/// The copy is done by the kernel with `copy_file_range(2)`, which may share
+/// the data between the files or copy it on the storage device rather than
+/// moving it through memory. io_uring has no such operation, so the system
+/// call runs on the blocking thread pool. Where the kernel can't copy between
+/// the files, failing with `EXDEV`, `ENOSYS` or `EOPNOTSUPP`, e.g. across
+/// filesystems on older kernels, the rest is copied with reads and writes
+/// through a single buffer of 1 MiB.
+///
+/// `progress` is called after each chunk, of up to 8 MiB when copied by the
+/// kernel and 1 MiB otherwise, e.g. to update a progress bar. Fewer than `len`
+/// bytes are copied if the end of `src` is reached first. The offsets of the
+/// files are left alone, and the ranges must not overlap if both are the same
+/// file.
+///
+/// # Errors
+///
+/// Errors are those of `copy_file_range(2)`, or of the reads and writes. The
+/// number of bytes copied before an error is the last one passed to
+/// `progress`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::{self, File};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let src = File::open("movie.mkv").await?;
+///         let dst = File::create("/mnt/usb/movie.mkv").await?;
+///         let len = src.len().await?;
+///
+///         let copied = fs::copy_range(&src, 0, &dst, 0, len, |copied| {
+///             println!("{}%", copied * 100 / len);
+///         })
+///         .await?;
+///         println!("copied {} bytes", copied);
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn copy_range<F>(
+    src: &File,
+    src_off: u64,
+    dst: &File,
+    dst_off: u64,
+    len: u64,
+    mut progress: F,
+) -> io::Result<u64>
+where
+    F: FnMut(u64),
+{
+    if src_off.checked_add(len).is_none() || dst_off.checked_add(len).is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "range too large for file",
+        ));
+    }
+
+    let mut copied = 0;
+
+    // The blocking calls may outlive the files, so they get their own
+    // descriptors.
+    let files = Arc::new((fallback::dup(&src.fd)?, fallback::dup(&dst.fd)?));
+    while copied < len {
+        let files = files.clone();
+        let chunk = (len - copied).min(KERNEL_CHUNK_LEN) as usize;
+        let mut off_in = (src_off + copied) as libc::loff_t;
+        let mut off_out = (dst_off + copied) as libc::loff_t;
+
+        let res = asyncify(move || {
+            syscall!(copy_file_range(
+                files.0.as_raw_fd(),
+                &mut off_in,
+                files.1.as_raw_fd(),
+                &mut off_out,
+                chunk,
+                0
+            ))
+        })
+        .await;
+        match res {
+            Ok(0) => return Ok(copied),
+            Ok(n) => {
+                copied += n as u64;
+                progress(copied);
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => match e.raw_os_error() {
+                Some(libc::EXDEV) | Some(libc::ENOSYS) | Some(libc::EOPNOTSUPP) => break,
+                _ => return Err(e),
+            },
+        }
+    }
+
+    let mut buf = Vec::with_capacity((len - copied).min(BUF_LEN) as usize);
+    while copied < len {
+        let want = (len - copied).min(BUF_LEN) as usize;
+        buf.clear();
+        let (res, slice) = src.read_at(buf.slice(..want), src_off + copied).await;
+        buf = slice.into_inner();
+        let n = match res {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        let (res, slice) = dst.write_all_at(buf.slice(..n), dst_off + copied).await;
+        buf = slice.into_inner();
+        res?;
+        copied += n as u64;
+        progress(copied);
+    }
+
+    Ok(copied)
+}
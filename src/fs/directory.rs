@@ -1,5 +1,8 @@
 use crate::driver::Op;
+use crate::fs::fallback;
+use crate::runtime;
 
+use std::fs::DirEntry;
 use std::io;
 use std::path::Path;
 
@@ -19,5 +22,88 @@ use std::path::Path;
 /// }
 /// ```
 pub async fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    if runtime::is_fallback() {
+        return fallback::remove_dir(path.as_ref()).await;
+    }
+
     Op::unlink_dir(path.as_ref())?.await
 }
+
+/// Returns the entries of a directory, sorted by file name.
+///
+/// The order in which a directory lists its entries depends on the
+/// filesystem, and may change as entries are added or removed. Sorting the
+/// entries gives a deterministic order, e.g. to process files in order or to
+/// produce reproducible output. As with [`std::fs::read_dir`], the `.` and
+/// `..` entries are skipped.
+///
+/// io_uring has no operation to list a directory, so it is read on Tokio's
+/// blocking thread pool.
+///
+/// All the entries are collected before being sorted, so memory grows with
+/// the size of the directory: each entry holds its file name, on top of a few
+/// dozen bytes. To only keep some entries of a large directory, use
+/// [`read_dir_filtered`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::read_dir_sorted;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         for entry in read_dir_sorted("/etc").await? {
+///             println!("{}", entry.file_name().to_string_lossy());
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn read_dir_sorted<P: AsRef<Path>>(path: P) -> io::Result<Vec<DirEntry>> {
+    read_dir_filtered(path, |_| true).await
+}
+
+/// Returns the entries of a directory for which `predicate` returns `true`,
+/// sorted by file name.
+///
+/// This behaves like [`read_dir_sorted`], except that `predicate` is called
+/// on each entry as the directory is read, on the blocking thread pool, and
+/// only the entries it accepts are kept. Memory thus grows with the number of
+/// matching entries, rather than with the size of the directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::read_dir_filtered;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let segments = read_dir_filtered("/var/lib/wal", |entry| {
+///             entry.path().extension().map_or(false, |ext| ext == "log")
+///         })
+///         .await?;
+///
+///         println!("{} segments", segments.len());
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn read_dir_filtered<P, F>(path: P, mut predicate: F) -> io::Result<Vec<DirEntry>>
+where
+    P: AsRef<Path>,
+    F: FnMut(&DirEntry) -> bool + Send + 'static,
+{
+    let path = path.as_ref().to_owned();
+    crate::util::asyncify(move || {
+        let mut entries = vec![];
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            if predicate(&entry) {
+                entries.push(entry);
+            }
+        }
+        entries.sort_unstable_by_key(DirEntry::file_name);
+        Ok(entries)
+    })
+    .await
+}
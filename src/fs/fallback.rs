@@ -0,0 +1,225 @@
+//! Blocking implementations of the file operations, used on a runtime
+//! without io_uring, as started by [`start_or_fallback`].
+//!
+//! The system calls run on the blocking thread pool. As the buffers need not
+//! be `Send`, data is copied between them and a temporary buffer moved to the
+//! pool. Each operation works on a duplicate of the file descriptor, so the
+//! file may be closed while the blocking call is running.
+//!
+//! [`start_or_fallback`]: crate::start_or_fallback
+
+use crate::buf::{IoBuf, IoBufMut};
+use crate::driver::{self, SharedFd};
+use crate::fs::{File, OpenOptions};
+use crate::util::asyncify;
+
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::Path;
+
+pub(crate) fn dup(fd: &SharedFd) -> io::Result<std::fs::File> {
+    let fd = syscall!(fcntl(fd.raw_fd(), libc::F_DUPFD_CLOEXEC, 0))?;
+    Ok(unsafe { std::fs::File::from_raw_fd(fd) })
+}
+
+pub(crate) async fn open(path: &Path, options: &OpenOptions) -> io::Result<File> {
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let flags = libc::O_CLOEXEC | driver::open_flags(options)?;
+    let mode = options.mode;
+
+    let fd = asyncify(move || syscall!(open(path.as_ptr(), flags, mode))).await?;
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+pub(crate) async fn read_at<T: IoBufMut>(
+    fd: &SharedFd,
+    buf: T,
+    pos: u64,
+) -> crate::BufResult<usize, T> {
+    read_with(fd, buf, move |file, data| file.read_at(data, pos)).await
+}
+
+/// Read at the file position, which is shared with the duplicate.
+pub(crate) async fn read<T: IoBufMut>(fd: &SharedFd, buf: T) -> crate::BufResult<usize, T> {
+    read_with(fd, buf, |file, data| io::Read::read(&mut &*file, data)).await
+}
+
+async fn read_with<T, F>(fd: &SharedFd, mut buf: T, f: F) -> crate::BufResult<usize, T>
+where
+    T: IoBufMut,
+    F: FnOnce(&std::fs::File, &mut [u8]) -> io::Result<usize> + Send + 'static,
+{
+    let file = match dup(fd) {
+        Ok(file) => file,
+        Err(e) => return (Err(e), buf),
+    };
+    let len = buf.bytes_total();
+
+    let res = asyncify(move || {
+        let mut data = vec![0; len];
+        let n = f(&file, &mut data)?;
+        data.truncate(n);
+        Ok::<_, io::Error>(data)
+    })
+    .await;
+
+    match res {
+        Ok(data) => {
+            // Safety: `data` is no longer than the buffer, and initializes
+            // as many bytes.
+            unsafe {
+                std::ptr::copy_nonoverlapping(data.as_ptr(), buf.stable_mut_ptr(), data.len());
+                buf.set_init(data.len());
+            }
+            (Ok(data.len()), buf)
+        }
+        Err(e) => (Err(e), buf),
+    }
+}
+
+pub(crate) async fn write_at<T: IoBuf>(
+    fd: &SharedFd,
+    buf: T,
+    pos: u64,
+) -> crate::BufResult<usize, T> {
+    let file = match dup(fd) {
+        Ok(file) => file,
+        Err(e) => return (Err(e), buf),
+    };
+    let data = crate::buf::deref(&buf).to_vec();
+
+    let res = asyncify(move || file.write_at(&data, pos)).await;
+    (res, buf)
+}
+
+pub(crate) async fn sync(fd: &SharedFd, data_only: bool) -> io::Result<()> {
+    let file = dup(fd)?;
+    asyncify(move || {
+        if data_only {
+            file.sync_data()
+        } else {
+            file.sync_all()
+        }
+    })
+    .await
+}
+
+pub(crate) async fn fadvise(fd: &SharedFd, offset: u64, len: u64, advice: i32) -> io::Result<()> {
+    let file = dup(fd)?;
+    asyncify(move || {
+        // `posix_fadvise` returns the error rather than setting `errno`.
+        match unsafe {
+            libc::posix_fadvise(
+                file.as_raw_fd(),
+                offset as libc::off_t,
+                len as libc::off_t,
+                advice,
+            )
+        } {
+            0 => Ok(()),
+            e => Err(io::Error::from_raw_os_error(e)),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn sync_file_range(
+    fd: &SharedFd,
+    offset: u64,
+    len: u64,
+    flags: u32,
+) -> io::Result<()> {
+    let file = dup(fd)?;
+    asyncify(move || {
+        syscall!(sync_file_range(
+            file.as_raw_fd(),
+            offset as libc::off64_t,
+            len as libc::off64_t,
+            flags
+        ))?;
+        Ok(())
+    })
+    .await
+}
+
+pub(crate) async fn fallocate(fd: &SharedFd, offset: u64, len: u64, mode: i32) -> io::Result<()> {
+    let file = dup(fd)?;
+    asyncify(move || {
+        syscall!(fallocate(
+            file.as_raw_fd(),
+            mode,
+            offset as libc::off_t,
+            len as libc::off_t
+        ))?;
+        Ok(())
+    })
+    .await
+}
+
+pub(crate) async fn statx(fd: &SharedFd, mask: u32, flags: i32) -> io::Result<libc::statx> {
+    let file = dup(fd)?;
+    asyncify(move || {
+        let mut statx = std::mem::MaybeUninit::uninit();
+        syscall!(statx(
+            file.as_raw_fd(),
+            b"\0".as_ptr().cast(),
+            flags | libc::AT_EMPTY_PATH,
+            mask,
+            statx.as_mut_ptr()
+        ))?;
+        Ok(unsafe { statx.assume_init() })
+    })
+    .await
+}
+
+pub(crate) async fn statx_path(path: &Path, mask: u32, flags: i32) -> io::Result<libc::statx> {
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    asyncify(move || {
+        let mut statx = std::mem::MaybeUninit::uninit();
+        syscall!(statx(
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            flags,
+            mask,
+            statx.as_mut_ptr()
+        ))?;
+        Ok(unsafe { statx.assume_init() })
+    })
+    .await
+}
+
+pub(crate) async fn remove_file(path: &Path) -> io::Result<()> {
+    let path = path.to_owned();
+    asyncify(move || std::fs::remove_file(path)).await
+}
+
+pub(crate) async fn remove_dir(path: &Path) -> io::Result<()> {
+    let path = path.to_owned();
+    asyncify(move || std::fs::remove_dir(path)).await
+}
+
+pub(crate) async fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    let (from, to) = (from.to_owned(), to.to_owned());
+    asyncify(move || std::fs::rename(from, to)).await
+}
+
+pub(crate) async fn rename_with_flags(from: &Path, to: &Path, flags: u32) -> io::Result<()> {
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+    asyncify(move || {
+        // Not every libc provides `renameat2`.
+        syscall!(syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            from.as_ptr(),
+            libc::AT_FDCWD,
+            to.as_ptr(),
+            flags
+        ))?;
+        Ok(())
+    })
+    .await
+}
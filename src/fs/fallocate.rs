@@ -0,0 +1,52 @@
+/// How [`File::fallocate`] manipulates the space of a file range.
+///
+/// [`File::fallocate`]: crate::fs::File::fallocate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FallocateMode {
+    /// Allocates the space of the range, extending the file if the range ends
+    /// past its end. Data already in the range is left untouched, and newly
+    /// allocated space reads as zeros.
+    Default,
+
+    /// Zeroes the range, keeping its space allocated, so later writes to it
+    /// cannot fail with `ENOSPC`. The file is extended if the range ends past
+    /// its end.
+    ZeroRange,
+
+    /// Deallocates the space of the range, which then reads as zeros. The
+    /// size of the file is unchanged.
+    PunchHole,
+
+    /// Removes the range from the file, shifting the data after it down and
+    /// shrinking the file. The range must be aligned to the filesystem block
+    /// size and end before the end of the file.
+    CollapseRange,
+
+    /// Inserts a hole of the size of the range at its offset, shifting the
+    /// data after it up and growing the file. The range must be aligned to
+    /// the filesystem block size and start before the end of the file.
+    InsertRange,
+}
+
+impl FallocateMode {
+    /// The `FALLOC_FL_*` flags of the mode.
+    pub(crate) fn bits(self) -> libc::c_int {
+        match self {
+            FallocateMode::Default => 0,
+            FallocateMode::ZeroRange => libc::FALLOC_FL_ZERO_RANGE,
+            // The kernel rejects punching a hole which could change the size.
+            FallocateMode::PunchHole => libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE,
+            FallocateMode::CollapseRange => libc::FALLOC_FL_COLLAPSE_RANGE,
+            FallocateMode::InsertRange => libc::FALLOC_FL_INSERT_RANGE,
+        }
+    }
+
+    /// Returns `true` if the range must be aligned to the filesystem block
+    /// size.
+    pub(crate) fn is_block_aligned(self) -> bool {
+        matches!(
+            self,
+            FallocateMode::CollapseRange | FallocateMode::InsertRange
+        )
+    }
+}
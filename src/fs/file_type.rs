@@ -0,0 +1,50 @@
+use std::io;
+
+/// The type of a file, as reported by [`File::file_type`].
+///
+/// [`File::file_type`]: crate::fs::File::file_type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileType {
+    /// A regular file (`S_IFREG`).
+    Regular,
+
+    /// A directory (`S_IFDIR`).
+    Directory,
+
+    /// A character device, such as a terminal (`S_IFCHR`).
+    CharDevice,
+
+    /// A block device (`S_IFBLK`).
+    BlockDevice,
+
+    /// A pipe or FIFO (`S_IFIFO`).
+    Fifo,
+
+    /// A socket (`S_IFSOCK`).
+    Socket,
+
+    /// A symbolic link (`S_IFLNK`), only seen through descriptors opened with
+    /// `O_PATH | O_NOFOLLOW`.
+    Symlink,
+}
+
+impl FileType {
+    /// The type of a file with the `st_mode` bits `mode`.
+    pub(crate) fn from_mode(mode: u32) -> io::Result<FileType> {
+        Ok(match mode & libc::S_IFMT {
+            libc::S_IFREG => FileType::Regular,
+            libc::S_IFDIR => FileType::Directory,
+            libc::S_IFCHR => FileType::CharDevice,
+            libc::S_IFBLK => FileType::BlockDevice,
+            libc::S_IFIFO => FileType::Fifo,
+            libc::S_IFSOCK => FileType::Socket,
+            libc::S_IFLNK => FileType::Symlink,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unknown file type",
+                ))
+            }
+        })
+    }
+}
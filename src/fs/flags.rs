@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// The status flags of an open file, such as `O_NONBLOCK` or `O_APPEND`.
+///
+/// Returned by [`File::get_flags`].
+///
+/// [`File::get_flags`]: crate::fs::File::get_flags
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FileFlags(libc::c_int);
+
+impl FileFlags {
+    pub(crate) fn from_bits(bits: libc::c_int) -> FileFlags {
+        FileFlags(bits)
+    }
+
+    /// Returns the raw flags, as returned by `fcntl(F_GETFL)`.
+    pub fn bits(&self) -> libc::c_int {
+        self.0
+    }
+
+    /// Returns `true` if the file is in nonblocking mode (`O_NONBLOCK`).
+    pub fn is_nonblocking(&self) -> bool {
+        self.0 & libc::O_NONBLOCK != 0
+    }
+
+    /// Returns `true` if every write appends to the end of the file
+    /// (`O_APPEND`).
+    pub fn is_append(&self) -> bool {
+        self.0 & libc::O_APPEND != 0
+    }
+
+    /// Returns `true` if I/O bypasses the page cache (`O_DIRECT`).
+    pub fn is_direct(&self) -> bool {
+        self.0 & libc::O_DIRECT != 0
+    }
+
+    /// Returns `true` if the file was opened for reading.
+    pub fn is_readable(&self) -> bool {
+        !self.is_path_only() && matches!(self.0 & libc::O_ACCMODE, libc::O_RDONLY | libc::O_RDWR)
+    }
+
+    /// Returns `true` if the file was opened for writing.
+    pub fn is_writable(&self) -> bool {
+        matches!(self.0 & libc::O_ACCMODE, libc::O_WRONLY | libc::O_RDWR)
+    }
+
+    /// Returns `true` if the file was opened without I/O rights, only as a
+    /// reference to the file (`O_PATH`).
+    pub fn is_path_only(&self) -> bool {
+        self.0 & libc::O_PATH != 0
+    }
+}
+
+impl fmt::Debug for FileFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileFlags")
+            .field("readable", &self.is_readable())
+            .field("writable", &self.is_writable())
+            .field("nonblocking", &self.is_nonblocking())
+            .field("append", &self.is_append())
+            .field("direct", &self.is_direct())
+            .field("path_only", &self.is_path_only())
+            .finish()
+    }
+}
@@ -1,12 +1,54 @@
 //! Filesystem manipulation operations.
 
+mod append_file;
+pub use append_file::AppendFile;
+
+mod copy_range;
+pub use copy_range::copy_range;
+
 mod directory;
-pub use directory::remove_dir;
+pub use directory::{read_dir_filtered, read_dir_sorted, remove_dir};
+
+mod fallback;
+
+mod fallocate;
+pub use fallocate::FallocateMode;
 
 mod file;
+pub use file::read_small;
+pub use file::read_to_string;
 pub use file::remove_file;
+pub use file::remove_files;
 pub use file::rename;
+pub use file::rename_with_flags;
+pub use file::statx;
+pub use file::sync_all_of;
+pub use file::write_atomic;
 pub use file::File;
 
+mod file_type;
+pub use file_type::FileType;
+
+mod flags;
+pub use flags::FileFlags;
+
 mod open_options;
 pub use open_options::OpenOptions;
+
+mod registered_file;
+pub use registered_file::RegisteredFile;
+
+mod rename_flags;
+pub use rename_flags::RenameFlags;
+
+mod replace;
+pub use replace::{replace_if_unchanged, replace_if_unchanged_locked, Conflict};
+
+mod statfs;
+pub use statfs::{statvfs, StatFs};
+
+mod vectored_write;
+pub use vectored_write::VectoredWriteError;
+
+mod write_hint;
+pub use write_hint::WriteLifeHint;
@@ -1,5 +1,6 @@
 use crate::driver::Op;
-use crate::fs::File;
+use crate::fs::{fallback, File, RegisteredFile};
+use crate::runtime;
 
 use std::io;
 use std::os::unix::fs::OpenOptionsExt;
@@ -60,8 +61,11 @@ pub struct OpenOptions {
     write: bool,
     append: bool,
     truncate: bool,
+    truncate_to: Option<u64>,
     create: bool,
     create_new: bool,
+    path_only: bool,
+    advice: Option<i32>,
     pub(crate) mode: libc::mode_t,
     pub(crate) custom_flags: libc::c_int,
 }
@@ -93,8 +97,11 @@ impl OpenOptions {
             write: false,
             append: false,
             truncate: false,
+            truncate_to: None,
             create: false,
             create_new: false,
+            path_only: false,
+            advice: None,
             mode: 0o666,
             custom_flags: 0,
         }
@@ -214,6 +221,52 @@ impl OpenOptions {
     /// ```
     pub fn truncate(&mut self, truncate: bool) -> &mut OpenOptions {
         self.truncate = truncate;
+        if truncate {
+            self.truncate_to = None;
+        }
+        self
+    }
+
+    /// Sets the option for truncating, or extending, a previous file to
+    /// `size` bytes.
+    ///
+    /// This is like [`truncate`], but preserves the first `size` bytes of the
+    /// file, e.g. to keep the header of a log file while discarding its body.
+    /// The file is opened without `O_TRUNC`, then its size is set with
+    /// [`File::set_len`]. The resize must complete after the open, so the two
+    /// operations cannot be submitted together.
+    ///
+    /// The file is only returned once it has been resized, so all operations
+    /// on it are ordered after the resize. If the resize fails, the file is
+    /// closed and the error is returned, but it stays created if `create` or
+    /// `create_new` caused it to be.
+    ///
+    /// This overrides any previous call to [`truncate`], and vice versa. The
+    /// file must be opened with write access for `truncate_to` to work.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::fs::OpenOptions;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         // Keep the 64 byte header
+    ///         let file = OpenOptions::new()
+    ///             .write(true)
+    ///             .truncate_to(64)
+    ///             .open("app.log")
+    ///             .await?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`truncate`]: OpenOptions::truncate
+    /// [`File::set_len`]: crate::fs::File::set_len
+    pub fn truncate_to(&mut self, size: u64) -> &mut OpenOptions {
+        self.truncate = false;
+        self.truncate_to = Some(size);
         self
     }
 
@@ -282,6 +335,126 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the option to only obtain a reference to the file, without I/O
+    /// rights (`O_PATH`).
+    ///
+    /// The file is neither read nor written, so no permission on it is
+    /// required, and neither `read` nor `write` need be set. The resulting
+    /// [`File`] holds on to the file itself, rather than to a path which may
+    /// be swapped for another file in the meantime: its metadata can be
+    /// queried, e.g. with [`File::file_type`], and a path-only directory
+    /// serves as the base of `*at` system calls through its raw descriptor.
+    ///
+    /// Reading from or writing to a path-only file, as well as syncing it,
+    /// fails with `EBADF`. [`FileFlags::is_path_only`] tells such files
+    /// apart, e.g. when received with [`File::from_raw_fd`].
+    ///
+    /// This option conflicts with `write`, `append`, `truncate`, `create` and
+    /// `create_new`, and opening fails with an error of kind
+    /// [`InvalidInput`] if any of them is set as well.
+    ///
+    /// [`File::file_type`]: crate::fs::File::file_type
+    /// [`FileFlags::is_path_only`]: crate::fs::FileFlags::is_path_only
+    /// [`File::from_raw_fd`]: std::os::unix::io::FromRawFd::from_raw_fd
+    /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::fs::OpenOptions;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let file = OpenOptions::new()
+    ///             .path_only(true)
+    ///             .open("/etc/shadow")
+    ///             .await?;
+    ///         println!("{:?}", file.file_type().await?);
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn path_only(&mut self, path_only: bool) -> &mut OpenOptions {
+        self.path_only = path_only;
+        self
+    }
+
+    /// Sets the option to advise the kernel that the file will be read
+    /// sequentially, with `POSIX_FADV_SEQUENTIAL`.
+    ///
+    /// The kernel then reads ahead more aggressively, which speeds up reading
+    /// a file from start to end. The advice is given right after the file is
+    /// opened, before it is returned, so it applies to the first read.
+    ///
+    /// The advice is only a hint: if it can't be given, e.g. to a pipe, the
+    /// file is still opened and returned as usual.
+    ///
+    /// This overrides any previous call to [`random`], and vice versa.
+    ///
+    /// [`random`]: OpenOptions::random
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::fs::OpenOptions;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let file = OpenOptions::new()
+    ///             .read(true)
+    ///             .sequential(true)
+    ///             .open("backup.tar")
+    ///             .await?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn sequential(&mut self, sequential: bool) -> &mut OpenOptions {
+        self.set_advice(libc::POSIX_FADV_SEQUENTIAL, sequential);
+        self
+    }
+
+    /// Sets the option to advise the kernel that the file will be read at
+    /// random offsets, with `POSIX_FADV_RANDOM`.
+    ///
+    /// The kernel then disables readahead, which would otherwise fill the
+    /// page cache with data that is never read, e.g. for the lookups of a
+    /// database index. As with [`sequential`], the advice is given before the
+    /// file is returned, and failing to give it does not fail the open.
+    ///
+    /// This overrides any previous call to [`sequential`], and vice versa.
+    ///
+    /// [`sequential`]: OpenOptions::sequential
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::fs::OpenOptions;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let file = OpenOptions::new()
+    ///             .read(true)
+    ///             .random(true)
+    ///             .open("index.db")
+    ///             .await?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn random(&mut self, random: bool) -> &mut OpenOptions {
+        self.set_advice(libc::POSIX_FADV_RANDOM, random);
+        self
+    }
+
+    fn set_advice(&mut self, advice: i32, set: bool) {
+        if set {
+            self.advice = Some(advice);
+        } else if self.advice == Some(advice) {
+            self.advice = None;
+        }
+    }
+
     /// Opens a file at `path` with the options specified by `self`.
     ///
     /// # Errors
@@ -333,10 +506,64 @@ impl OpenOptions {
     /// [`Other`]: io::ErrorKind::Other
     /// [`PermissionDenied`]: io::ErrorKind::PermissionDenied
     pub async fn open(&self, path: impl AsRef<Path>) -> io::Result<File> {
-        Op::open(path.as_ref(), self)?.await
+        #[allow(unused_mut)]
+        let mut file = if runtime::is_fallback() {
+            fallback::open(path.as_ref(), self).await?
+        } else {
+            Op::open(path.as_ref(), self)?.await?
+        };
+
+        #[cfg(debug_assertions)]
+        if self.custom_flags & libc::O_DIRECT != 0 {
+            file.load_direct_align().await;
+        }
+
+        if let Some(size) = self.truncate_to {
+            if let Err(e) = file.set_len(size).await {
+                let _ = file.close().await;
+                return Err(e);
+            }
+        }
+
+        if let Some(advice) = self.advice {
+            // The advice is only a hint, which e.g. pipes do not take.
+            let _res = file.fadvise(0, 0, advice).await;
+            #[cfg(feature = "tracing")]
+            if let Err(error) = _res {
+                tracing::debug!(%error, advice, "failed to advise the access pattern");
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Opens a file at `path` as a direct descriptor, registered in the ring's
+    /// fixed file table, with the options specified by `self`.
+    ///
+    /// See [`RegisteredFile`] for the differences with a regular file. The
+    /// `O_CLOEXEC` flag does not apply to direct descriptors, which are never
+    /// inherited.
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of [`open`], this fails if no slot of the fixed file
+    /// table is available: all of them are in use, the kernel does not
+    /// support direct descriptors, which requires Linux 5.15, or the runtime
+    /// runs without io_uring.
+    ///
+    /// [`open`]: OpenOptions::open
+    pub async fn open_registered(&self, path: impl AsRef<Path>) -> io::Result<RegisteredFile> {
+        RegisteredFile::open(path.as_ref(), self).await
     }
 
     pub(crate) fn access_mode(&self) -> io::Result<libc::c_int> {
+        if self.path_only {
+            return match (self.write, self.append) {
+                (false, false) => Ok(libc::O_PATH),
+                _ => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+            };
+        }
+
         match (self.read, self.write, self.append) {
             (true, false, false) => Ok(libc::O_RDONLY),
             (false, true, false) => Ok(libc::O_WRONLY),
@@ -351,7 +578,7 @@ impl OpenOptions {
         match (self.write, self.append) {
             (true, false) => {}
             (false, false) => {
-                if self.truncate || self.create || self.create_new {
+                if self.truncate || self.truncate_to.is_some() || self.create || self.create_new {
                     return Err(io::Error::from_raw_os_error(libc::EINVAL));
                 }
             }
@@ -0,0 +1,166 @@
+use crate::buf::{IoBuf, IoBufMut};
+use crate::driver::{self, FixedSlot, Op, SharedFd};
+use crate::fs::{File, OpenOptions};
+use crate::runtime::CONTEXT;
+
+use io_uring::types::FsyncFlags;
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::FromRawFd;
+use std::path::Path;
+
+/// A file opened as a direct descriptor, registered in the ring's fixed file
+/// table instead of the process file table.
+///
+/// Direct descriptors are only known to the ring, which saves the kernel
+/// looking up the file on each operation. When a regular file descriptor is
+/// needed again, e.g. to hand the file to another API, [`install`] creates
+/// one. An already open [`File`] is registered with [`File::register`].
+///
+/// The file is closed when dropped, which releases its slot of the table
+/// once the kernel closed it. The table has a limited number of slots, shared
+/// by the whole runtime.
+///
+/// [`install`]: RegisteredFile::install
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::OpenOptions;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let registered = OpenOptions::new()
+///             .read(true)
+///             .open_registered("hello.txt")
+///             .await?;
+///
+///         let (res, buf) = registered.read_at(vec![0; 4096], 0).await;
+///         println!("{:?}", &buf[..res?]);
+///         Ok(())
+///     })
+/// }
+/// ```
+pub struct RegisteredFile {
+    /// Slot of the direct descriptor, taken by `close`
+    slot: Option<FixedSlot>,
+}
+
+impl RegisteredFile {
+    pub(crate) async fn open(path: &Path, options: &OpenOptions) -> io::Result<RegisteredFile> {
+        let slot = driver::fixed_slot()
+            .ok_or_else(|| io::Error::other("no slot of the fixed file table is available"))?;
+
+        Op::open_direct(path, options, &slot)?.await?;
+
+        Ok(RegisteredFile { slot: Some(slot) })
+    }
+
+    pub(crate) fn register(fd: &SharedFd) -> io::Result<RegisteredFile> {
+        let slot = driver::register_fd(fd.raw_fd())?;
+
+        Ok(RegisteredFile { slot: Some(slot) })
+    }
+
+    /// Returns the index of the file in the fixed file table.
+    pub fn index(&self) -> u32 {
+        self.slot().index()
+    }
+
+    /// Read some bytes at the specified offset from the file into the
+    /// specified buffer, returning how many bytes were read.
+    ///
+    /// This behaves like [`File::read_at`], through the direct descriptor.
+    pub async fn read_at<T: IoBufMut>(&self, buf: T, pos: u64) -> crate::BufResult<usize, T> {
+        match Op::read_direct(self.slot(), buf, pos) {
+            Ok(op) => op.await,
+            Err((e, buf)) => (Err(e), buf),
+        }
+    }
+
+    /// Write a buffer into this file at the specified offset, returning how
+    /// many bytes were written.
+    ///
+    /// This behaves like [`File::write_at`], through the direct descriptor.
+    pub async fn write_at<T: IoBuf>(&self, buf: T, pos: u64) -> crate::BufResult<usize, T> {
+        match Op::write_direct(self.slot(), buf, pos) {
+            Ok(op) => op.await,
+            Err((e, buf)) => (Err(e), buf),
+        }
+    }
+
+    /// Attempts to sync all OS-internal metadata to disk.
+    ///
+    /// This behaves like [`File::sync_all`], through the direct descriptor.
+    pub async fn sync_all(&self) -> io::Result<()> {
+        Op::fsync_direct(self.slot(), FsyncFlags::empty())?.await
+    }
+
+    /// Attempts to sync file data to disk.
+    ///
+    /// This behaves like [`File::sync_data`], through the direct descriptor.
+    pub async fn sync_data(&self) -> io::Result<()> {
+        Op::fsync_direct(self.slot(), FsyncFlags::DATASYNC)?.await
+    }
+
+    /// Installs the direct descriptor into the process file table, returning
+    /// it as a regular [`File`].
+    ///
+    /// The returned file is independent of the registered one, which remains
+    /// open: each must be closed on its own. The new file descriptor has
+    /// `O_CLOEXEC` set.
+    ///
+    /// This requires `IORING_OP_FIXED_FD_INSTALL`, added in Linux 6.8, and
+    /// fails with an error of kind [`Unsupported`] on older kernels.
+    ///
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    pub async fn install(&self) -> io::Result<File> {
+        if !crate::probe()?.is_supported(driver::IORING_OP_FIXED_FD_INSTALL) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "installing direct descriptors is not supported by the kernel",
+            ));
+        }
+
+        let fd = Op::fixed_fd_install(self.slot())?.await?;
+
+        // Safety: the kernel just created the file descriptor for us.
+        Ok(unsafe { File::from_raw_fd(fd) })
+    }
+
+    /// Closes the direct descriptor, releasing its slot of the fixed file
+    /// table.
+    ///
+    /// Dropping the file also closes it, but without a way to observe errors.
+    pub async fn close(mut self) -> io::Result<()> {
+        let slot = self.slot.take().expect("slot is held until closed");
+        Op::close_direct(slot)?.await
+    }
+
+    fn slot(&self) -> &FixedSlot {
+        self.slot.as_ref().expect("slot is held until closed")
+    }
+}
+
+impl fmt::Debug for RegisteredFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegisteredFile")
+            .field("index", &self.slot.as_ref().map(FixedSlot::index))
+            .finish()
+    }
+}
+
+impl Drop for RegisteredFile {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot.take() {
+            // The slot is held by the operation until the kernel closed the
+            // descriptor. Off the runtime, the table is gone along with the
+            // descriptor. While the driver is in use, the descriptor is left
+            // in the slot, and replaced once the slot is used again.
+            if let Ok(true) = CONTEXT.try_with(|cx| cx.is_set()) {
+                let _ = Op::close_direct(slot);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::ops;
+
+/// Flags changing how [`rename_with_flags`] renames a file, as passed to
+/// `renameat2(2)`.
+///
+/// Flags are combined with `|`. [`EXCHANGE`] cannot be combined with the
+/// other flags.
+///
+/// [`rename_with_flags`]: crate::fs::rename_with_flags
+/// [`EXCHANGE`]: RenameFlags::EXCHANGE
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenameFlags(u32);
+
+impl RenameFlags {
+    /// Fails with an error of kind [`AlreadyExists`] instead of replacing the
+    /// destination (`RENAME_NOREPLACE`).
+    ///
+    /// [`AlreadyExists`]: std::io::ErrorKind::AlreadyExists
+    pub const NOREPLACE: RenameFlags = RenameFlags(libc::RENAME_NOREPLACE);
+
+    /// Atomically exchanges the source and the destination, which must both
+    /// exist (`RENAME_EXCHANGE`).
+    pub const EXCHANGE: RenameFlags = RenameFlags(libc::RENAME_EXCHANGE);
+
+    /// Atomically leaves a whiteout in place of the source
+    /// (`RENAME_WHITEOUT`).
+    ///
+    /// A whiteout is a character device with device number 0/0, which union
+    /// filesystems such as overlayfs interpret as the removal of the file from
+    /// the lower layers. This requires the `CAP_MKNOD` capability and a
+    /// filesystem supporting whiteouts, such as ext4, xfs, btrfs, tmpfs or
+    /// overlayfs itself.
+    pub const WHITEOUT: RenameFlags = RenameFlags(libc::RENAME_WHITEOUT);
+
+    /// Returns the flags of a plain rename, replacing the destination.
+    pub const fn empty() -> RenameFlags {
+        RenameFlags(0)
+    }
+
+    /// Returns the raw `RENAME_*` flags.
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if all flags of `other` are set.
+    pub const fn contains(&self, other: RenameFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for RenameFlags {
+    type Output = RenameFlags;
+
+    fn bitor(self, rhs: RenameFlags) -> RenameFlags {
+        RenameFlags(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for RenameFlags {
+    fn bitor_assign(&mut self, rhs: RenameFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Debug for RenameFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenameFlags")
+            .field("noreplace", &self.contains(RenameFlags::NOREPLACE))
+            .field("exchange", &self.contains(RenameFlags::EXCHANGE))
+            .field("whiteout", &self.contains(RenameFlags::WHITEOUT))
+            .finish()
+    }
+}
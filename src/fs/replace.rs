@@ -0,0 +1,199 @@
+use crate::buf::IoBuf;
+use crate::driver::Op;
+use crate::fs::file::write_atomic_checked;
+use crate::fs::{fallback, File, OpenOptions};
+use crate::runtime;
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Error returned by [`replace_if_unchanged`] when the file was modified,
+/// replaced or removed since it was last read.
+///
+/// The error is wrapped in an [`io::Error`], from which it is recovered by
+/// downcasting:
+///
+/// ```no_run
+/// use std::time::SystemTime;
+/// use tokio_uring::fs::{replace_if_unchanged, Conflict};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let mtime = std::fs::metadata("config.toml")?.modified()?;
+///
+///         // ... edit the configuration ...
+///
+///         match replace_if_unchanged("config.toml", b"answer = 42\n".to_vec(), mtime).await {
+///             Ok(()) => {}
+///             Err(e) if e.get_ref().is_some_and(|e| e.is::<Conflict>()) => {
+///                 println!("edited concurrently, starting over");
+///             }
+///             Err(e) => return Err(e.into()),
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Conflict {
+    modified: Option<SystemTime>,
+}
+
+impl Conflict {
+    /// Returns the modification time of the file found in place, or `None`
+    /// if the file was removed or replaced.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.modified {
+            Some(_) => write!(f, "file was modified concurrently"),
+            None => write!(f, "file was removed or replaced concurrently"),
+        }
+    }
+}
+
+impl Error for Conflict {}
+
+/// Atomically replaces the contents of a file with `data`, unless it was
+/// modified since `expected_mtime`.
+///
+/// This is [`write_atomic`] with optimistic concurrency: the temporary file is
+/// written and synced first, then the modification time of `path` is
+/// compared to `expected_mtime`, as read along with the previous contents of
+/// the file, and the rename is only submitted if they match. Otherwise,
+/// including if `path` no longer exists, the temporary file is removed and a
+/// [`Conflict`] error is returned.
+///
+/// Writing first keeps the window between the check and the rename as small
+/// as a single operation, but does not close it: a writer may still modify
+/// the file in between, and its changes are then lost. Timestamps also have a
+/// limited granularity, so two writes in a row may leave the same
+/// modification time. Use [`replace_if_unchanged_locked`] among cooperating
+/// writers.
+///
+/// [`write_atomic`]: crate::fs::write_atomic
+pub async fn replace_if_unchanged<T: IoBuf>(
+    path: impl AsRef<Path>,
+    data: T,
+    expected_mtime: SystemTime,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    write_atomic_checked(path, data, async {
+        let statx = match symlink_statx(path).await {
+            Ok(statx) => statx,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(conflict(None)),
+            Err(e) => return Err(e),
+        };
+
+        let modified = mtime(&statx);
+        if modified != expected_mtime {
+            return Err(conflict(Some(modified)));
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Like [`replace_if_unchanged`], holding an open file description lock on
+/// the file from the check until the rename.
+///
+/// The lock is taken with `F_OFD_SETLKW`, waiting for other holders to
+/// release it. Once it is held, the file at `path` must still be the locked
+/// one, and its modification time must match `expected_mtime`. Writers also
+/// using this function are thus serialized, and a writer which waited for the
+/// lock on a file which was replaced in the meantime gets a [`Conflict`]
+/// error. The lock is advisory: writers not taking it are not excluded.
+///
+/// The file must be writable, as taking a write lock requires opening it for
+/// writing.
+pub async fn replace_if_unchanged_locked<T: IoBuf>(
+    path: impl AsRef<Path>,
+    data: T,
+    expected_mtime: SystemTime,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    write_atomic_checked(path, data, async {
+        let file = match OpenOptions::new().write(true).open(path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(conflict(None)),
+            Err(e) => return Err(e),
+        };
+        lock(&file).await?;
+
+        let locked = file.statx(libc::STATX_INO | libc::STATX_MTIME, 0).await?;
+        let current = match symlink_statx(path).await {
+            Ok(statx) => statx,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(conflict(None)),
+            Err(e) => return Err(e),
+        };
+
+        // The file may have been replaced while waiting for the lock.
+        let same_file = (locked.stx_dev_major, locked.stx_dev_minor, locked.stx_ino)
+            == (
+                current.stx_dev_major,
+                current.stx_dev_minor,
+                current.stx_ino,
+            );
+        if !same_file {
+            return Err(conflict(None));
+        }
+
+        let modified = mtime(&current);
+        if modified != expected_mtime {
+            return Err(conflict(Some(modified)));
+        }
+
+        // The lock is released once the file is closed, after the rename.
+        Ok(file)
+    })
+    .await
+}
+
+fn conflict(modified: Option<SystemTime>) -> io::Error {
+    io::Error::other(Conflict { modified })
+}
+
+async fn symlink_statx(path: &Path) -> io::Result<libc::statx> {
+    let mask = libc::STATX_INO | libc::STATX_MTIME;
+    if runtime::is_fallback() {
+        return fallback::statx_path(path, mask, libc::AT_SYMLINK_NOFOLLOW).await;
+    }
+
+    Op::statx_path(path, mask, libc::AT_SYMLINK_NOFOLLOW)?.await
+}
+
+fn mtime(statx: &libc::statx) -> SystemTime {
+    let time = statx.stx_mtime;
+    let nanos = Duration::from_nanos(time.tv_nsec as u64);
+    if time.tv_sec >= 0 {
+        UNIX_EPOCH + Duration::from_secs(time.tv_sec as u64) + nanos
+    } else {
+        UNIX_EPOCH - Duration::from_secs(time.tv_sec.unsigned_abs()) + nanos
+    }
+}
+
+/// Take a write lock on the whole file, waiting for it on the blocking
+/// thread pool.
+async fn lock(file: &File) -> io::Result<()> {
+    // The lock belongs to the open file description, shared with the
+    // duplicate, so it outlives the blocking task.
+    let fd = syscall!(fcntl(file.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0))?;
+    let dup = unsafe { std::fs::File::from_raw_fd(fd) };
+
+    crate::util::asyncify(move || {
+        let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+        lock.l_type = libc::F_WRLCK as _;
+        lock.l_whence = libc::SEEK_SET as _;
+        syscall!(fcntl(dup.as_raw_fd(), libc::F_OFD_SETLKW, &lock))?;
+        Ok(())
+    })
+    .await
+}
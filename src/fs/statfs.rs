@@ -0,0 +1,93 @@
+use crate::util::asyncify;
+
+use std::ffi::CString;
+use std::fmt;
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// Information about a mounted filesystem.
+///
+/// Returned by [`statvfs`] and [`File::statvfs`].
+///
+/// [`File::statvfs`]: crate::fs::File::statvfs
+#[derive(Clone, Copy)]
+pub struct StatFs(libc::statfs);
+
+impl StatFs {
+    /// Returns the optimal transfer block size of the filesystem, in bytes.
+    ///
+    /// Buffers used with `O_DIRECT` should be aligned to this size.
+    pub fn block_size(&self) -> u64 {
+        self.0.f_bsize as u64
+    }
+
+    /// Returns the total number of blocks in the filesystem.
+    pub fn blocks(&self) -> u64 {
+        self.0.f_blocks
+    }
+
+    /// Returns the number of free blocks in the filesystem.
+    pub fn blocks_free(&self) -> u64 {
+        self.0.f_bfree
+    }
+
+    /// Returns the number of free blocks available to unprivileged users.
+    pub fn blocks_available(&self) -> u64 {
+        self.0.f_bavail
+    }
+
+    /// Returns the type of the filesystem, as a magic number such as
+    /// `libc::EXT4_SUPER_MAGIC`.
+    pub fn fs_type(&self) -> u64 {
+        self.0.f_type as u64
+    }
+
+    pub(crate) fn fstatfs(fd: libc::c_int) -> io::Result<StatFs> {
+        let mut buf = MaybeUninit::uninit();
+        syscall!(fstatfs(fd, buf.as_mut_ptr()))?;
+        Ok(StatFs(unsafe { buf.assume_init() }))
+    }
+}
+
+impl fmt::Debug for StatFs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatFs")
+            .field("block_size", &self.block_size())
+            .field("blocks", &self.blocks())
+            .field("blocks_free", &self.blocks_free())
+            .field("blocks_available", &self.blocks_available())
+            .field("fs_type", &self.fs_type())
+            .finish()
+    }
+}
+
+/// Returns information about the filesystem containing `path`.
+///
+/// io_uring has no operation for this, so the `statfs` system call runs on
+/// the blocking thread pool.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::statvfs;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let stat = statvfs("/tmp").await?;
+///         println!("{} bytes free", stat.blocks_available() * stat.block_size());
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn statvfs<P: AsRef<Path>>(path: P) -> io::Result<StatFs> {
+    let path = CString::new(path.as_ref().as_os_str().as_bytes())?;
+
+    asyncify(move || {
+        let mut buf = MaybeUninit::uninit();
+        syscall!(statfs(path.as_ptr(), buf.as_mut_ptr()))?;
+        Ok(StatFs(unsafe { buf.assume_init() }))
+    })
+    .await
+}
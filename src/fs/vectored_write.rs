@@ -0,0 +1,120 @@
+use crate::buf::IoBuf;
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Error returned by [`File::write_at_all_vectored`], locating where the
+/// batch of buffers was interrupted.
+///
+/// The buffers before [`buffers_written`] were written whole, and the first
+/// [`bytes_into_next`] bytes of the following one. The buffers are handed
+/// back, so the write can be resumed from that point.
+///
+/// [`File::write_at_all_vectored`]: crate::fs::File::write_at_all_vectored
+/// [`buffers_written`]: VectoredWriteError::buffers_written
+/// [`bytes_into_next`]: VectoredWriteError::bytes_into_next
+pub struct VectoredWriteError<T> {
+    error: io::Error,
+    buffers_written: usize,
+    bytes_into_next: usize,
+    bufs: Vec<T>,
+}
+
+impl<T: IoBuf> VectoredWriteError<T> {
+    /// Locates the end of the first `written` bytes of `bufs`.
+    pub(crate) fn new(error: io::Error, bufs: Vec<T>, mut written: usize) -> Self {
+        let mut buffers_written = 0;
+        for buf in &bufs {
+            if written < buf.bytes_init() {
+                break;
+            }
+            written -= buf.bytes_init();
+            buffers_written += 1;
+        }
+
+        VectoredWriteError {
+            error,
+            buffers_written,
+            bytes_into_next: written,
+            bufs,
+        }
+    }
+}
+
+impl<T> VectoredWriteError<T> {
+    /// Returns the error which interrupted the write.
+    pub fn error(&self) -> &io::Error {
+        &self.error
+    }
+
+    /// Returns the number of leading buffers which were written whole.
+    pub fn buffers_written(&self) -> usize {
+        self.buffers_written
+    }
+
+    /// Returns the number of bytes written from the buffer following the
+    /// ones written whole.
+    pub fn bytes_into_next(&self) -> usize {
+        self.bytes_into_next
+    }
+
+    /// Consumes the error, returning `(buffers_written, bytes_into_next,
+    /// bufs)`.
+    pub fn into_parts(self) -> (usize, usize, Vec<T>) {
+        (self.buffers_written, self.bytes_into_next, self.bufs)
+    }
+}
+
+impl<T> fmt::Debug for VectoredWriteError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VectoredWriteError")
+            .field("error", &self.error)
+            .field("buffers_written", &self.buffers_written)
+            .field("bytes_into_next", &self.bytes_into_next)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for VectoredWriteError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} after {} buffers and {} bytes",
+            self.error, self.buffers_written, self.bytes_into_next
+        )
+    }
+}
+
+impl<T> Error for VectoredWriteError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<T> From<VectoredWriteError<T>> for io::Error {
+    fn from(e: VectoredWriteError<T>) -> io::Error {
+        e.error
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locate() {
+        let locate = |written| {
+            let bufs = vec![b"ab".to_vec(), vec![], b"cde".to_vec(), b"f".to_vec()];
+            let e = VectoredWriteError::new(io::Error::other("failed"), bufs, written);
+            (e.buffers_written(), e.bytes_into_next())
+        };
+
+        assert_eq!(locate(0), (0, 0));
+        assert_eq!(locate(1), (0, 1));
+        assert_eq!(locate(2), (2, 0));
+        assert_eq!(locate(4), (2, 2));
+        assert_eq!(locate(5), (3, 0));
+        assert_eq!(locate(6), (4, 0));
+    }
+}
@@ -0,0 +1,74 @@
+/// `F_GET_RW_HINT`, not exported by the `libc` crate.
+pub(crate) const F_GET_RW_HINT: libc::c_int = 1035;
+
+/// `F_SET_RW_HINT`, not exported by the `libc` crate.
+pub(crate) const F_SET_RW_HINT: libc::c_int = 1036;
+
+/// The expected lifetime of the data written to a file, relative to other
+/// files.
+///
+/// Storage which groups data by lifetime, e.g. SSDs supporting write streams,
+/// can use the hint to place data which is overwritten or deleted together,
+/// reducing write amplification. A write-ahead log would be [`Short`], while
+/// the data files it is checkpointed into would be [`Long`].
+///
+/// Set with [`File::set_write_hint`].
+///
+/// [`Short`]: WriteLifeHint::Short
+/// [`Long`]: WriteLifeHint::Long
+/// [`File::set_write_hint`]: crate::fs::File::set_write_hint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteLifeHint {
+    /// No hint was set (`RWH_WRITE_LIFE_NOT_SET`).
+    NotSet,
+
+    /// The data has no particular lifetime (`RWH_WRITE_LIFE_NONE`).
+    NoLifetime,
+
+    /// The data is expected to be short-lived (`RWH_WRITE_LIFE_SHORT`).
+    Short,
+
+    /// The data is expected to live longer than [`Short`] data
+    /// (`RWH_WRITE_LIFE_MEDIUM`).
+    ///
+    /// [`Short`]: WriteLifeHint::Short
+    Medium,
+
+    /// The data is expected to live longer than [`Medium`] data
+    /// (`RWH_WRITE_LIFE_LONG`).
+    ///
+    /// [`Medium`]: WriteLifeHint::Medium
+    Long,
+
+    /// The data is expected to live longer than [`Long`] data
+    /// (`RWH_WRITE_LIFE_EXTREME`).
+    ///
+    /// [`Long`]: WriteLifeHint::Long
+    Extreme,
+}
+
+impl WriteLifeHint {
+    /// The `RWH_WRITE_LIFE_*` value of the hint.
+    pub(crate) fn to_raw(self) -> u64 {
+        match self {
+            WriteLifeHint::NotSet => 0,
+            WriteLifeHint::NoLifetime => 1,
+            WriteLifeHint::Short => 2,
+            WriteLifeHint::Medium => 3,
+            WriteLifeHint::Long => 4,
+            WriteLifeHint::Extreme => 5,
+        }
+    }
+
+    pub(crate) fn from_raw(raw: u64) -> WriteLifeHint {
+        match raw {
+            1 => WriteLifeHint::NoLifetime,
+            2 => WriteLifeHint::Short,
+            3 => WriteLifeHint::Medium,
+            4 => WriteLifeHint::Long,
+            5 => WriteLifeHint::Extreme,
+            // The kernel only stores the values above.
+            _ => WriteLifeHint::NotSet,
+        }
+    }
+}
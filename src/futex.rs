@@ -0,0 +1,129 @@
+//! Futex operations for `tokio-uring`.
+//!
+//! These wait on and wake futexes through the ring, rather than with the
+//! blocking `futex(2)` system call. Synchronization primitives built on them,
+//! such as mutexes or condition variables, park tasks on the same event loop
+//! as their I/O, without blocking the thread.
+//!
+//! Futexes are 32-bit words, private to the process: a [`wake`] through the
+//! ring wakes waiters blocked in `futex(2)` with `FUTEX_PRIVATE_FLAG`, and
+//! conversely. Waiters and wakers are matched by `mask`: a wakeup only wakes
+//! waiters whose mask intersects its own. Pass [`MATCH_ANY`] to match all of
+//! them.
+//!
+//! Futex operations require Linux 6.7, and fail with an error of kind
+//! [`Unsupported`] on older kernels.
+//!
+//! [`Unsupported`]: std::io::ErrorKind::Unsupported
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::sync::atomic::{AtomicU32, Ordering};
+//! use tokio_uring::futex;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     tokio_uring::start(async {
+//!         let ready = std::rc::Rc::new(AtomicU32::new(0));
+//!
+//!         let setter = ready.clone();
+//!         tokio_uring::spawn(async move {
+//!             setter.store(1, Ordering::Release);
+//!             futex::wake(&setter, 1, futex::MATCH_ANY).await.unwrap();
+//!         });
+//!
+//!         while ready.load(Ordering::Acquire) == 0 {
+//!             // The wait returns early if the futex was already set.
+//!             let _ = futex::wait(&ready, 0, futex::MATCH_ANY).await;
+//!         }
+//!         Ok(())
+//!     })
+//! }
+//! ```
+
+use crate::driver::{self, Op};
+use crate::runtime::CONTEXT;
+
+use std::io;
+use std::sync::atomic::AtomicU32;
+
+/// A mask matching every waiter or wakeup.
+pub const MATCH_ANY: u32 = u32::MAX;
+
+/// Waits on `futex` until woken by a [`wake`] whose mask intersects `mask`.
+///
+/// The wait is only queued if `futex` holds `expected` when the kernel picks
+/// up the operation. Otherwise, it fails with an error of kind
+/// [`WouldBlock`], as the value the caller waits for may already have
+/// changed. As with `futex(2)`, callers should check the value again once
+/// woken, in a loop.
+///
+/// Dropping the returned future before it completes cancels the wait, so it
+/// does not consume a wakeup meant for another waiter.
+///
+/// # Errors
+///
+/// Fails with an error of kind [`InvalidInput`] if `mask` is zero, and of
+/// kind [`Unsupported`] on kernels older than 6.7.
+///
+/// [`WouldBlock`]: io::ErrorKind::WouldBlock
+/// [`InvalidInput`]: io::ErrorKind::InvalidInput
+/// [`Unsupported`]: io::ErrorKind::Unsupported
+pub async fn wait(futex: &AtomicU32, expected: u32, mask: u32) -> io::Result<()> {
+    check(driver::IORING_OP_FUTEX_WAIT, mask)?;
+
+    let op = Op::futex_wait(futex.as_ptr(), expected, mask)?;
+    let mut guard = CancelOnDrop(op.index());
+    let res = op.await;
+    guard.0 = None;
+    res
+}
+
+/// Wakes up to `n` tasks or threads waiting on `futex` with a mask
+/// intersecting `mask`, returning how many were woken.
+///
+/// # Errors
+///
+/// Fails with an error of kind [`InvalidInput`] if `mask` is zero, and of
+/// kind [`Unsupported`] on kernels older than 6.7.
+///
+/// [`InvalidInput`]: io::ErrorKind::InvalidInput
+/// [`Unsupported`]: io::ErrorKind::Unsupported
+pub async fn wake(futex: &AtomicU32, n: u32, mask: u32) -> io::Result<usize> {
+    check(driver::IORING_OP_FUTEX_WAKE, mask)?;
+
+    Op::futex_wake(futex.as_ptr(), n, mask)?.await
+}
+
+fn check(opcode: u8, mask: u32) -> io::Result<()> {
+    if mask == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "futex mask matches nothing",
+        ));
+    }
+    if !crate::probe()?.is_supported(opcode) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "futex operations are not supported by the kernel",
+        ));
+    }
+    Ok(())
+}
+
+/// Cancels the wait at the given index, if still in flight once dropped.
+struct CancelOnDrop(Option<usize>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(index) = self.0 {
+            CONTEXT.with(|cx| {
+                if cx.is_set() {
+                    cx.with_driver_mut(|driver| {
+                        let _ = driver.cancel(index);
+                    })
+                }
+            });
+        }
+    }
+}
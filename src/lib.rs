@@ -55,6 +55,27 @@
 //! will happen in the background. There is no guarantee as to **when** the
 //! implicit close-on-drop operation happens, so it is recommended to explicitly
 //! call `close()`.
+//!
+//! # Tracing
+//!
+//! With the `tracing` feature enabled, each operation is traced in a
+//! [`tracing`] span at the `TRACE` level, named `io_uring_op`. The span
+//! carries the operation type (`op`, such as `read`, `write`, `fsync` or
+//! `accept`) and the file descriptor it targets (`fd`, the slot for fixed
+//! files). Once the operation completes, the span records:
+//!
+//! - `queue_wait_us`, the time the entry waited in the submission queue
+//!   before being submitted to the kernel,
+//! - `kernel_us`, the time from the submission to the completion being
+//!   reaped,
+//! - `result`, the raw result of the operation, a negated `errno` on failure,
+//!
+//! and emits an `operation completed` event, then closes. In debug builds,
+//! the files still open when the runtime shuts down, and the operations leaked
+//! once the shutdown timed out, are also reported as `WARN` events. Without
+//! the feature, none of this is compiled in.
+//!
+//! [`tracing`]: https://docs.rs/tracing
 
 #![warn(missing_docs)]
 
@@ -72,15 +93,33 @@ macro_rules! syscall {
 #[macro_use]
 mod future;
 mod driver;
+mod op_set;
+mod retry;
 mod runtime;
 mod util;
 
 pub mod buf;
 pub mod fs;
+pub mod futex;
 pub mod net;
 
+pub use driver::CancelToken;
+pub use driver::Features;
+pub use driver::PollFd;
+pub use driver::Probe;
+pub use driver::RingHandle;
+pub use driver::SqReservation;
+pub use driver::SqStats;
+pub use driver::TaggedCompletion;
+pub use driver::WaitStrategy;
+pub use driver::{ForceAsync, ForceAsyncExt};
+pub use driver::{LinkTimeout, LinkTimeoutExt};
+pub use op_set::OpSet;
+pub use retry::{with_retry, Retry};
 pub use runtime::spawn;
+pub use runtime::Notifier;
 pub use runtime::Runtime;
+pub use runtime::RuntimeError;
 
 use std::future::Future;
 
@@ -142,8 +181,60 @@ use std::future::Future;
 /// }
 /// ```
 pub fn start<F: Future>(future: F) -> F::Output {
-    let rt = runtime::Runtime::new(&builder()).unwrap();
-    rt.block_on(future)
+    builder().start(future)
+}
+
+/// Starts an `io_uring` enabled Tokio runtime, or, if io_uring is not
+/// available, a runtime performing file operations with blocking system calls
+/// on a thread pool.
+///
+/// This behaves like [`start`], unless the ring cannot be created because the
+/// kernel does not support io_uring, or it is blocked, e.g. by a seccomp
+/// filter in a container or the `kernel.io_uring_disabled` sysctl. This lets
+/// libraries use `tokio-uring` on deployment targets without io_uring, with
+/// the same API in both modes.
+///
+/// Without io_uring, the following operations run on Tokio's blocking thread
+/// pool, copying data between the buffers passed in and the pool:
+///
+/// - opening files, with [`File::open`], [`File::create`] and
+///   [`OpenOptions::open`],
+/// - [`File::read_at`] and [`File::write_at`], and the methods built on them,
+///   such as [`File::read_exact_at`] and [`File::write_all_at`],
+/// - [`File::sync_all`] and [`File::sync_data`],
+/// - [`fs::remove_file`], [`fs::remove_dir`] and [`fs::rename`].
+///
+/// Files are closed synchronously. Other operations, including all network
+/// operations, fail with an error of kind [`Unsupported`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start_or_fallback(async {
+///         // Works whether or not io_uring is available
+///         let file = File::open("hello.txt").await?;
+///         let (res, buf) = file.read_at(vec![0; 4096], 0).await;
+///         println!("{:?}", &buf[..res?]);
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// [`File::open`]: fs::File::open
+/// [`File::create`]: fs::File::create
+/// [`OpenOptions::open`]: fs::OpenOptions::open
+/// [`File::read_at`]: fs::File::read_at
+/// [`File::write_at`]: fs::File::write_at
+/// [`File::read_exact_at`]: fs::File::read_exact_at
+/// [`File::write_all_at`]: fs::File::write_all_at
+/// [`File::sync_all`]: fs::File::sync_all
+/// [`File::sync_data`]: fs::File::sync_data
+/// [`Unsupported`]: std::io::ErrorKind::Unsupported
+pub fn start_or_fallback<F: Future>(future: F) -> F::Output {
+    builder().start_or_fallback(future)
 }
 
 /// Create and return an io_uring::Builder that can then be modified
@@ -161,6 +252,17 @@ pub fn uring_builder() -> io_uring::Builder {
 // #[derive(Clone, Default)]
 pub struct Builder {
     entries: u32,
+    cq_entries: Option<u32>,
+    max_cqe_per_tick: usize,
+    max_in_flight: usize,
+    on_tagged_completion: Option<std::sync::Arc<dyn Fn(TaggedCompletion) + Send + Sync>>,
+    on_message: Option<std::sync::Arc<dyn Fn(u64) + Send + Sync>>,
+    sqpoll_cpu: Option<u32>,
+    coop_taskrun: bool,
+    defer_taskrun: bool,
+    idle_timeout: Option<std::time::Duration>,
+    raise_memlock: bool,
+    wait_strategy: WaitStrategy,
     urb: io_uring::Builder,
 }
 
@@ -171,6 +273,17 @@ pub struct Builder {
 pub fn builder() -> Builder {
     Builder {
         entries: 256,
+        cq_entries: None,
+        max_cqe_per_tick: usize::MAX,
+        max_in_flight: usize::MAX,
+        on_tagged_completion: None,
+        on_message: None,
+        sqpoll_cpu: None,
+        coop_taskrun: false,
+        defer_taskrun: false,
+        idle_timeout: None,
+        raise_memlock: false,
+        wait_strategy: WaitStrategy::Block,
         urb: io_uring::IoUring::builder(),
     }
 }
@@ -182,13 +295,356 @@ impl Builder {
     /// The kernel requires the number of completion queue entries to be larger than
     /// the submission queue entries so generally will double the sq entries count.
     ///
-    /// The caller can specify even a larger cq entries count by using the uring_builder
-    /// as shown in the start example below.
+    /// The caller can specify even a larger cq entries count with [`cq_entries`].
+    ///
+    /// The rings are mapped from memory allocated by the kernel, on regular
+    /// pages, so large rings may span many TLB entries. They can't be backed
+    /// with huge pages: that takes `IORING_SETUP_NO_MMAP`, added in Linux 6.5,
+    /// with the rings placed in memory provided by the application, which the
+    /// underlying `io-uring` crate does not support.
+    ///
+    /// [`cq_entries`]: Builder::cq_entries
     pub fn entries(&mut self, e: u32) -> &mut Self {
         self.entries = e;
         self
     }
 
+    /// Set number of completion queue entries in uring
+    /// (`IORING_SETUP_CQSIZE`), independently of the submission queue.
+    ///
+    /// By default, the completion queue has twice as many entries as the
+    /// submission queue. Multishot operations, such as [`poll_fd`], post many
+    /// completions for a single submission, which may arrive faster than
+    /// the runtime reaps them. A larger completion queue keeps them from
+    /// overflowing, see [`Features::nodrop`] for what happens then.
+    ///
+    /// The kernel rounds this up to a power of two. It must be at least the
+    /// number of submission queue entries set with [`entries`]: otherwise,
+    /// [`build`] fails with an error of kind [`InvalidInput`].
+    ///
+    /// [`poll_fd`]: crate::poll_fd
+    /// [`Features::nodrop`]: crate::Features::nodrop
+    /// [`entries`]: Builder::entries
+    /// [`build`]: Builder::build
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tokio_uring::builder()
+    ///     .entries(64)
+    ///     .cq_entries(4096)
+    ///     .start(async {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn cq_entries(&mut self, e: u32) -> &mut Self {
+        self.cq_entries = Some(e);
+        self
+    }
+
+    /// Set the maximum number of completion queue entries reaped at once.
+    ///
+    /// Once this many completions have been processed, the driver yields to
+    /// let the woken tasks run and submit new operations before reaping the
+    /// remaining completions. This keeps a burst of completions from delaying
+    /// submissions, at the cost of more frequent wakeups.
+    ///
+    /// By default, all available completions are reaped at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn max_cqe_per_tick(&mut self, max: usize) -> &mut Self {
+        assert!(max > 0, "at least one completion must be reaped at once");
+        self.max_cqe_per_tick = max;
+        self
+    }
+
+    /// Set the maximum number of in-flight operations holding a buffer.
+    ///
+    /// Each read or write operation keeps its buffer alive until the kernel
+    /// completes it. Once `max` of them are in flight, submitting another one
+    /// waits until one completes. This bounds the memory pinned by in-flight
+    /// operations, e.g. in a server reading from every connection at once.
+    ///
+    /// Operations that do not hold a buffer, such as `open`, `close` or
+    /// `fsync`, are not limited. A slot is released as soon as the kernel
+    /// completes the operation, even if its result is never awaited.
+    ///
+    /// By default, there is no limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn max_in_flight(&mut self, max: usize) -> &mut Self {
+        assert!(max > 0, "at least one operation must be allowed in flight");
+        self.max_in_flight = max;
+        self
+    }
+
+    /// Set a callback invoked when a tagged operation completes.
+    ///
+    /// Operations are tagged with a `u64` chosen by the caller, e.g. with
+    /// [`File::read_at_tagged`], to correlate their completion with
+    /// application context while debugging. The callback receives the tag,
+    /// the result of the operation and how long it was in flight. Tags are
+    /// ignored if no callback is set.
+    ///
+    /// The callback is invoked from within the driver, as completions are
+    /// reaped, and must not use the `tokio-uring` runtime: submitting an
+    /// operation from it panics, as the driver is in use. To act on a
+    /// completion, send it to a task, e.g. through one of
+    /// [`tokio::sync::mpsc`].
+    ///
+    /// [`File::read_at_tagged`]: crate::fs::File::read_at_tagged
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tokio_uring::builder()
+    ///     .on_tagged_completion(|c| {
+    ///         eprintln!("op {} returned {} after {:?}", c.tag(), c.result(), c.elapsed());
+    ///     })
+    ///     .start(async {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn on_tagged_completion<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(TaggedCompletion) + Send + Sync + 'static,
+    {
+        self.on_tagged_completion = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// Set a callback invoked with the data of the messages posted to this
+    /// runtime's ring by other runtimes, with [`post_to`].
+    ///
+    /// The ring becomes ready as soon as a message is posted, so the callback
+    /// is invoked without delay, even if the runtime was idle. Messages are
+    /// ignored if no callback is set.
+    ///
+    /// The callback is invoked from within the driver, and must not use the
+    /// `tokio-uring` runtime. To hand work over to a task, send it through a
+    /// channel, e.g. one of [`tokio::sync::mpsc`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    ///
+    /// tokio_uring::builder()
+    ///     .on_message(move |data| {
+    ///         let _ = tx.send(data);
+    ///     })
+    ///     .start(async {
+    ///         while let Some(data) = rx.recv().await {
+    ///             println!("received {}", data);
+    ///         }
+    ///     });
+    /// ```
+    pub fn on_message<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        self.on_message = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// Pin the kernel's submission queue polling thread to `cpu`.
+    ///
+    /// This is only meaningful when submission queue polling is enabled, with
+    /// `setup_sqpoll` on the io_uring builder passed to [`uring_builder`].
+    /// Dedicating a core to the polling thread keeps it from contending with
+    /// application threads, which matters for latency sensitive deployments.
+    ///
+    /// Starting the runtime fails with an error if `cpu` is not a valid CPU
+    /// index, or if the kernel rejects the affinity, e.g. because the CPU is
+    /// offline or not in the allowed set. Some kernels also require the
+    /// `CAP_SYS_NICE` capability to set the affinity.
+    ///
+    /// [`uring_builder`]: Builder::uring_builder
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tokio_uring::builder()
+    ///     .uring_builder(tokio_uring::uring_builder().setup_sqpoll(1000))
+    ///     .sqpoll_cpu(3)
+    ///     .start(async {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn sqpoll_cpu(&mut self, cpu: u32) -> &mut Self {
+        self.sqpoll_cpu = Some(cpu);
+        self
+    }
+
+    /// Enable cooperative task running (`IORING_SETUP_COOP_TASKRUN`).
+    ///
+    /// The kernel no longer interrupts the thread to process completions,
+    /// which are processed on the next transition into the kernel instead.
+    /// As all submissions and completions happen on the runtime thread, this
+    /// reduces overhead without delaying completions noticeably.
+    ///
+    /// Starting the runtime fails with an error of kind [`Unsupported`] on
+    /// kernels older than 5.19.
+    ///
+    /// [`Unsupported`]: std::io::ErrorKind::Unsupported
+    pub fn coop_taskrun(&mut self, enable: bool) -> &mut Self {
+        self.coop_taskrun = enable;
+        self
+    }
+
+    /// Enable deferred task running (`IORING_SETUP_DEFER_TASKRUN`).
+    ///
+    /// Completion work is deferred until the driver reaps completions, rather
+    /// than run as soon as possible. This reduces inter-processor interrupts
+    /// and task work overhead for single-threaded workloads. The driver
+    /// enters the kernel to get events whenever the ring becomes ready, so no
+    /// completion is missed.
+    ///
+    /// This also sets `IORING_SETUP_SINGLE_ISSUER`, as the kernel requires.
+    /// Starting the runtime fails with an error of kind [`Unsupported`] on
+    /// kernels older than 6.1.
+    ///
+    /// [`Unsupported`]: std::io::ErrorKind::Unsupported
+    pub fn defer_taskrun(&mut self, enable: bool) -> &mut Self {
+        self.defer_taskrun = enable;
+        self
+    }
+
+    /// Tear down the ring once it has been idle for `timeout`.
+    ///
+    /// The ring and its kernel resources are released once no operation was
+    /// submitted or completed for `timeout`, and rebuilt with the same
+    /// configuration when the next operation is submitted. This is
+    /// transparent to callers, and saves kernel memory for applications only
+    /// doing io_uring I/O in occasional bursts.
+    ///
+    /// The ring is only considered idle with no operation in flight, and
+    /// while no buffers, `eventfd` or fixed files are registered with it. A
+    /// ring which handed out a [`RingHandle`] is never torn down.
+    ///
+    /// By default, the ring lives as long as the runtime.
+    pub fn idle_timeout(&mut self, timeout: std::time::Duration) -> &mut Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how the runtime waits for completions once it has nothing left to
+    /// run.
+    ///
+    /// With [`WaitStrategy::Spin`], the runtime checks the completion queue up
+    /// to `max_spins` times before parking the thread, trading CPU time for
+    /// the latency of being woken up. This may pay off when operations
+    /// complete within microseconds, e.g. on loopback or fast storage, and
+    /// when whatever completes them runs on another CPU. Spinning delays
+    /// threads sharing the CPU of the runtime: on the `criterion_wait_strategy`
+    /// benchmark, UDP round trips through an echo thread on a single CPU
+    /// machine took about 10µs with either strategy, and spinning up to
+    /// 100000 times was about 15% slower. Measure with your own workload and
+    /// hardware before relying on a speedup.
+    ///
+    /// This composes with SQPOLL, set up through [`uring_builder`]: the kernel
+    /// thread then picks up submissions, and spinning picks up completions,
+    /// so a busy ring is driven without entering the kernel at all. Spinning
+    /// has no effect with [`defer_taskrun`], as completions are then only
+    /// posted when entering the kernel.
+    ///
+    /// By default, the runtime parks as soon as it has nothing left to run.
+    ///
+    /// [`uring_builder`]: Builder::uring_builder
+    /// [`defer_taskrun`]: Builder::defer_taskrun
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::WaitStrategy;
+    ///
+    /// tokio_uring::builder()
+    ///     .wait_strategy(WaitStrategy::Spin { max_spins: 1000 })
+    ///     .start(async {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn wait_strategy(&mut self, strategy: WaitStrategy) -> &mut Self {
+        self.wait_strategy = strategy;
+        self
+    }
+
+    /// Raise the soft `RLIMIT_MEMLOCK` of the process to its hard limit
+    /// before creating the ring.
+    ///
+    /// Kernels older than 5.12 account the memory of the ring, and of the
+    /// buffers registered with [`FixedBufRegistry`], against the locked
+    /// memory limit, which often defaults to 64 KiB. Exceeding it fails with
+    /// a cryptic `ENOMEM`. Raising the soft limit needs no privilege, and is
+    /// best effort: if it fails, the ring is created anyway. Newer kernels
+    /// account this memory against the cgroup instead, so this is only an
+    /// aid for legacy kernels. See [`memlock_required`] for the amount
+    /// needed.
+    ///
+    /// By default, the limit is left unchanged.
+    ///
+    /// [`FixedBufRegistry`]: crate::buf::FixedBufRegistry
+    /// [`memlock_required`]: Builder::memlock_required
+    pub fn try_raise_memlock(&mut self, enable: bool) -> &mut Self {
+        self.raise_memlock = enable;
+        self
+    }
+
+    /// Returns an estimate of the locked memory, in bytes, the configured ring
+    /// and `registered` bytes of buffers registered with it need on kernels
+    /// older than 5.12.
+    ///
+    /// The estimate assumes the completion queue size set with
+    /// [`cq_entries`], or else the default, twice the submission queue size,
+    /// and counts registered buffers in whole pages. Compare it to
+    /// `RLIMIT_MEMLOCK`, e.g. to report a clear error before the kernel fails
+    /// with `ENOMEM`.
+    ///
+    /// [`cq_entries`]: Builder::cq_entries
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let builder = tokio_uring::builder();
+    ///
+    /// // Ring with 256 entries and 64 registered 4 KiB buffers
+    /// let needed = builder.memlock_required(64 * 4096);
+    /// assert!(needed > 64 * 4096);
+    /// ```
+    pub fn memlock_required(&self, registered: usize) -> usize {
+        // Largest cache line the kernel aligns the ring fields to, e.g. on
+        // arm64 and powerpc; x86_64 uses 64 bytes.
+        const CACHE_LINE: usize = 128;
+        // Header of `struct io_rings`, before the CQEs: the SQ and the CQ
+        // head and tail are each aligned to a cache line, followed by the ring
+        // masks, sizes and flags, and the CQEs are aligned to a cache line
+        // again.
+        const RINGS_HEADER: usize = 3 * CACHE_LINE;
+
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let pages = |len: usize| len.div_ceil(page_size) * page_size;
+
+        let sq_entries = self.entries.max(1).next_power_of_two() as usize;
+        let cq_entries = match self.cq_entries {
+            Some(e) => e.max(1).next_power_of_two() as usize,
+            None => 2 * sq_entries,
+        };
+        // As sized by `rings_size` in Linux 5.11, the last version charging
+        // the rings to `RLIMIT_MEMLOCK`: `struct_size(rings, cqes,
+        // cq_entries)`, aligned to a cache line, then the SQ index array.
+        let cqes = RINGS_HEADER + cq_entries * std::mem::size_of::<io_uring::cqueue::Entry>();
+        let rings =
+            cqes.div_ceil(CACHE_LINE) * CACHE_LINE + sq_entries * std::mem::size_of::<u32>();
+        let sqes = sq_entries * std::mem::size_of::<io_uring::squeue::Entry>();
+
+        pages(rings) + pages(sqes) + pages(registered)
+    }
+
     /// Replace the default io_uring Builder. This allows the caller to craft the io_uring Builder
     /// using the io_uring crate's Builder API.
     ///
@@ -227,7 +683,48 @@ impl Builder {
     /// }
     /// ```
     pub fn start<F: Future>(&self, future: F) -> F::Output {
-        let rt = runtime::Runtime::new(self).unwrap();
+        let rt = self.build().unwrap_or_else(|e| panic!("{}", e));
+        rt.block_on(future)
+    }
+
+    /// Creates the runtime, without running anything on it.
+    ///
+    /// Unlike [`start`], which panics if the ring cannot be created, this
+    /// reports why, distinguishing a kernel without io_uring, io_uring being
+    /// blocked, and `RLIMIT_MEMLOCK` being too low.
+    ///
+    /// [`start`]: Builder::start
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::RuntimeError;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rt = match tokio_uring::builder().entries(4096).build() {
+    ///         Err(RuntimeError::MemlockExceeded(_)) => tokio_uring::builder().entries(64).build()?,
+    ///         res => res?,
+    ///     };
+    ///
+    ///     rt.block_on(async {
+    ///         // use the runtime
+    ///     });
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn build(&self) -> Result<runtime::Runtime, RuntimeError> {
+        Ok(runtime::Runtime::new(self)?)
+    }
+
+    /// Start an `io_uring` enabled Tokio runtime, or, if io_uring is not
+    /// available, a runtime performing file operations with blocking system
+    /// calls on a thread pool.
+    ///
+    /// See [`start_or_fallback`] for details.
+    ///
+    /// [`start_or_fallback`]: crate::start_or_fallback
+    pub fn start_or_fallback<F: Future>(&self, future: F) -> F::Output {
+        let rt = runtime::Runtime::new_or_fallback(self).unwrap();
         rt.block_on(future)
     }
 }
@@ -281,6 +778,641 @@ pub type BufResult<T, B> = (std::io::Result<T>, B);
 /// }
 /// ```
 pub async fn no_op() -> std::io::Result<()> {
-    let op = driver::Op::<driver::NoOp>::no_op().unwrap();
+    let op = driver::Op::<driver::NoOp>::no_op()?;
     op.await
 }
+
+/// Submits an operation built from a raw submission queue entry, returning its
+/// raw result along with `data`.
+///
+/// This is an escape hatch for operations `tokio-uring` does not provide. The
+/// entry is built by `f`, which is given access to `data`, so it can refer to
+/// the buffers and other resources held by `data`. These are kept alive until
+/// the kernel completes the operation, even if the returned future is dropped
+/// first, and are then handed back.
+///
+/// The result is the CQE's `res` field: a non-negative value, such as a byte
+/// count or a new file descriptor depending on the operation, or an error
+/// built from the negated error code. Its meaning is not interpreted any
+/// further. The entry's `user_data` is overwritten.
+///
+/// In particular, the initialized length of buffers is not updated, as only
+/// the operation knows what was written. After a read, call
+/// [`IoBufMut::set_init`] with the number of bytes read to expose them.
+///
+/// [`IoBufMut::set_init`]: crate::buf::IoBufMut::set_init
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// # Safety
+///
+/// The operation must be valid, and only refer to memory which remains valid
+/// until it completes. The entry may only point to memory owned by `data`
+/// which does not move when `data` is moved, such as the contents of a `Vec`
+/// or a `Box`, but not a field of `data` itself. The entry must not produce
+/// more than one completion, so multishot operations are not supported.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_uring::{opcode, types};
+/// use std::os::unix::io::AsRawFd;
+/// use tokio_uring::buf::IoBufMut;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let file = tokio_uring::fs::File::open("hello.txt").await?;
+///
+///         let buf = Vec::with_capacity(4096);
+///         let fd = types::Fd(file.as_raw_fd());
+///         let (res, mut buf) = unsafe {
+///             tokio_uring::submit_raw(buf, |buf| {
+///                 opcode::Read::new(fd, buf.as_mut_ptr(), buf.capacity() as u32).build()
+///             })
+///         }
+///         .await;
+///
+///         // Safety: the kernel initialized the bytes it read.
+///         unsafe { buf.set_init(res? as usize) };
+///         println!("{:?}", buf);
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async unsafe fn submit_raw<T, F>(data: T, f: F) -> BufResult<i32, T>
+where
+    T: Unpin + 'static,
+    F: FnOnce(&mut T) -> io_uring::squeue::Entry,
+{
+    match driver::Op::raw(data, f) {
+        Ok(op) => op.await,
+        Err((e, data)) => (Err(e), data),
+    }
+}
+
+/// Add, modify or remove an entry of an epoll set, through the ring.
+///
+/// This is equivalent to `epoll_ctl(2)`, but avoids a separate system call,
+/// which helps migrating an epoll based event loop incrementally. `op` is one
+/// of `libc::EPOLL_CTL_ADD`, `libc::EPOLL_CTL_MOD` or `libc::EPOLL_CTL_DEL`.
+/// `event` is required to add or modify an entry, and ignored otherwise.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// # Errors
+///
+/// Returns an error of kind [`InvalidInput`] if `op` is not a valid operation,
+/// or if `event` is missing, and an error of kind [`Unsupported`] if the
+/// kernel does not support epoll operations on the ring, or on a runtime
+/// without io_uring. Otherwise, errors are those of `epoll_ctl(2)`.
+///
+/// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+/// [`Unsupported`]: std::io::ErrorKind::Unsupported
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::os::unix::io::AsRawFd;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+///         let socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+///
+///         let event = libc::epoll_event {
+///             events: libc::EPOLLIN as u32,
+///             u64: 42,
+///         };
+///         tokio_uring::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, socket.as_raw_fd(), Some(event)).await?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn epoll_ctl(
+    epfd: std::os::unix::io::RawFd,
+    op: i32,
+    fd: std::os::unix::io::RawFd,
+    event: Option<libc::epoll_event>,
+) -> std::io::Result<()> {
+    use std::io;
+
+    let event = match (op, event) {
+        (libc::EPOLL_CTL_ADD, Some(event)) | (libc::EPOLL_CTL_MOD, Some(event)) => event,
+        (libc::EPOLL_CTL_DEL, _) => libc::epoll_event { events: 0, u64: 0 },
+        (libc::EPOLL_CTL_ADD, None) | (libc::EPOLL_CTL_MOD, None) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "an event is required to add or modify an epoll entry",
+            ))
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid epoll operation",
+            ))
+        }
+    };
+
+    if !probe()?.is_supported(io_uring::opcode::EpollCtl::CODE) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "epoll operations are not supported by the ring",
+        ));
+    }
+
+    driver::Op::<driver::EpollCtl>::epoll_ctl(epfd, op, fd, event)?.await
+}
+
+/// Polls `fd` for readiness, through the ring.
+///
+/// This arms a multishot `IORING_OP_POLL_ADD`, which posts a completion each
+/// time the file becomes ready for any of `events`, the `poll(2)` event mask
+/// such as `libc::POLLIN as u32`. The returned [`PollFd`] yields the events
+/// the file is ready for, without re-arming the poll between them. The poll
+/// stays armed until [`PollFd::remove`] is awaited or the stream is dropped.
+///
+/// `fd` is not owned by the stream, and must stay open until the poll is
+/// removed.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+/// Multishot polls require Linux 5.13.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use std::os::unix::io::AsRawFd;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+///
+///         let mut readable = tokio_uring::poll_fd(socket.as_raw_fd(), libc::POLLIN as u32)?;
+///         if let Some(events) = readable.next().await {
+///             println!("ready for {:#x}", events?);
+///         }
+///
+///         // No completion is posted for the poll past this point.
+///         readable.remove().await?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn poll_fd(fd: std::os::unix::io::RawFd, events: u32) -> std::io::Result<PollFd> {
+    PollFd::new(fd, events)
+}
+
+/// Returns the set of operations supported by the running kernel.
+///
+/// This allows checking, before use, whether an operation only available in
+/// newer kernels can be submitted, instead of discovering it through an
+/// `EINVAL` error on first use. The kernel is queried once per runtime; later
+/// calls return the cached result.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// # Errors
+///
+/// Returns an error if the kernel does not support probing, which is the case
+/// for kernels older than 5.6, and an error of kind [`Unsupported`] on a
+/// runtime without io_uring.
+///
+/// [`Unsupported`]: std::io::ErrorKind::Unsupported
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_uring::opcode;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let probe = tokio_uring::probe()?;
+///
+///         if probe.is_supported(opcode::SendZc::CODE) {
+///             println!("zero copy send is supported");
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn probe() -> std::io::Result<Probe> {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Err(driver::op::unsupported());
+        }
+        cx.with_driver_mut(|driver| driver.probe())
+    })
+}
+
+/// Returns the io_uring features supported by the kernel, as reported when the
+/// current thread's ring was set up.
+///
+/// Features describe how the kernel behaves, rather than which operations it
+/// supports, which is reported by [`probe`]. For instance,
+/// [`Features::nodrop`] tells whether completions can be lost when the
+/// completion queue overflows.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+/// On a runtime without io_uring, no feature is reported.
+///
+/// # Examples
+///
+/// ```no_run
+/// tokio_uring::start(async {
+///     let features = tokio_uring::features();
+///
+///     if !features.fast_poll() {
+///         println!("socket operations may block kernel worker threads");
+///     }
+/// });
+/// ```
+pub fn features() -> Features {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Features::default();
+        }
+        cx.with_driver_mut(|driver| driver.features())
+    })
+}
+
+/// Returns a handle to the current thread's ring, through which runtimes on
+/// other threads post it messages with [`post_to`].
+///
+/// Messages are delivered to the callback set with [`Builder::on_message`].
+/// Once a handle was taken, the ring is no longer torn down when idle, see
+/// [`Builder::idle_timeout`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::mpsc;
+///
+/// let (handle_tx, handle_rx) = mpsc::channel();
+///
+/// let worker = std::thread::spawn(move || {
+///     tokio_uring::builder()
+///         .on_message(|data| println!("received {}", data))
+///         .start(async move {
+///             handle_tx.send(tokio_uring::ring_handle().unwrap()).unwrap();
+///             // ...
+///         });
+/// });
+///
+/// tokio_uring::start(async {
+///     let worker_ring = handle_rx.recv().unwrap();
+///     tokio_uring::post_to(&worker_ring, 42).await.unwrap();
+/// });
+/// ```
+pub fn ring_handle() -> std::io::Result<RingHandle> {
+    RingHandle::current()
+}
+
+/// Posts a message carrying `data` to the ring of another runtime, with
+/// `IORING_OP_MSG_RING`.
+///
+/// The message is a completion posted directly on the target ring, waking
+/// its runtime, which passes `data` to the callback set with
+/// [`Builder::on_message`]. This hands work over between threads without a
+/// round trip through an `eventfd`. This completes once the message was
+/// posted, not once it was handled.
+///
+/// This requires Linux 5.18, and fails with an error of kind [`Unsupported`]
+/// on older kernels, or from a runtime without io_uring.
+///
+/// [`Unsupported`]: std::io::ErrorKind::Unsupported
+pub async fn post_to(target: &RingHandle, data: u64) -> std::io::Result<()> {
+    if runtime::is_fallback() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "messages require io_uring",
+        ));
+    }
+
+    if !probe()?.is_supported(io_uring::opcode::MsgRingData::CODE) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "messages between rings are not supported by the kernel",
+        ));
+    }
+
+    driver::Op::msg_ring(target, data)?.await
+}
+
+/// Returns a snapshot of the occupancy of the current thread's submission
+/// queue.
+///
+/// Operations are queued until the runtime submits them to the kernel, or
+/// until the queue is full. A queue frequently close to full suggests
+/// [`Builder::entries`] should be raised, or submissions throttled, e.g. with
+/// [`Builder::max_in_flight`].
+///
+/// This only reads the indices of the ring, without entering the kernel.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+/// On a runtime without io_uring, the queue is reported empty, with no room.
+///
+/// # Examples
+///
+/// ```no_run
+/// tokio_uring::start(async {
+///     let stats = tokio_uring::sq_stats();
+///     println!("{} of {} entries queued", stats.len(), stats.capacity());
+/// });
+/// ```
+pub fn sq_stats() -> SqStats {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return SqStats::new(0, 0);
+        }
+        cx.with_driver_mut(|driver| driver.sq_stats())
+    })
+}
+
+/// Makes room for `n` entries in the current thread's submission queue, so a
+/// chain of `n` linked operations can be pushed as a whole.
+///
+/// The entries of a chain linked with `IOSQE_IO_LINK` must reach the kernel in
+/// the same submission, or the link is broken. When the queue fills up midway
+/// through a chain, the entries already queued are submitted to make room,
+/// splitting it. This submits the queued operations as needed, so at least
+/// `n` entries are free once it returns.
+///
+/// The room is not set aside for the caller: the reservation holds as long as
+/// the task pushes its entries, e.g. with [`submit_raw`], without yielding in
+/// between, as no other task runs and the runtime does not submit the queue
+/// until then. Poll the futures of the chain once each before awaiting any
+/// of them.
+///
+/// Returns `None` if `n` exceeds the size of the submission queue, or if the
+/// queued operations could not be submitted, e.g. as the completion queue is
+/// full. The caller can then wait for in-flight operations to complete and
+/// retry.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_uring::{opcode, squeue, types};
+/// use std::os::unix::io::AsRawFd;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let file = tokio_uring::fs::File::create("log").await?;
+///         let fd = types::Fd(file.as_raw_fd());
+///
+///         let _reservation = tokio_uring::reserve(2).expect("submission queue too small");
+///         let write = unsafe {
+///             tokio_uring::submit_raw(b"record".to_vec(), |buf| {
+///                 opcode::Write::new(fd, buf.as_ptr(), buf.len() as u32)
+///                     .build()
+///                     .flags(squeue::Flags::IO_LINK)
+///             })
+///         };
+///         let sync = unsafe {
+///             tokio_uring::submit_raw((), |_| opcode::Fsync::new(fd).build())
+///         };
+///
+///         // Both futures are polled, pushing their entries, before either
+///         // is awaited.
+///         let ((written, _), (synced, _)) = futures::join!(write, sync);
+///         written?;
+///         synced?;
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// [`submit_raw`]: crate::submit_raw
+pub fn reserve(n: usize) -> Option<SqReservation> {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return None;
+        }
+        cx.with_driver_mut(|driver| driver.reserve(n).ok())
+            .map(|()| SqReservation::new(n))
+    })
+}
+
+/// Syncs the data of several files to disk, submitting all the syncs to the
+/// kernel at once.
+///
+/// An `fdatasync` is queued for each file, after making room for all of them
+/// in the submission queue, so they reach the kernel in a single
+/// `io_uring_enter` and run concurrently. This commits a transaction spanning
+/// several files, of any kind, at the cost of a single system call, where
+/// awaiting [`File::sync_data`] on each file in turn enters the kernel for
+/// each of them. Sets of files larger than the submission queue are submitted
+/// in several batches.
+///
+/// If syncing a file fails, the first error, in the order of `files`, is
+/// returned, but only once all the syncs have completed.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// [`File::sync_data`]: fs::File::sync_data
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let data = File::create("data.bin").await?;
+///         let index = File::create("index.bin").await?;
+///
+///         // ... write to both files ...
+///
+///         tokio_uring::commit(&[&data, &index]).await?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn commit(files: &[&fs::File]) -> std::io::Result<()> {
+    use futures_util::future;
+
+    if runtime::is_fallback() {
+        let syncs = files.iter().map(|file| file.sync_data());
+        return future::join_all(syncs).await.into_iter().collect();
+    }
+
+    // Without room for all of them, the syncs are submitted in batches.
+    let _ = runtime::CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.reserve(files.len())));
+    let syncs: Vec<_> = files
+        .iter()
+        .map(|file| driver::Op::datasync(&file.fd))
+        .collect();
+
+    // Syncs queued before a failed one are awaited all the same.
+    future::join_all(syncs.into_iter().map(|sync| async move { sync?.await }))
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Submits the operations queued on the current thread's ring to the kernel
+/// right away, returning how many were submitted.
+///
+/// Operations are normally submitted in batches, when the runtime has no
+/// other work to do or the submission queue is full. Calling `flush` ends the
+/// current batch early, so latency sensitive operations reach the kernel
+/// without waiting for the rest of the tasks to run. It does not wait for any
+/// operation to complete.
+///
+/// Returns `Ok(0)` without entering the kernel if nothing is queued, and an
+/// error of kind [`Unsupported`] on a runtime without io_uring.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// [`Unsupported`]: std::io::ErrorKind::Unsupported
+///
+/// # Examples
+///
+/// ```no_run
+/// fn main() -> std::io::Result<()> {
+///     tokio_uring::start(async {
+///         let file = tokio_uring::fs::File::open("hello.txt").await?;
+///         let read = file.read_at(vec![0; 4096], 0);
+///         futures::pin_mut!(read);
+///
+///         // Queue the read, then submit it before doing anything else.
+///         let _ = futures::poll!(&mut read);
+///         tokio_uring::flush()?;
+///
+///         let (res, _) = read.await;
+///         res?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn flush() -> std::io::Result<usize> {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Err(driver::op::unsupported());
+        }
+        cx.with_driver_mut(|driver| driver.flush())
+    })
+}
+
+/// Waits until every operation submitted so far on the current runtime has
+/// completed.
+///
+/// This is a barrier on the completion of operations, e.g. to take a
+/// consistent snapshot of the application state; it does not make any data
+/// durable, see [`File::sync_all`] for this. Operations submitted after the
+/// call, including ones submitted while waiting, are not waited for.
+///
+/// Operations whose future was dropped are waited for too, as the kernel may
+/// still be working on them.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+///
+/// [`File::sync_all`]: crate::fs::File::sync_all
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::File;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let file = File::create("state.bin").await?;
+///         let write = file.write_at(b"state".to_vec(), 0);
+///         futures::pin_mut!(write);
+///
+///         // Submit the write, then wait for it along with all other
+///         // operations in flight.
+///         let _ = futures::poll!(&mut write);
+///         tokio_uring::quiesce().await;
+///         assert!(futures::poll!(&mut write).is_ready());
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn quiesce() {
+    if runtime::is_fallback() {
+        return;
+    }
+
+    let epoch = runtime::CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.start_quiesce()));
+    future::poll_fn(|cx| {
+        runtime::CONTEXT.with(|runtime_context| {
+            runtime_context.with_driver_mut(|driver| driver.poll_quiesced(epoch, cx))
+        })
+    })
+    .await
+}
+
+/// Registers an `eventfd` to be signaled whenever an operation completes on
+/// the current runtime's ring.
+///
+/// This allows an external event loop to learn about completions without
+/// polling the ring itself. Only one `eventfd` can be registered at a time.
+/// The caller keeps ownership of `fd`, and must keep it open until
+/// [`unregister_eventfd`] is called or the runtime shuts down.
+///
+/// This function must be called from the context of a `tokio-uring` runtime.
+/// It fails with an error of kind [`Unsupported`] on a runtime without
+/// io_uring.
+///
+/// [`Unsupported`]: std::io::ErrorKind::Unsupported
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::os::unix::io::AsRawFd;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let notifier = tokio_uring::Notifier::new()?;
+///         tokio_uring::register_eventfd(notifier.as_raw_fd())?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn register_eventfd(fd: std::os::unix::io::RawFd) -> std::io::Result<()> {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Err(driver::op::unsupported());
+        }
+        cx.with_driver_mut(|driver| {
+            driver.uring()?.submitter().register_eventfd(fd)?;
+            driver.eventfd_registered = true;
+            Ok(())
+        })
+    })
+}
+
+/// Like [`register_eventfd`], but the `eventfd` is only signaled for
+/// operations that did not complete inline during submission.
+///
+/// Such completions are already known to the submitting thread, so this
+/// avoids spurious wakeups of whoever waits on the `eventfd`.
+pub fn register_eventfd_async(fd: std::os::unix::io::RawFd) -> std::io::Result<()> {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Err(driver::op::unsupported());
+        }
+        cx.with_driver_mut(|driver| {
+            driver.uring()?.submitter().register_eventfd_async(fd)?;
+            driver.eventfd_registered = true;
+            Ok(())
+        })
+    })
+}
+
+/// Unregisters the `eventfd` registered with [`register_eventfd`] or
+/// [`register_eventfd_async`].
+pub fn unregister_eventfd() -> std::io::Result<()> {
+    runtime::CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return Err(driver::op::unsupported());
+        }
+        cx.with_driver_mut(|driver| {
+            driver.uring()?.submitter().unregister_eventfd()?;
+            driver.eventfd_registered = false;
+            Ok(())
+        })
+    })
+}
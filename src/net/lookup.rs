@@ -0,0 +1,46 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+
+/// Resolves `host`, a host name and port such as `"example.com:80"`, to the
+/// socket addresses it stands for, on the blocking thread pool.
+///
+/// Connecting, binding and sending take resolved [`SocketAddr`]s, so name
+/// resolution never happens behind their back. Resolution goes through the
+/// system resolver, `getaddrinfo(3)`, which blocks, possibly for seconds on
+/// DNS queries: called on the runtime thread, it would stall every task of
+/// the runtime. This runs it on Tokio's blocking thread pool instead.
+///
+/// Literal addresses, such as `"127.0.0.1:8080"` or `"[::1]:8080"`, are
+/// accepted too, and resolve to themselves.
+///
+/// # Errors
+///
+/// Fails if `host` is not of the form `host:port`, or if the resolution
+/// fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::net::{self, TcpStream};
+///
+/// fn main() -> std::io::Result<()> {
+///     tokio_uring::start(async {
+///         for addr in net::lookup_host("example.com:80").await? {
+///             if let Ok(stream) = TcpStream::connect(addr).await {
+///                 println!("connected to {}", stream.peer_addr()?);
+///                 break;
+///             }
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn lookup_host(host: &str) -> io::Result<impl Iterator<Item = SocketAddr>> {
+    // Parsing a literal address needs no resolver.
+    if let Ok(addr) = host.parse::<SocketAddr>() {
+        return Ok(vec![addr].into_iter());
+    }
+
+    let host = host.to_owned();
+    crate::util::asyncify(move || host.to_socket_addrs()).await
+}
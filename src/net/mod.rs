@@ -7,16 +7,24 @@
 //!
 //! * [`TcpListener`] and [`TcpStream`] provide functionality for communication over TCP
 //! * [`UdpSocket`] provides functionality for communication over UDP
+//! * [`Socket`] provides functionality for sockets of any other protocol
+//! * [`lookup_host`] resolves host names to socket addresses
 
 //!
 //! [`TcpListener`]: TcpListener
 //! [`TcpStream`]: TcpStream
 //! [`UdpSocket`]: UdpSocket
+//! [`Socket`]: Socket
+//! [`lookup_host`]: lookup_host
 
+mod lookup;
+mod socket;
 mod tcp;
 mod udp;
 mod unix;
 
+pub use lookup::lookup_host;
+pub use socket::Socket;
 pub use tcp::{TcpListener, TcpStream};
 pub use udp::UdpSocket;
 pub use unix::{UnixListener, UnixStream};
@@ -0,0 +1,123 @@
+use std::{
+    io,
+    net::SocketAddr,
+    os::unix::io::{AsRawFd, FromRawFd, RawFd},
+};
+
+use socket2::SockAddr;
+
+use crate::{
+    buf::{IoBuf, IoBufMut},
+    driver::{self, SharedFd},
+};
+
+/// A socket of any domain, type and protocol.
+///
+/// This is the building block for protocols without a dedicated type, such
+/// as SCTP, raw IP or `SOCK_SEQPACKET` sockets. Addresses are passed as
+/// [`socket2::SockAddr`], which holds an address of any family.
+///
+/// The socket is created with `SOCK_CLOEXEC` and `SOCK_NONBLOCK` set. The
+/// operations go through the ring, which waits for a non-blocking socket to
+/// be ready, so the flag only matters to code handed the raw file descriptor.
+///
+/// # Examples
+///
+/// ```no_run
+/// use socket2::SockAddr;
+/// use tokio_uring::net::Socket;
+///
+/// fn main() -> std::io::Result<()> {
+///     tokio_uring::start(async {
+///         let socket = Socket::new(libc::AF_UNIX, libc::SOCK_SEQPACKET, 0)?;
+///         socket.connect(&SockAddr::unix("/run/service.sock")?).await?;
+///
+///         let (result, _) = socket.send(b"hello".to_vec()).await;
+///         result?;
+///         Ok(())
+///     })
+/// }
+/// ```
+pub struct Socket {
+    inner: driver::Socket,
+}
+
+impl Socket {
+    /// Creates a new socket, as with `socket(2)`.
+    ///
+    /// `domain`, `ty` and `protocol` are the `AF_*`, `SOCK_*` and `IPPROTO_*`
+    /// constants of the [`libc`] crate. `ty` may include further `SOCK_*`
+    /// flags, and `protocol` is usually 0, selecting the default protocol of
+    /// the domain and type.
+    pub fn new(domain: libc::c_int, ty: libc::c_int, protocol: libc::c_int) -> io::Result<Socket> {
+        let ty = ty | libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK;
+        let fd = syscall!(socket(domain, ty, protocol))?;
+        Ok(Socket::from_shared_fd(SharedFd::new(fd)))
+    }
+
+    /// Binds the socket to `addr`.
+    ///
+    /// This submits `IORING_OP_BIND`, or calls `bind(2)` on kernels older
+    /// than 6.11.
+    pub async fn bind(&self, addr: &SockAddr) -> io::Result<()> {
+        self.inner.bind_to(addr.clone()).await
+    }
+
+    /// Marks the socket as accepting connections, with room for `backlog`
+    /// pending connections.
+    ///
+    /// This submits `IORING_OP_LISTEN`, or calls `listen(2)` on kernels older
+    /// than 6.11.
+    pub async fn listen(&self, backlog: libc::c_int) -> io::Result<()> {
+        self.inner.listen_async(backlog).await
+    }
+
+    /// Connects the socket to `addr`.
+    pub async fn connect(&self, addr: &SockAddr) -> io::Result<()> {
+        self.inner.connect(addr.clone()).await
+    }
+
+    /// Accepts a new connection on a listening socket, returning it along with
+    /// the address of the peer, if it is an IP address.
+    ///
+    /// The accepted socket has `SOCK_CLOEXEC` set, but not `SOCK_NONBLOCK`.
+    pub async fn accept(&self) -> io::Result<(Socket, Option<SocketAddr>)> {
+        let (socket, addr) = self.inner.accept().await?;
+        Ok((Socket { inner: socket }, addr))
+    }
+
+    /// Sends data on a connected socket, returning the original buffer and
+    /// quantity of data sent.
+    pub async fn send<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
+        self.inner.send(buf).await
+    }
+
+    /// Receives data on a connected socket into the buffer, returning the
+    /// original buffer and quantity of data received.
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        self.inner.recv(buf).await
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    pub fn shutdown(&self, how: std::net::Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+
+    fn from_shared_fd(fd: SharedFd) -> Socket {
+        Socket {
+            inner: driver::Socket::from_shared_fd(fd),
+        }
+    }
+}
+
+impl FromRawFd for Socket {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Socket::from_shared_fd(SharedFd::new(fd))
+    }
+}
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
@@ -47,6 +47,16 @@ impl TcpListener {
     ///
     /// Binding with a port number of 0 will request that the OS assigns a port
     /// to this listener.
+    ///
+    /// This calls `bind(2)` and `listen(2)`, even on kernels supporting
+    /// `IORING_OP_BIND` and `IORING_OP_LISTEN`: the function is not `async`,
+    /// so it can't wait for operations on the ring, and making it so would
+    /// break its callers. To set up a listener through the ring, use a
+    /// [`Socket`], with [`Socket::bind`] and [`Socket::listen`].
+    ///
+    /// [`Socket`]: crate::net::Socket
+    /// [`Socket::bind`]: crate::net::Socket::bind
+    /// [`Socket::listen`]: crate::net::Socket::listen
     pub fn bind(addr: SocketAddr) -> io::Result<Self> {
         let socket = Socket::bind(addr, libc::SOCK_STREAM)?;
         socket.listen(1024)?;
@@ -97,4 +107,20 @@ impl TcpListener {
         })?;
         Ok((stream, socket_addr))
     }
+
+    /// Cancels every in-flight operation on this listener, returning how many
+    /// were canceled.
+    ///
+    /// Pending [`accept`] calls fail with `ECANCELED`. This is meant for
+    /// shutting down a server without tracking the tasks accepting
+    /// connections.
+    ///
+    /// This requires Linux 5.19, and fails with an error of kind
+    /// [`Unsupported`] on older kernels.
+    ///
+    /// [`accept`]: TcpListener::accept
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    pub async fn cancel_all(&self) -> io::Result<usize> {
+        self.inner.cancel_all().await
+    }
 }
@@ -7,6 +7,7 @@ use std::{
 use crate::{
     buf::{IoBuf, IoBufMut},
     driver::{SharedFd, Socket},
+    fs::File,
 };
 
 /// A TCP stream between a local and a remote socket.
@@ -43,6 +44,11 @@ pub struct TcpStream {
 
 impl TcpStream {
     /// Opens a TCP connection to a remote host at the given `SocketAddr`
+    ///
+    /// The address must be resolved already: resolve host names with
+    /// [`lookup_host`], which does not block the runtime thread.
+    ///
+    /// [`lookup_host`]: crate::net::lookup_host
     pub async fn connect(addr: SocketAddr) -> io::Result<TcpStream> {
         let socket = Socket::new(addr, libc::SOCK_STREAM)?;
         socket.connect(socket2::SockAddr::from(addr)).await?;
@@ -69,12 +75,171 @@ impl TcpStream {
         Self { inner }
     }
 
+    /// Returns the local address that this stream is bound to.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::net::TcpStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     tokio_uring::start(async {
+    ///         let stream = TcpStream::connect("127.0.0.1:8080".parse().unwrap()).await?;
+    ///
+    ///         // The local port was picked by the kernel
+    ///         println!("connected from {}", stream.local_addr()?);
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.with_std(std::net::TcpStream::local_addr)
+    }
+
+    /// Returns the address of the remote peer of this stream.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.with_std(std::net::TcpStream::peer_addr)
+    }
+
+    fn with_std<R>(&self, f: impl FnOnce(&std::net::TcpStream) -> R) -> R {
+        // SAFETY: Our fd is the handle the kernel has given us for a TcpStream.
+        // Create a std::net::TcpStream long enough to call the method and then
+        // forget it so the socket is not closed here.
+        let s = unsafe { std::net::TcpStream::from_raw_fd(self.inner.as_raw_fd()) };
+        let res = f(&s);
+        std::mem::forget(s);
+        res
+    }
+
     /// Read some data from the stream into the buffer, returning the original buffer and
     /// quantity of data read.
     pub async fn read<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
         self.inner.read(buf).await
     }
 
+    /// Receive some data from the stream into the buffer, returning the original buffer and
+    /// quantity of data received.
+    ///
+    /// A return value of `0` means the peer closed its side of the connection. If the peer
+    /// reset the connection instead, an error of kind [`ConnectionReset`] is returned. Use
+    /// [`recv_or_eof`] to treat an abrupt close like an orderly one.
+    ///
+    /// [`ConnectionReset`]: std::io::ErrorKind::ConnectionReset
+    /// [`recv_or_eof`]: TcpStream::recv_or_eof
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        self.inner.recv(buf).await
+    }
+
+    /// Receive some data from the stream into the buffer, treating a reset or aborted
+    /// connection as end of stream.
+    ///
+    /// This behaves like [`recv`], except that `ECONNRESET` and `ECONNABORTED` are reported
+    /// as a successful read of `0` bytes. This suits protocols where the peer may close the
+    /// connection abruptly once it is done sending.
+    ///
+    /// [`recv`]: TcpStream::recv
+    pub async fn recv_or_eof<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        match self.inner.recv(buf).await {
+            (Err(e), buf)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted
+                ) =>
+            {
+                (Ok(0), buf)
+            }
+            res => res,
+        }
+    }
+
+    /// Receive data until the peer closes its side of the connection, appending it to `buf`
+    /// and returning how many bytes were received.
+    ///
+    /// The buffer grows as needed, so a peer sending without end makes it grow without
+    /// bound. Use [`read_to_end_with_limits`] for untrusted peers. A reset connection fails
+    /// with an error of kind [`ConnectionReset`]. On error, the buffer is returned with the
+    /// data received so far.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::net::TcpStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     tokio_uring::start(async {
+    ///         let stream = TcpStream::connect("127.0.0.1:8080".parse().unwrap()).await?;
+    ///         stream.write_all(b"GET / HTTP/1.0\r\n\r\n".to_vec()).await.0?;
+    ///
+    ///         let (res, response) = stream.read_to_end(vec![]).await;
+    ///         println!("received {} bytes", res?);
+    ///         println!("{}", String::from_utf8_lossy(&response));
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`read_to_end_with_limits`]: TcpStream::read_to_end_with_limits
+    /// [`ConnectionReset`]: std::io::ErrorKind::ConnectionReset
+    pub async fn read_to_end(&self, buf: Vec<u8>) -> crate::BufResult<usize, Vec<u8>> {
+        self.read_to_end_with_limits(buf, None, false).await
+    }
+
+    /// Receive data until the peer closes its side of the connection, receiving at most
+    /// `max_len` bytes.
+    ///
+    /// This behaves like [`read_to_end`], except that receiving more than `max_len` bytes
+    /// fails with an error of kind [`OutOfMemory`], returning the buffer with the first
+    /// `max_len` bytes received. The buffer never grows past what is needed to detect this.
+    /// If `reset_is_eof` is set, a reset or aborted connection ends the stream like an
+    /// orderly close, as with [`recv_or_eof`].
+    ///
+    /// [`read_to_end`]: TcpStream::read_to_end
+    /// [`recv_or_eof`]: TcpStream::recv_or_eof
+    /// [`OutOfMemory`]: std::io::ErrorKind::OutOfMemory
+    pub async fn read_to_end_with_limits(
+        &self,
+        mut buf: Vec<u8>,
+        max_len: Option<usize>,
+        reset_is_eof: bool,
+    ) -> crate::BufResult<usize, Vec<u8>> {
+        let start = buf.len();
+        // One byte past the limit, to tell a stream of exactly `max_len` bytes
+        // from a longer one.
+        let end = max_len.map_or(usize::MAX, |max| {
+            start.saturating_add(max).saturating_add(1)
+        });
+
+        loop {
+            if buf.len() == buf.capacity() {
+                buf.reserve(buf.capacity().max(4096).min(end - buf.len()));
+            }
+
+            let (len, cap) = (buf.len(), buf.capacity().min(end));
+            let slice = buf.slice(len..cap);
+            let (res, slice) = if reset_is_eof {
+                self.recv_or_eof(slice).await
+            } else {
+                self.recv(slice).await
+            };
+            buf = slice.into_inner();
+
+            match res {
+                Ok(0) => return (Ok(buf.len() - start), buf),
+                Ok(_) if buf.len() == end => {
+                    buf.truncate(end - 1);
+                    let err = io::Error::new(
+                        io::ErrorKind::OutOfMemory,
+                        "stream exceeds the maximum length",
+                    );
+                    return (Err(err), buf);
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return (Err(e), buf),
+            }
+        }
+    }
+
     /// Write some data to the stream from the buffer, returning the original buffer and
     /// quantity of data written.
     pub async fn write<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
@@ -192,6 +357,89 @@ impl TcpStream {
         self.inner.writev(buf).await
     }
 
+    /// Sends the data of several buffers to the stream with a single
+    /// operation, returning how many bytes were sent.
+    ///
+    /// The buffers are sent in order, as if they were concatenated, using
+    /// `sendmsg(2)`. This avoids copying e.g. a header and a body into one
+    /// buffer first. Like [`write`], this may send only some prefix of the
+    /// data.
+    ///
+    /// The buffers are returned along with the result.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::net::TcpStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     tokio_uring::start(async {
+    ///         let stream = TcpStream::connect("127.0.0.1:8080".parse().unwrap()).await?;
+    ///
+    ///         let bufs = vec![b"header".to_vec(), b"body".to_vec()];
+    ///         let (res, _) = stream.send_vectored(bufs).await;
+    ///         println!("sent {} bytes", res?);
+    ///
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`write`]: TcpStream::write
+    pub async fn send_vectored<T: IoBuf>(&self, bufs: Vec<T>) -> crate::BufResult<usize, Vec<T>> {
+        self.inner.send_vectored(bufs).await
+    }
+
+    /// Receives data from the stream into several buffers with a single
+    /// operation, returning how many bytes were received.
+    ///
+    /// The buffers are filled in order, each from the end of its initialized
+    /// part up to its capacity, using `recvmsg(2)`. A buffer is only written to
+    /// once the previous ones are full. A return value of `0` means the peer
+    /// closed its side of the connection.
+    ///
+    /// The buffers are returned along with the result.
+    pub async fn recv_vectored<T: IoBufMut>(
+        &self,
+        bufs: Vec<T>,
+    ) -> crate::BufResult<usize, Vec<T>> {
+        self.inner.recv_vectored(bufs).await
+    }
+
+    /// Sends `len` bytes of `file`, starting at `offset`, to the stream,
+    /// returning how many bytes were sent.
+    ///
+    /// The data is moved from the file to the socket within the kernel, without
+    /// being copied through userspace. As io_uring has no direct equivalent of
+    /// `sendfile(2)`, the data goes through a pipe: a splice from the file into
+    /// the pipe is linked to a splice from the pipe into the socket, so both are
+    /// submitted together. Ranges larger than the pipe are sent in several such
+    /// steps.
+    ///
+    /// Fewer than `len` bytes are sent only if the end of the file is reached.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::fs::File;
+    /// use tokio_uring::net::TcpStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     tokio_uring::start(async {
+    ///         let stream = TcpStream::connect("127.0.0.1:8080".parse().unwrap()).await?;
+    ///         let file = File::open("index.html").await?;
+    ///
+    ///         let n = stream.send_file(&file, 0, 4096).await?;
+    ///         println!("sent {} bytes", n);
+    ///
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub async fn send_file(&self, file: &File, offset: u64, len: usize) -> io::Result<usize> {
+        self.inner.send_file(&file.fd, offset, len).await
+    }
+
     /// Shuts down the read, write, or both halves of this connection.
     ///
     /// This function will cause all pending and future I/O on the specified portions to return
@@ -199,6 +447,57 @@ impl TcpStream {
     pub fn shutdown(&self, how: std::net::Shutdown) -> io::Result<()> {
         self.inner.shutdown(how)
     }
+
+    /// Shuts down the read, write, or both halves of this connection, over the
+    /// ring.
+    ///
+    /// This is [`shutdown`] as an operation, submitting `IORING_OP_SHUTDOWN`,
+    /// or calling `shutdown(2)` on kernels older than 5.11. Shutting down the
+    /// write half sends a FIN while the read half stays open, to half-close
+    /// the connection: the peer reads to the end of the stream, and can still
+    /// send a response, which is received as usual.
+    ///
+    /// [`shutdown`]: TcpStream::shutdown
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::net::Shutdown;
+    /// use tokio_uring::net::TcpStream;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let stream = TcpStream::connect("127.0.0.1:8080".parse().unwrap()).await?;
+    ///
+    ///         // The end of the request is signaled by half-closing.
+    ///         stream.write_all(b"request".to_vec()).await.0?;
+    ///         stream.shutdown_async(Shutdown::Write).await?;
+    ///
+    ///         let (res, buf) = stream.read(vec![0; 4096]).await;
+    ///         println!("response: {:?}", &buf[..res?]);
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub async fn shutdown_async(&self, how: std::net::Shutdown) -> io::Result<()> {
+        self.inner.shutdown_async(how).await
+    }
+
+    /// Cancels every in-flight operation on this connection, returning how many
+    /// were canceled.
+    ///
+    /// The canceled operations fail with `ECANCELED`, their futures returning
+    /// the buffers as usual. This is meant for tearing down a connection
+    /// without tracking each pending operation, e.g. a read waiting for
+    /// data which will never come.
+    ///
+    /// This requires Linux 5.19, and fails with an error of kind
+    /// [`Unsupported`] on older kernels.
+    ///
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    pub async fn cancel_all(&self) -> io::Result<usize> {
+        self.inner.cancel_all().await
+    }
 }
 
 impl FromRawFd for TcpStream {
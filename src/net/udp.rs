@@ -91,8 +91,11 @@ pub struct UdpSocket {
 
 impl UdpSocket {
     /// Creates a new UDP socket and attempt to bind it to the addr provided.
+    ///
+    /// This submits `IORING_OP_BIND`, or calls `bind(2)` on kernels older
+    /// than 6.11.
     pub async fn bind(socket_addr: SocketAddr) -> io::Result<UdpSocket> {
-        let socket = Socket::bind(socket_addr, libc::SOCK_DGRAM)?;
+        let socket = Socket::bind_async(socket_addr, libc::SOCK_DGRAM).await?;
         Ok(UdpSocket { inner: socket })
     }
 
@@ -154,13 +157,35 @@ impl UdpSocket {
         Self { inner }
     }
 
+    /// Returns the local address that this socket is bound to.
+    ///
+    /// This can be useful, for example, when binding to port 0 to
+    /// figure out which port was actually bound.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        // SAFETY: Our fd is the handle the kernel has given us for a UdpSocket.
+        // Create a std::net::UdpSocket long enough to call its local_addr method
+        // and then forget it so the socket is not closed here.
+        let s = unsafe { std::net::UdpSocket::from_raw_fd(self.inner.as_raw_fd()) };
+        let local_addr = s.local_addr();
+        std::mem::forget(s);
+        local_addr
+    }
+
     /// Connects this UDP socket to a remote address, allowing the `write` and
     /// `read` syscalls to be used to send data and also applies filters to only
     /// receive data from the specified address.
     ///
+    /// Once connected, [`send`] and [`recv`] exchange datagrams with the
+    /// remote address without passing it on each operation, and datagrams from
+    /// other addresses are filtered out by the kernel. `connect` can be called
+    /// again to change the remote address.
+    ///
     /// Note that usually, a successful `connect` call does not specify
     /// that there is a remote server listening on the port, rather, such an
     /// error would only be detected after the first send.
+    ///
+    /// [`send`]: UdpSocket::send
+    /// [`recv`]: UdpSocket::recv
     pub async fn connect(&self, socket_addr: SocketAddr) -> io::Result<()> {
         self.inner.connect(SockAddr::from(socket_addr)).await
     }
@@ -175,6 +200,37 @@ impl UdpSocket {
         self.inner.send_to(buf, socket_addr).await
     }
 
+    /// Sends a single datagram to the remote address the socket is connected
+    /// to. On success, returns the number of bytes sent.
+    ///
+    /// Fails with `EDESTADDRREQ` if the socket is not [connected].
+    ///
+    /// [connected]: UdpSocket::connect
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tokio_uring::net::UdpSocket;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     tokio_uring::start(async {
+    ///         let socket = UdpSocket::bind("0.0.0.0:0".parse().unwrap()).await?;
+    ///         socket.connect("10.0.0.53:53".parse().unwrap()).await?;
+    ///
+    ///         let query = vec![0; 32];
+    ///         let (res, _) = socket.send(query).await;
+    ///         res?;
+    ///
+    ///         let (res, response) = socket.recv(vec![0; 512]).await;
+    ///         println!("{:?}", &response[..res?]);
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub async fn send<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
+        self.inner.send(buf).await
+    }
+
     /// Sends data on the socket. Will attempt to do so without intermediate copies.
     /// On success, returns the number of bytes written.
     ///
@@ -197,6 +253,15 @@ impl UdpSocket {
         self.inner.recv_from(buf).await
     }
 
+    /// Receives a single datagram from the remote address the socket is
+    /// connected to. On success, returns the number of bytes read.
+    ///
+    /// The datagram is truncated if it does not fit in the buffer. Datagrams
+    /// from other addresses are discarded by the kernel.
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        self.inner.recv(buf).await
+    }
+
     /// Read a packet of data from the socket into the buffer, returning the original buffer and
     /// quantity of data read.
     pub async fn read<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
@@ -216,6 +281,22 @@ impl UdpSocket {
     pub fn shutdown(&self, how: std::net::Shutdown) -> io::Result<()> {
         self.inner.shutdown(how)
     }
+
+    /// Cancels every in-flight operation on this socket, returning how many
+    /// were canceled.
+    ///
+    /// The canceled operations fail with `ECANCELED`, their futures returning
+    /// the buffers as usual. This is meant for tearing down a socket
+    /// without tracking each pending operation, e.g. a read waiting for
+    /// data which will never come.
+    ///
+    /// This requires Linux 5.19, and fails with an error of kind
+    /// [`Unsupported`] on older kernels.
+    ///
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    pub async fn cancel_all(&self) -> io::Result<usize> {
+        self.inner.cancel_all().await
+    }
 }
 
 impl FromRawFd for UdpSocket {
@@ -93,4 +93,20 @@ impl UnixListener {
         let stream = UnixStream { inner: socket };
         Ok(stream)
     }
+
+    /// Cancels every in-flight operation on this listener, returning how many
+    /// were canceled.
+    ///
+    /// Pending [`accept`] calls fail with `ECANCELED`. This is meant for
+    /// shutting down a server without tracking the tasks accepting
+    /// connections.
+    ///
+    /// This requires Linux 5.19, and fails with an error of kind
+    /// [`Unsupported`] on older kernels.
+    ///
+    /// [`accept`]: UnixListener::accept
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    pub async fn cancel_all(&self) -> io::Result<usize> {
+        self.inner.cancel_all().await
+    }
 }
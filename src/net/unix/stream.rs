@@ -1,6 +1,7 @@
 use crate::{
     buf::{IoBuf, IoBufMut},
     driver::{SharedFd, Socket},
+    fs::File,
 };
 use socket2::SockAddr;
 use std::{
@@ -83,6 +84,78 @@ impl UnixStream {
         self.inner.write(buf).await
     }
 
+    /// Sends some data from the buffer to the stream, passing the file
+    /// descriptors `fds` along with it, and returns how many bytes were sent.
+    ///
+    /// The descriptors are passed as an `SCM_RIGHTS` control message: the peer
+    /// receives new descriptors referring to the same open files, e.g. with
+    /// [`recv_with_fd`]. They stay open on this side. The buffer should hold at
+    /// least one byte, as the descriptors are attached to the data sent.
+    ///
+    /// At most 253 descriptors can be passed at once, otherwise an error of kind
+    /// [`InvalidInput`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::os::unix::io::AsRawFd;
+    /// use tokio_uring::fs::File;
+    /// use tokio_uring::net::UnixStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     tokio_uring::start(async {
+    ///         let stream = UnixStream::connect("/tmp/tokio-uring-unix-test.sock").await?;
+    ///         let file = File::open("secret.key").await?;
+    ///
+    ///         let (res, _) = stream.send_with_fd(b"key".to_vec(), &[file.as_raw_fd()]).await;
+    ///         res?;
+    ///
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`recv_with_fd`]: UnixStream::recv_with_fd
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+    pub async fn send_with_fd<T: IoBuf>(
+        &self,
+        buf: T,
+        fds: &[RawFd],
+    ) -> crate::BufResult<usize, T> {
+        self.inner.send_with_fds(buf, fds).await
+    }
+
+    /// Receives some data from the stream into the buffer, along with any file
+    /// descriptors passed with it, returning the quantity of data received and
+    /// the descriptors as owned [`File`]s.
+    ///
+    /// The descriptors are created with the close-on-exec flag set, and are
+    /// closed when the returned files are dropped.
+    ///
+    /// # Errors
+    ///
+    /// If the kernel could not deliver all of the passed descriptors, the
+    /// descriptors which were received are closed, and an error of kind
+    /// [`InvalidData`] is returned. The data received is still written to the
+    /// buffer.
+    ///
+    /// [`File`]: crate::fs::File
+    /// [`InvalidData`]: std::io::ErrorKind::InvalidData
+    pub async fn recv_with_fd<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> crate::BufResult<(usize, Vec<File>), T> {
+        let (res, buf) = self.inner.recv_with_fds(buf).await;
+        let res = res.map(|(n, fds)| {
+            let files = fds
+                .into_iter()
+                .map(|fd| unsafe { File::from_raw_fd(fd) })
+                .collect();
+            (n, files)
+        });
+        (res, buf)
+    }
+
     /// Attempts to write an entire buffer to the stream.
     ///
     /// This method will continuously call [`write`] until there is no more data to be
@@ -165,6 +238,22 @@ impl UnixStream {
     pub fn shutdown(&self, how: std::net::Shutdown) -> io::Result<()> {
         self.inner.shutdown(how)
     }
+
+    /// Cancels every in-flight operation on this connection, returning how many
+    /// were canceled.
+    ///
+    /// The canceled operations fail with `ECANCELED`, their futures returning
+    /// the buffers as usual. This is meant for tearing down a connection
+    /// without tracking each pending operation, e.g. a read waiting for
+    /// data which will never come.
+    ///
+    /// This requires Linux 5.19, and fails with an error of kind
+    /// [`Unsupported`] on older kernels.
+    ///
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    pub async fn cancel_all(&self) -> io::Result<usize> {
+        self.inner.cancel_all().await
+    }
 }
 
 impl FromRawFd for UnixStream {
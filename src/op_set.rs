@@ -0,0 +1,197 @@
+use crate::runtime::CONTEXT;
+
+use futures_util::stream::{FuturesUnordered, Stream, StreamExt};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A set of futures submitting operations, run concurrently on the current
+/// task, whose outputs are collected as they complete.
+///
+/// This is the counterpart of `tokio::task::JoinSet` for the futures of this
+/// runtime, which are not `Send`: a server can push a read per connection and
+/// handle each of them as it completes, with [`join_next`], then push the
+/// next read. Futures can be pushed at any time, including while others are
+/// pending. They only run while the set is awaited.
+///
+/// Dropping the set, or calling [`abort_all`], drops the pending futures and
+/// cancels the operations they submitted which are still in flight, rather
+/// than leaving them to run to completion. Only the operations submitted while
+/// the set polls its futures are tracked, not those of tasks spawned from
+/// them, and only those dropped along with the futures are canceled, not those
+/// owned by an output already returned. On a runtime without io_uring, the
+/// futures are dropped, but the blocking tasks they started run to
+/// completion.
+///
+/// [`join_next`]: OpSet::join_next
+/// [`abort_all`]: OpSet::abort_all
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::fs::File;
+/// use tokio_uring::OpSet;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let mut reads = OpSet::new();
+///         for path in ["a.txt", "b.txt", "c.txt"] {
+///             reads.push(async move {
+///                 let file = File::open(path).await?;
+///                 let (res, buf) = file.read_at(vec![0; 4096], 0).await;
+///                 res.map(|n| (path, buf[..n].to_vec()))
+///             });
+///         }
+///
+///         while let Some(res) = reads.join_next().await {
+///             let (path, data) = res?;
+///             println!("{}: {} bytes", path, data.len());
+///         }
+///         Ok::<_, std::io::Error>(())
+///     })?;
+///     Ok(())
+/// }
+/// ```
+pub struct OpSet<T> {
+    /// Scope the operations of the futures are submitted in
+    scope: u64,
+
+    futures: FuturesUnordered<Scoped<T>>,
+}
+
+/// A future of the set, polled in the scope of the set.
+struct Scoped<T> {
+    scope: u64,
+    future: Pin<Box<dyn Future<Output = T>>>,
+}
+
+impl<T> OpSet<T> {
+    /// Creates an empty set.
+    ///
+    /// This must be called from within a runtime, to which the set is then
+    /// bound.
+    pub fn new() -> OpSet<T> {
+        // Without io_uring, there are no operations to track.
+        let scope = CONTEXT.with(|cx| {
+            if cx.is_fallback() {
+                return 0;
+            }
+            cx.with_driver_mut(|driver| driver.new_scope())
+        });
+
+        OpSet {
+            scope,
+            futures: FuturesUnordered::new(),
+        }
+    }
+
+    /// Adds a future to the set.
+    ///
+    /// The future is first polled the next time the set is.
+    pub fn push<F>(&mut self, future: F)
+    where
+        F: Future<Output = T> + 'static,
+    {
+        self.futures.push(Scoped {
+            scope: self.scope,
+            future: Box::pin(future),
+        });
+    }
+
+    /// Waits for one of the futures of the set to complete, returning its
+    /// output, or `None` if the set is empty.
+    pub async fn join_next(&mut self) -> Option<T> {
+        self.futures.next().await
+    }
+
+    /// Polls for one of the futures of the set to complete, returning its
+    /// output, or `None` if the set is empty.
+    pub fn poll_join_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.futures.poll_next_unpin(cx)
+    }
+
+    /// Drops all the futures of the set, canceling the operations they
+    /// submitted which are still in flight.
+    ///
+    /// The canceled operations complete in the background. The set can be
+    /// used again afterwards.
+    pub fn abort_all(&mut self) {
+        if self.futures.is_empty() {
+            return;
+        }
+
+        // Dropping the futures hands their operations to the driver, which
+        // keeps track of them until they complete.
+        self.futures.clear();
+        CONTEXT.with(|cx| {
+            if cx.is_set() {
+                cx.with_driver_mut(|driver| driver.cancel_scope(self.scope))
+            }
+        });
+    }
+
+    /// Returns the number of futures in the set.
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+
+    /// Returns `true` if the set holds no futures.
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+}
+
+impl<T> Default for OpSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stream for OpSet<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_join_next(cx)
+    }
+}
+
+impl<T> fmt::Debug for OpSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpSet").field("len", &self.len()).finish()
+    }
+}
+
+impl<T> Drop for OpSet<T> {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
+
+impl<T> Future for Scoped<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        /// Restores the previous scope, even if the future panics.
+        struct Enter(Option<u64>);
+
+        impl Drop for Enter {
+            fn drop(&mut self) {
+                set_scope(self.0);
+            }
+        }
+
+        let _enter = Enter(set_scope(Some(self.scope)));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Set the scope operations are submitted in, returning the previous one.
+fn set_scope(scope: Option<u64>) -> Option<u64> {
+    CONTEXT.with(|cx| {
+        if cx.is_fallback() {
+            return None;
+        }
+        cx.with_driver_mut(|driver| driver.set_scope(scope))
+    })
+}
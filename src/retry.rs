@@ -0,0 +1,133 @@
+use crate::BufResult;
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+/// A policy retrying operations failing with transient errors.
+///
+/// Networked filesystems such as NFS may fail operations with errors which go
+/// away when the operation is submitted again, e.g. `ESTALE` after a server
+/// failover. A `Retry` resubmits such operations, waiting for a backoff which
+/// doubles after each attempt.
+///
+/// Only the errors the policy was configured with are retried, by default
+/// `ESTALE` and `EAGAIN`. Any other error, such as `ENOSPC` or `EACCES`, is
+/// returned right away.
+///
+/// See [`with_retry`] for the common case.
+#[derive(Clone)]
+pub struct Retry {
+    max_retries: u32,
+    backoff: Duration,
+    errnos: Vec<i32>,
+}
+
+impl Retry {
+    /// Creates a policy retrying an operation up to `max_retries` times,
+    /// first after `backoff`, then after twice as long as the previous time.
+    pub fn new(max_retries: u32, backoff: Duration) -> Retry {
+        Retry {
+            max_retries,
+            backoff,
+            errnos: vec![libc::ESTALE, libc::EAGAIN],
+        }
+    }
+
+    /// Also retries operations failing with the OS error `errno`.
+    pub fn retry_on(&mut self, errno: i32) -> &mut Self {
+        if !self.errnos.contains(&errno) {
+            self.errnos.push(errno);
+        }
+        self
+    }
+
+    /// Returns `true` if the policy retries operations failing with `err`.
+    pub fn is_retried(&self, err: &io::Error) -> bool {
+        err.raw_os_error()
+            .is_some_and(|errno| self.errnos.contains(&errno))
+    }
+
+    /// Runs the operation submitted by `op` on `buf`, resubmitting it as long
+    /// as it fails with an error the policy retries.
+    ///
+    /// `op` is passed the buffer returned by the previous attempt. The result
+    /// and buffer of the last attempt are returned.
+    pub async fn run<T, R, F, Fut>(&self, buf: T, mut op: F) -> BufResult<R, T>
+    where
+        F: FnMut(T) -> Fut,
+        Fut: Future<Output = BufResult<R, T>>,
+    {
+        let mut backoff = self.backoff;
+        let (mut res, mut buf) = op(buf).await;
+
+        for _ in 0..self.max_retries {
+            match res {
+                Err(ref e) if self.is_retried(e) => {}
+                _ => break,
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+
+            (res, buf) = op(buf).await;
+        }
+
+        (res, buf)
+    }
+}
+
+impl fmt::Debug for Retry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("max_retries", &self.max_retries)
+            .field("backoff", &self.backoff)
+            .field("errnos", &self.errnos)
+            .finish()
+    }
+}
+
+/// Runs the operation submitted by `op` on `buf`, resubmitting it up to
+/// `max_retries` times while it fails with a transient error.
+///
+/// The operation is retried on `ESTALE` and `EAGAIN`, first after `backoff`,
+/// then after twice as long as the previous time. The result and buffer of
+/// the last attempt are returned. See [`Retry`] to retry on other errors.
+///
+/// This composes with the other wrappers of operations, e.g. bounding each
+/// attempt with [`link_timeout`].
+///
+/// [`link_timeout`]: crate::LinkTimeoutExt::link_timeout
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tokio_uring::fs::File;
+/// use tokio_uring::LinkTimeoutExt;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let file = File::open("/mnt/nfs/data.bin").await?;
+///         let (res, buf) = tokio_uring::with_retry(3, Duration::from_millis(10), vec![0; 4096], |buf| {
+///             file.read_at(buf, 0).link_timeout(Duration::from_secs(1))
+///         })
+///         .await;
+///         println!("{:?}", &buf[..res?]);
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn with_retry<T, R, F, Fut>(
+    max_retries: u32,
+    backoff: Duration,
+    buf: T,
+    op: F,
+) -> BufResult<R, T>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = BufResult<R, T>>,
+{
+    Retry::new(max_retries, backoff).run(buf, op).await
+}
@@ -1,11 +1,13 @@
 use crate::driver::Driver;
 use crate::util::PhantomUnsendUnsync;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::marker::PhantomData;
 
 /// Owns the driver and resides in thread-local storage.
 pub(crate) struct RuntimeContext {
     driver: RefCell<Option<Driver>>,
+    /// Set while a runtime without io_uring runs on this thread.
+    fallback: Cell<bool>,
     _phantom: PhantomUnsendUnsync,
 }
 
@@ -14,6 +16,7 @@ impl RuntimeContext {
     pub(crate) const fn new() -> Self {
         Self {
             driver: RefCell::new(None),
+            fallback: Cell::new(false),
             _phantom: PhantomData,
         }
     }
@@ -35,6 +38,16 @@ impl RuntimeContext {
         *guard = None;
     }
 
+    /// Mark whether a runtime without io_uring runs on this thread.
+    pub(crate) fn set_fallback(&self, fallback: bool) {
+        self.fallback.set(fallback);
+    }
+
+    /// Check if a runtime without io_uring runs on this thread
+    pub(crate) fn is_fallback(&self) -> bool {
+        self.fallback.get()
+    }
+
     /// Check if driver is initialized
     pub(crate) fn is_set(&self) -> bool {
         self.driver
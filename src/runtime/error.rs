@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Error returned by [`Builder::build`] when the runtime cannot be created.
+///
+/// Creating the ring is the step most likely to fail on a new deployment
+/// target. The variants tell the usual causes apart, each carrying the
+/// underlying error.
+///
+/// [`Builder::build`]: crate::Builder::build
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RuntimeError {
+    /// The kernel does not support io_uring, or it was built without it.
+    Unsupported(io::Error),
+
+    /// io_uring is blocked, by a seccomp filter, e.g. the default one of
+    /// container runtimes, or by the `kernel.io_uring_disabled` sysctl.
+    PermissionDenied(io::Error),
+
+    /// The memory of the ring exceeds `RLIMIT_MEMLOCK`.
+    ///
+    /// Kernels older than 5.12 account the memory of the ring against the
+    /// locked memory limit of the process. Raise the limit, e.g. with
+    /// `ulimit -l` or [`Builder::try_raise_memlock`], or request fewer
+    /// entries with [`Builder::entries`].
+    ///
+    /// [`Builder::try_raise_memlock`]: crate::Builder::try_raise_memlock
+    /// [`Builder::entries`]: crate::Builder::entries
+    MemlockExceeded(io::Error),
+
+    /// Any other failure.
+    Other(io::Error),
+}
+
+impl RuntimeError {
+    /// Returns the underlying I/O error.
+    pub fn io_error(&self) -> &io::Error {
+        match self {
+            RuntimeError::Unsupported(e)
+            | RuntimeError::PermissionDenied(e)
+            | RuntimeError::MemlockExceeded(e)
+            | RuntimeError::Other(e) => e,
+        }
+    }
+}
+
+impl From<io::Error> for RuntimeError {
+    fn from(e: io::Error) -> RuntimeError {
+        match e.raw_os_error() {
+            Some(libc::ENOSYS) => RuntimeError::Unsupported(e),
+            Some(libc::EPERM) | Some(libc::EACCES) => RuntimeError::PermissionDenied(e),
+            Some(libc::ENOMEM) => RuntimeError::MemlockExceeded(e),
+            _ => RuntimeError::Other(e),
+        }
+    }
+}
+
+impl From<RuntimeError> for io::Error {
+    fn from(e: RuntimeError) -> io::Error {
+        match e {
+            RuntimeError::Unsupported(e)
+            | RuntimeError::PermissionDenied(e)
+            | RuntimeError::MemlockExceeded(e)
+            | RuntimeError::Other(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::Unsupported(e) => {
+                write!(f, "io_uring is not supported by the kernel: {}", e)
+            }
+            RuntimeError::PermissionDenied(e) => write!(
+                f,
+                "io_uring is blocked, by a seccomp filter or the \
+                 kernel.io_uring_disabled sysctl: {}",
+                e
+            ),
+            RuntimeError::MemlockExceeded(e) => write!(
+                f,
+                "the ring exceeds RLIMIT_MEMLOCK, raise it with `ulimit -l` \
+                 or use fewer entries: {}",
+                e
+            ),
+            RuntimeError::Other(e) => write!(f, "failed to create the runtime: {}", e),
+        }
+    }
+}
+
+impl Error for RuntimeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.io_error())
+    }
+}
@@ -1,14 +1,21 @@
 use crate::driver::Driver;
 
+use futures_util::future::{self, Either};
 use std::future::Future;
 use std::io;
 use std::mem::ManuallyDrop;
-use std::os::unix::io::AsRawFd;
+use std::time::Instant;
 use tokio::io::unix::AsyncFd;
 use tokio::task::LocalSet;
 
 mod context;
 
+mod error;
+pub use error::RuntimeError;
+
+mod notify;
+pub use notify::Notifier;
+
 pub(crate) use context::RuntimeContext;
 
 thread_local! {
@@ -22,6 +29,17 @@ pub struct Runtime {
 
     /// Tokio runtime, always current-thread
     rt: ManuallyDrop<tokio::runtime::Runtime>,
+
+    /// Whether the runtime runs without io_uring
+    fallback: bool,
+}
+
+/// Returns whether the current thread runs a runtime without io_uring, as
+/// started by [`start_or_fallback`].
+///
+/// [`start_or_fallback`]: crate::start_or_fallback
+pub(crate) fn is_fallback() -> bool {
+    CONTEXT.try_with(|cx| cx.is_fallback()).unwrap_or(false)
 }
 
 /// Spawns a new asynchronous task, returning a [`JoinHandle`] for it.
@@ -33,13 +51,19 @@ pub struct Runtime {
 ///
 /// This function must be called from the context of a `tokio-uring` runtime.
 ///
+/// The task runs on the thread of the runtime, and never moves to another
+/// thread, as the operations it submits are bound to the runtime's ring. The
+/// task therefore does not need to be `Send`, and may hold `tokio-uring`
+/// resources such as files and sockets.
+///
+/// The task can be canceled with [`JoinHandle::abort`]. Operations it had in
+/// flight keep their buffers until the kernel completes them.
+///
 /// [`JoinHandle`]: tokio::task::JoinHandle
+/// [`JoinHandle::abort`]: tokio::task::JoinHandle::abort
 ///
 /// # Examples
 ///
-/// In this example, a server is started and `spawn` is used to start a new task
-/// that processes each received connection.
-///
 /// ```no_run
 /// tokio_uring::start(async {
 ///     let handle = tokio_uring::spawn(async {
@@ -60,7 +84,12 @@ impl Runtime {
         let rt = tokio::runtime::Builder::new_current_thread()
             .on_thread_park(|| {
                 CONTEXT.with(|x| {
-                    let _ = x.with_driver_mut(|d| d.uring.submit());
+                    x.with_driver_mut(|d| {
+                        let _ = d.submit();
+                        // A completion posted while spinning makes the ring
+                        // readable, so the thread does not actually sleep.
+                        d.spin();
+                    })
                 });
             })
             .enable_all()
@@ -72,27 +101,87 @@ impl Runtime {
 
         let driver = Driver::new(b)?;
 
-        let driver_fd = driver.as_raw_fd();
-
         CONTEXT.with(|cx| cx.set_driver(driver));
 
-        let drive = {
-            let _guard = rt.enter();
-            let driver = AsyncFd::new(driver_fd).unwrap();
+        let idle_deadline = || CONTEXT.with(|cx| cx.with_driver_mut(|d| d.idle_deadline()));
+
+        let drive = async move {
+            loop {
+                // Wait for the ring to be (re)built
+                let fd = crate::future::poll_fn(|cx| {
+                    CONTEXT.with(|x| x.with_driver_mut(|driver| driver.poll_ring(cx)))
+                })
+                .await;
+                let driver = AsyncFd::new(fd).unwrap();
 
-            async move {
                 loop {
-                    // Wait for read-readiness
-                    let mut guard = driver.readable().await.unwrap();
-                    CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.tick()));
-                    guard.clear_ready();
+                    // Wait for read-readiness, or for the ring to be idle
+                    let mut guard = match idle_deadline() {
+                        None => driver.readable().await.unwrap(),
+                        Some(deadline) => {
+                            let readable = driver.readable();
+                            let idle = tokio::time::sleep_until(deadline.into());
+                            tokio::pin!(readable, idle);
+                            match future::select(readable, idle).await {
+                                Either::Left((guard, _)) => guard.unwrap(),
+                                Either::Right(_) => match idle_deadline() {
+                                    Some(deadline) if deadline <= Instant::now() => break,
+                                    _ => continue,
+                                },
+                            }
+                        }
+                    };
+
+                    if CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.tick())) {
+                        guard.clear_ready();
+                    } else {
+                        // Only part of the completions were reaped. Let the
+                        // woken tasks run and submit the operations they
+                        // queued before reaping more.
+                        drop(guard);
+                        tokio::task::yield_now().await;
+                        CONTEXT.with(|cx| {
+                            let _ = cx.with_driver_mut(|driver| driver.submit());
+                        });
+                    }
                 }
+
+                // The ring is idle: deregister it before it is closed, as
+                // its file descriptor may then be reused.
+                drop(driver);
+                CONTEXT.with(|cx| cx.with_driver_mut(|driver| driver.release_ring()));
             }
         };
 
         local.spawn_local(drive);
 
-        Ok(Runtime { local, rt })
+        Ok(Runtime {
+            local,
+            rt,
+            fallback: false,
+        })
+    }
+
+    /// Create a new tokio_uring runtime on the current thread, or, if io_uring
+    /// is unavailable, a runtime dispatching file operations to the blocking
+    /// thread pool.
+    pub(crate) fn new_or_fallback(b: &crate::Builder) -> io::Result<Runtime> {
+        match Runtime::new(b) {
+            Err(e) if is_unavailable(&e) => {}
+            res => return res,
+        }
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        CONTEXT.with(|cx| cx.set_fallback(true));
+
+        Ok(Runtime {
+            local: ManuallyDrop::new(LocalSet::new()),
+            rt: ManuallyDrop::new(rt),
+            fallback: true,
+        })
     }
 
     /// Runs a future to completion on the current runtime
@@ -118,12 +207,35 @@ impl Drop for Runtime {
             ManuallyDrop::drop(&mut self.rt);
         }
 
+        // Files dropped along with the tasks are closing by now. Any other
+        // file outlives the runtime, and is only closed, synchronously, once
+        // dropped, which is usually a leak.
+        #[cfg(all(debug_assertions, feature = "tracing"))]
+        for fd in crate::driver::open_fds() {
+            tracing::warn!(fd, "file descriptor still open at runtime shutdown");
+        }
+
         // once tasks are dropped, we can unset the driver
-        // this will block until all completions are received
-        CONTEXT.with(|rc| rc.unset_driver())
+        // this will block until all completions are received, or the drain
+        // timeout elapses
+        if self.fallback {
+            CONTEXT.with(|rc| rc.set_fallback(false))
+        } else {
+            CONTEXT.with(|rc| rc.unset_driver())
+        }
     }
 }
 
+/// Whether creating the ring failed because io_uring is not available at all,
+/// as opposed to being misconfigured: the kernel lacks it, or it is blocked by
+/// a seccomp filter or the `kernel.io_uring_disabled` sysctl.
+fn is_unavailable(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::ENOSYS) | Some(libc::EPERM) | Some(libc::EACCES)
+    )
+}
+
 #[cfg(test)]
 mod test {
 
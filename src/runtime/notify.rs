@@ -0,0 +1,119 @@
+use crate::driver::{EventfdRead, Op};
+use crate::runtime::CONTEXT;
+
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::Arc;
+
+/// Wakes a `tokio-uring` runtime from any thread.
+///
+/// A `Notifier` wraps an `eventfd`. Calling [`notify`] from any thread
+/// completes the read that [`notified`] keeps in flight on the runtime's ring,
+/// waking the task waiting on it. Notifications sent while no task is waiting
+/// are not lost, and several notifications may be coalesced into one wakeup.
+///
+/// The handle is cheap to clone and can be sent to other threads.
+///
+/// [`notify`]: Notifier::notify
+/// [`notified`]: Notifier::notified
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_uring::Notifier;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     tokio_uring::start(async {
+///         let notifier = Notifier::new()?;
+///
+///         let remote = notifier.clone();
+///         std::thread::spawn(move || remote.notify().unwrap());
+///
+///         notifier.notified().await?;
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Notifier {
+    fd: Arc<std::fs::File>,
+}
+
+impl Notifier {
+    /// Create a new notifier, backed by a new `eventfd`.
+    pub fn new() -> io::Result<Notifier> {
+        let fd = syscall!(eventfd(0, libc::EFD_CLOEXEC))?;
+        let fd = unsafe { std::fs::File::from_raw_fd(fd) };
+        Ok(Notifier { fd: Arc::new(fd) })
+    }
+
+    /// Wake the task waiting in [`notified`], or the next one to call it.
+    ///
+    /// This may be called from any thread, including ones not running a
+    /// `tokio-uring` runtime.
+    ///
+    /// [`notified`]: Notifier::notified
+    pub fn notify(&self) -> io::Result<()> {
+        let one = 1u64.to_ne_bytes();
+        syscall!(write(self.as_raw_fd(), one.as_ptr() as *const _, one.len()))?;
+        Ok(())
+    }
+
+    /// Wait until [`notify`] has been called, consuming all pending
+    /// notifications.
+    ///
+    /// The wait is a read on the `eventfd` submitted to the ring, so it must be
+    /// called from the context of a `tokio-uring` runtime. Dropping the
+    /// returned future before it completes cancels the read. If the read
+    /// already consumed notifications, they are posted again, so they wake the
+    /// next wait instead of being lost.
+    ///
+    /// [`notify`]: Notifier::notify
+    pub async fn notified(&self) -> io::Result<()> {
+        let mut read = CancelOnDrop {
+            op: Op::eventfd_read(&self.fd)?,
+            fd: self.fd.clone(),
+        };
+        (&mut read.op).await.map(drop)
+    }
+}
+
+/// Cancels the read, if still in flight once dropped, and posts again the
+/// notifications it consumed.
+struct CancelOnDrop {
+    op: Op<EventfdRead>,
+    fd: Arc<std::fs::File>,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        // The read is dropped right after, its completions are then passed
+        // to the callback rather than discarded.
+        if let Some(index) = self.op.index() {
+            CONTEXT.with(|cx| {
+                if cx.is_set() {
+                    cx.with_driver_mut(|driver| {
+                        let notifier = Notifier {
+                            fd: self.fd.clone(),
+                        };
+                        driver.on_discard(
+                            index,
+                            Box::new(move |cqe| {
+                                if cqe.result.is_ok() {
+                                    let _ = notifier.notify();
+                                }
+                            }),
+                        );
+                        let _ = driver.cancel(index);
+                    })
+                }
+            });
+        }
+    }
+}
+
+impl AsRawFd for Notifier {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
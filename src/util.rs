@@ -2,3 +2,18 @@ use std::marker::PhantomData;
 
 /// Utility ZST for ensuring that opcodes are `!Send` and `!Sync`.
 pub(crate) type PhantomUnsendUnsync = PhantomData<*mut ()>;
+
+/// Run a blocking function on the blocking thread pool.
+///
+/// Used for the few operations io_uring has no opcode for. A panic in `f` is
+/// resumed on the calling task.
+pub(crate) async fn asyncify<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
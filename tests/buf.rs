@@ -40,6 +40,43 @@ fn test_vec() {
     assert_eq!(&v[..], &DATA[..10]);
 }
 
+#[test]
+fn test_array() {
+    use tokio_uring::fs::OpenOptions;
+
+    let mut a = Box::new([0u8; 4]);
+    assert_eq!(a.as_ptr(), a.stable_ptr());
+    assert_eq!(a.as_mut_ptr(), a.stable_mut_ptr());
+    assert_eq!(a.bytes_init(), 4);
+    assert_eq!(a.bytes_total(), 4);
+
+    let empty = Box::new([0u8; 0]);
+    assert_eq!(empty.bytes_init(), 0);
+    assert_eq!(empty.bytes_total(), 0);
+
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    tokio_uring::start(async {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        let (res, _) = file.write_at(Box::new(*b"\0\0\0\x05hello"), 0).await;
+        assert_eq!(res.unwrap(), 9);
+
+        // A length prefix, then the data it announces
+        let (res, len) = file.read_at(Box::new([0u8; 4]), 0).await;
+        assert_eq!(res.unwrap(), 4);
+        assert_eq!(u32::from_be_bytes(*len), 5);
+        let (res, data) = file.read_at(Box::new([0u8; 8]), 4).await;
+        assert_eq!(&data[..res.unwrap()], b"hello");
+
+        let (res, _) = file.read_at(empty, 0).await;
+        assert_eq!(res.unwrap(), 0);
+    });
+}
+
 #[test]
 fn test_slice() {
     let v = &b""[..];
@@ -138,3 +175,90 @@ fn can_deref_slice_into_uninit_buf() {
     buf.copy_from_slice(&[43]);
     assert_eq!(&buf[..], &[43]);
 }
+
+#[test]
+fn test_pool() {
+    use tokio_uring::buf::BufPool;
+
+    let pool = BufPool::new(2, 16);
+    assert_eq!(pool.available(), 2);
+
+    let mut buf = pool.get();
+    let ptr = buf.stable_ptr();
+    assert_eq!(buf.bytes_init(), 0);
+    assert_eq!(buf.bytes_total(), 16);
+    assert_eq!(pool.available(), 1);
+
+    unsafe {
+        std::ptr::copy(DATA.as_ptr(), buf.stable_mut_ptr(), 10);
+        buf.set_init(10);
+    }
+    assert_eq!(&buf[..], &DATA[..10]);
+
+    // A returned buffer is reused, and reset.
+    drop(buf);
+    assert_eq!(pool.available(), 2);
+    let buf = pool.get();
+    assert_eq!(buf.stable_ptr(), ptr);
+    assert_eq!(buf.bytes_init(), 0);
+
+    // The pool grows when exhausted.
+    let bufs: Vec<_> = (0..3).map(|_| pool.get()).collect();
+    assert_eq!(pool.available(), 0);
+    drop(bufs);
+    drop(buf);
+    assert_eq!(pool.available(), 4);
+}
+
+#[test]
+fn test_split() {
+    use tokio_uring::buf::SplitBuf;
+
+    let mut buf = Vec::with_capacity(16);
+    buf.extend_from_slice(b"hello");
+    let ptr = buf.stable_ptr();
+
+    let mut parts = buf.split([8..16, 0..8]);
+    assert_eq!(parts[0].stable_ptr(), unsafe { ptr.add(8) });
+    assert_eq!(parts[0].bytes_init(), 0);
+    assert_eq!(parts[0].bytes_total(), 8);
+    assert_eq!(&parts[1][..], b"hello");
+    assert_eq!(parts[1].bytes_total(), 8);
+
+    // Initializing the regions initializes the joined buffer.
+    for part in &mut parts {
+        let len = part.bytes_total();
+        unsafe {
+            std::ptr::copy(DATA.as_ptr(), part.stable_mut_ptr(), len);
+            part.set_init(len);
+        }
+    }
+
+    // Regions are only joined once all of them are returned.
+    let last = parts.pop().unwrap();
+    let mut parts = SplitBuf::join(parts).unwrap_err();
+    parts.push(last);
+
+    let buf = SplitBuf::join(parts).unwrap();
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(&buf[..8], &DATA[..8]);
+    assert_eq!(&buf[8..], &DATA[..8]);
+
+    // A gap leaves the rest of the buffer uninitialized.
+    let mut parts = buf.split([0..4, 12..16]);
+    unsafe { parts[1].set_init(4) };
+    drop(parts.remove(0));
+    let buf = SplitBuf::join(parts).unwrap();
+    assert_eq!(buf.len(), 16);
+
+    let mut parts = Vec::<u8>::with_capacity(16).split([0..4, 12..16]);
+    unsafe { parts[1].set_init(4) };
+    let buf = SplitBuf::join(parts).unwrap();
+    assert!(buf.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "ranges overlap")]
+fn test_split_overlap() {
+    Vec::<u8>::with_capacity(16).split([8..16, 0..9]);
+}
@@ -6,3 +6,34 @@ fn basic_remove_dir() {
         assert!(std::fs::metadata(temp_dir.path()).is_err());
     });
 }
+
+#[test]
+fn read_dir_sorted() {
+    use tokio_uring::fs;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    for name in ["b.log", "c.txt", "a.log"] {
+        std::fs::write(dir.path().join(name), b"").unwrap();
+    }
+
+    tokio_uring::start(async {
+        let names = |entries: Vec<std::fs::DirEntry>| -> Vec<_> {
+            entries.iter().map(|entry| entry.file_name()).collect()
+        };
+
+        // No `.` or `..` entries.
+        let entries = fs::read_dir_sorted(dir.path()).await.unwrap();
+        assert_eq!(names(entries), ["a.log", "b.log", "c.txt"]);
+
+        let entries = fs::read_dir_filtered(dir.path(), |entry| {
+            entry.path().extension().unwrap() == "log"
+        })
+        .await
+        .unwrap();
+        assert_eq!(names(entries), ["a.log", "b.log"]);
+
+        let missing = dir.path().join("missing");
+        let err = fs::read_dir_sorted(missing).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    });
+}
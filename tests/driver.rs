@@ -122,6 +122,444 @@ fn completion_overflow() {
         });
 }
 
+#[test]
+fn limited_cqe_per_tick() {
+    use tokio::task::JoinSet;
+
+    tokio_uring::builder().max_cqe_per_tick(1).start(async {
+        let mut js = JoinSet::new();
+
+        for _ in 0..50 {
+            js.spawn_local(tokio_uring::no_op());
+        }
+
+        // Every completion must eventually be reaped, even though they all
+        // become available at once.
+        while let Some(res) = js.join_next().await {
+            res.unwrap().unwrap();
+        }
+    });
+}
+
+#[test]
+fn limited_in_flight() {
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), b"hello world").unwrap();
+
+    tokio_uring::builder().max_in_flight(1).start(async {
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        // A task awaiting a slot while its own operation holds the last one
+        // must not deadlock.
+        let (a, b) =
+            futures::future::join(file.read_at(vec![0; 5], 0), file.read_at(vec![0; 5], 6)).await;
+        assert_eq!(&a.1[..a.0.unwrap()], b"hello");
+        assert_eq!(&b.1[..b.0.unwrap()], b"world");
+
+        // A dropped operation releases its slot once the kernel completes it.
+        poll_once(file.read_at(vec![0; 5], 0)).await;
+        let (res, _) = file.read_at(vec![0; 5], 0).await;
+        res.unwrap();
+    });
+}
+
+#[test]
+fn tagged_completions() {
+    use std::sync::{Arc, Mutex};
+
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), b"hello world").unwrap();
+
+    let completions = Arc::new(Mutex::new(vec![]));
+    let c = completions.clone();
+
+    tokio_uring::builder()
+        .on_tagged_completion(move |completion| c.lock().unwrap().push(completion))
+        .start(async {
+            let file = File::open(tempfile.path()).await.unwrap();
+
+            let (res, _) = file.read_at_tagged(vec![0; 5], 0, 7).await;
+            assert_eq!(res.unwrap(), 5);
+
+            // Untagged operations are not reported.
+            file.read_at(vec![0; 5], 0).await.0.unwrap();
+
+            // The file was opened read-only.
+            let (res, _) = file.write_at_tagged(b"hello".to_vec(), 0, 8).await;
+            assert!(res.is_err());
+        });
+
+    let completions = completions.lock().unwrap();
+    assert_eq!(completions.len(), 2);
+    assert_eq!(completions[0].tag(), 7);
+    assert_eq!(completions[0].result(), 5);
+    assert_eq!(completions[1].tag(), 8);
+    assert_eq!(completions[1].result(), -libc::EBADF);
+}
+
+#[test]
+fn epoll_ctl() {
+    use std::io::ErrorKind;
+    use std::os::unix::io::AsRawFd;
+
+    tokio_uring::start(async {
+        let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        assert!(epfd >= 0);
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let fd = socket.as_raw_fd();
+        let event = libc::epoll_event {
+            events: libc::EPOLLIN as u32,
+            u64: 42,
+        };
+
+        let err = tokio_uring::epoll_ctl(epfd, 42, fd, Some(event))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        match tokio_uring::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, Some(event)).await {
+            Err(e) if e.kind() == ErrorKind::Unsupported => {}
+            res => {
+                res.unwrap();
+
+                // The entry was added.
+                let err = tokio_uring::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, Some(event))
+                    .await
+                    .unwrap_err();
+                assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+
+                tokio_uring::epoll_ctl(epfd, libc::EPOLL_CTL_DEL, fd, None)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        unsafe { libc::close(epfd) };
+    });
+}
+
+#[test]
+fn poll_fd() {
+    use futures::StreamExt;
+    use std::os::unix::io::AsRawFd;
+
+    tokio_uring::start(async {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let fd = socket.as_raw_fd();
+
+        let mut readable = tokio_uring::poll_fd(fd, libc::POLLIN as u32).unwrap();
+        for _ in 0..2 {
+            socket.send_to(b"ping", addr).unwrap();
+            let events = readable.next().await.unwrap().unwrap();
+            assert_ne!(events & libc::POLLIN as u32, 0);
+        }
+        readable.remove().await.unwrap();
+
+        // Dropping an armed poll removes it too, so the runtime can shut
+        // down.
+        let readable = tokio_uring::poll_fd(fd, libc::POLLIN as u32).unwrap();
+        drop(readable);
+        tokio_uring::no_op().await.unwrap();
+    });
+}
+
+#[test]
+fn op_set() {
+    use std::io::Write;
+    use std::os::unix::io::FromRawFd;
+    use tokio_uring::OpSet;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(b"hello world").unwrap();
+        let file = std::rc::Rc::new(File::open(tempfile.path()).await.unwrap());
+
+        let mut reads = OpSet::new();
+        for len in [5, 11] {
+            let file = file.clone();
+            reads.push(async move {
+                let (res, buf) = file.read_at(vec![0; len], 0).await;
+                buf[..res.unwrap()].to_vec()
+            });
+        }
+        assert_eq!(reads.len(), 2);
+
+        // Futures can be pushed while others are pending.
+        let first = reads.join_next().await.unwrap();
+        reads.push(async { b"pushed".to_vec() });
+        let mut outputs = vec![first];
+        while let Some(output) = reads.join_next().await {
+            outputs.push(output);
+        }
+        outputs.sort();
+        assert_eq!(outputs, [&b"hello"[..], b"hello world", b"pushed"]);
+        assert!(reads.is_empty());
+
+        // Aborting cancels the read in flight, which would otherwise consume
+        // the data written afterwards.
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = std::rc::Rc::new(unsafe { File::from_raw_fd(fds[0]) });
+        let mut tx = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+
+        let pending = rx.clone();
+        reads.push(async move { pending.read(vec![0; 16]).await.1 });
+        assert!(futures::poll!(futures::StreamExt::next(&mut reads)).is_pending());
+        reads.abort_all();
+        tokio_uring::no_op().await.unwrap();
+
+        tx.write_all(b"ping").unwrap();
+        let (res, buf) = rx.read(vec![0; 16]).await;
+        assert_eq!(&buf[..res.unwrap()], b"ping");
+
+        // Operations owned by the output of a completed future are not
+        // canceled along with the pending ones.
+        let mut reads = OpSet::new();
+        let owned = rx.clone();
+        reads.push(async move {
+            let mut read = Box::pin(async move { owned.read(vec![0; 16]).await });
+            assert!(futures::poll!(read.as_mut()).is_pending());
+            Some(read)
+        });
+        reads.push(async { futures::future::pending().await });
+        let read = reads.join_next().await.unwrap().unwrap();
+        reads.abort_all();
+        tokio_uring::no_op().await.unwrap();
+
+        tx.write_all(b"pong").unwrap();
+        let (res, buf) = read.await;
+        assert_eq!(&buf[..res.unwrap()], b"pong");
+    });
+}
+
+#[test]
+fn submit_raw() {
+    use io_uring::{opcode, types};
+    use std::os::unix::io::AsRawFd;
+
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), b"hello world").unwrap();
+
+    tokio_uring::start(async {
+        let file = File::open(tempfile.path()).await.unwrap();
+        let fd = types::Fd(file.as_raw_fd());
+
+        let (res, buf) = unsafe {
+            tokio_uring::submit_raw(vec![0u8; 5], |buf| {
+                opcode::Read::new(fd, buf.as_mut_ptr(), buf.len() as u32)
+                    .offset(6)
+                    .build()
+            })
+        }
+        .await;
+        assert_eq!(res.unwrap(), 5);
+        assert_eq!(buf, b"world");
+
+        // Errors are translated from the negated error code.
+        let (res, ()) =
+            unsafe { tokio_uring::submit_raw((), |_| opcode::Fsync::new(types::Fd(-1)).build()) }
+                .await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+    });
+}
+
+#[test]
+fn link_timeout() {
+    use std::io::ErrorKind;
+    use std::os::unix::io::FromRawFd;
+    use std::time::{Duration, Instant};
+    use tokio_uring::LinkTimeoutExt;
+
+    tokio_uring::start(async {
+        // Reading from a pipe without a writer blocks until timed out.
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let tx = unsafe { File::from_raw_fd(fds[1]) };
+
+        let start = Instant::now();
+        let (res, buf) = rx
+            .read_at(Vec::with_capacity(16), 0)
+            .link_timeout(Duration::from_millis(50))
+            .await;
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::TimedOut);
+        assert_eq!(buf.capacity(), 16);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // An operation completing in time is unaffected.
+        tx.write_at(b"hello".to_vec(), 0).await.0.unwrap();
+        let (res, buf) = rx
+            .read_at(Vec::with_capacity(16), 0)
+            .link_timeout(Duration::from_secs(5))
+            .await;
+        assert_eq!(&buf[..res.unwrap()], b"hello");
+    });
+}
+
+#[test]
+fn force_async() {
+    use tokio_uring::ForceAsyncExt;
+
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), b"hello world").unwrap();
+
+    tokio_uring::start(async {
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let (res, buf) = file.read_at(vec![0; 5], 6).force_async().await;
+        assert_eq!(res.unwrap(), 5);
+        assert_eq!(buf, b"world");
+
+        // Operations submitted afterwards are not flagged, and still complete.
+        let (res, buf) = file.read_at(vec![0; 5], 0).await;
+        assert_eq!(res.unwrap(), 5);
+        assert_eq!(buf, b"hello");
+    });
+}
+
+#[test]
+fn with_retry() {
+    use std::cell::Cell;
+    use std::future::Future;
+    use std::io;
+    use std::pin::Pin;
+    use std::time::Duration;
+
+    type ReadFuture<'a> =
+        Pin<Box<dyn Future<Output = tokio_uring::BufResult<usize, Vec<u8>>> + 'a>>;
+
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), b"hello world").unwrap();
+
+    tokio_uring::start(async {
+        let file = File::open(tempfile.path()).await.unwrap();
+        let attempts = Cell::new(0);
+
+        // Fails with `errno` until the given attempt, then reads the file.
+        let read = |errno: i32, failures: u32| {
+            let file = &file;
+            let attempts = &attempts;
+            move |buf: Vec<u8>| -> ReadFuture<'_> {
+                attempts.set(attempts.get() + 1);
+                if attempts.get() <= failures {
+                    let err = io::Error::from_raw_os_error(errno);
+                    Box::pin(async move { (Err(err), buf) })
+                } else {
+                    Box::pin(file.read_at(buf, 0))
+                }
+            }
+        };
+        let backoff = Duration::from_millis(1);
+
+        let (res, buf) =
+            tokio_uring::with_retry(3, backoff, Vec::with_capacity(16), read(libc::ESTALE, 2))
+                .await;
+        assert_eq!(&buf[..res.unwrap()], b"hello world");
+        assert_eq!(attempts.replace(0), 3);
+
+        // Other errors are not retried.
+        let (res, buf) =
+            tokio_uring::with_retry(3, backoff, Vec::with_capacity(16), read(libc::EACCES, 2))
+                .await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EACCES));
+        assert_eq!(buf.capacity(), 16);
+        assert_eq!(attempts.replace(0), 1);
+
+        // Unless configured to.
+        let (res, _) = tokio_uring::Retry::new(3, backoff)
+            .retry_on(libc::EIO)
+            .run(Vec::with_capacity(16), read(libc::EIO, 1))
+            .await;
+        res.unwrap();
+        assert_eq!(attempts.replace(0), 2);
+
+        // The last error is returned once out of retries.
+        let (res, buf) =
+            tokio_uring::with_retry(2, backoff, Vec::with_capacity(16), read(libc::EAGAIN, 5))
+                .await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EAGAIN));
+        assert_eq!(buf.capacity(), 16);
+        assert_eq!(attempts.get(), 3);
+    });
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn trace_spans() {
+    use std::fmt::Debug;
+    use std::os::unix::io::AsRawFd;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata};
+
+    /// Collects the fields of the operation spans, as `name=value`.
+    #[derive(Clone, Default)]
+    struct Collector(Arc<Mutex<Vec<Vec<String>>>>);
+
+    struct Fields<'a>(&'a mut Vec<String>);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl tracing::Subscriber for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut spans = self.0.lock().unwrap();
+            let mut fields = vec![];
+            attrs.record(&mut Fields(&mut fields));
+            spans.push(fields);
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, id: &Id, values: &Record<'_>) {
+            let mut spans = self.0.lock().unwrap();
+            values.record(&mut Fields(&mut spans[id.into_u64() as usize - 1]));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), b"hello world").unwrap();
+
+    let collector = Collector::default();
+    let fd = tracing::subscriber::with_default(collector.clone(), || {
+        tokio_uring::start(async {
+            let file = File::open(tempfile.path()).await.unwrap();
+            let (res, _) = file.read_at(vec![0; 5], 0).await;
+            assert_eq!(res.unwrap(), 5);
+            file.as_raw_fd()
+        })
+    });
+
+    let spans = collector.0.lock().unwrap();
+    let read = spans
+        .iter()
+        .find(|fields| fields.contains(&"op=\"read\"".to_string()))
+        .unwrap();
+    assert!(read.contains(&format!("fd={}", fd)));
+    assert!(read.contains(&"result=5".to_string()));
+    assert!(read.iter().any(|field| field.starts_with("queue_wait_us=")));
+    assert!(read.iter().any(|field| field.starts_with("kernel_us=")));
+    assert!(spans
+        .iter()
+        .any(|fields| fields.contains(&"op=\"openat\"".to_string())));
+}
+
 fn tempfile() -> NamedTempFile {
     NamedTempFile::new().unwrap()
 }
@@ -63,6 +63,994 @@ fn basic_write() {
     });
 }
 
+#[cfg(feature = "bytes")]
+#[test]
+fn read_at_bytes() {
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let bytes = file.read_at_bytes(5, 6).await.unwrap();
+        assert_eq!(&bytes[..], &HELLO[6..11]);
+
+        // Short at the end of the file, then empty.
+        let bytes = file.read_at_bytes(1024, 0).await.unwrap();
+        assert_eq!(&bytes[..], HELLO);
+        let bytes = file.read_at_bytes(1024, HELLO.len() as u64).await.unwrap();
+        assert!(bytes.is_empty());
+
+        assert!(file.read_at_bytes(0, 0).await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn read_at_least() {
+    use std::io::ErrorKind;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let (res, buf) = file.read_at_least(Vec::with_capacity(1024), 0, 4).await;
+        let n = res.unwrap();
+        assert!(n >= 4);
+        assert_eq!(&buf[..], &HELLO[..n]);
+
+        let (res, buf) = file.read_at_least(Vec::with_capacity(4), 0, 8).await;
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert!(buf.is_empty());
+
+        // Hitting the end of the file keeps what was read.
+        let pos = HELLO.len() as u64 - 2;
+        let (res, buf) = file.read_at_least(Vec::with_capacity(8), pos, 4).await;
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(&buf[..], &HELLO[HELLO.len() - 2..]);
+    });
+}
+
+#[test]
+fn append_file() {
+    use tokio_uring::fs::AppendFile;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(b"head").unwrap();
+
+        let log = AppendFile::open(tempfile.path()).await.unwrap();
+        assert_eq!(log.len(), 4);
+
+        // Concurrent appends do not overlap.
+        let (a, b) =
+            futures::future::join(log.append(b"aaa".to_vec()), log.append(b"bb".to_vec())).await;
+        let (a, b) = (a.0.unwrap(), b.0.unwrap());
+        assert_eq!(a, 4);
+        assert_eq!(b, 7);
+        assert_eq!(log.len(), 9);
+
+        let (res, buf) = log.read_exact_at(vec![0; 2], b).await;
+        res.unwrap();
+        assert_eq!(buf, b"bb");
+        log.close().await.unwrap();
+
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"headaaabb");
+
+        // The end is picked up when reopening.
+        let log = AppendFile::open(tempfile.path()).await.unwrap();
+        assert_eq!(log.append(b"c".to_vec()).await.0.unwrap(), 9);
+    });
+}
+
+#[test]
+fn set_len() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = File::create(tempfile.path()).await.unwrap();
+        assert_eq!(file.len().await.unwrap(), 0);
+        file.write_all_at(HELLO.to_vec(), 0).await.0.unwrap();
+        assert_eq!(file.len().await.unwrap(), HELLO.len() as u64);
+
+        file.set_len(5).await.unwrap();
+        assert_eq!(file.len().await.unwrap(), 5);
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), &HELLO[..5]);
+
+        // Extends with zeros.
+        file.set_len(8).await.unwrap();
+        let data = std::fs::read(tempfile.path()).unwrap();
+        assert_eq!(&data[..5], &HELLO[..5]);
+        assert_eq!(&data[5..], &[0; 3]);
+
+        assert!(file.set_len(u64::MAX).await.is_err());
+    });
+}
+
+#[test]
+fn truncate_to() {
+    use tokio_uring::fs::OpenOptions;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate_to(5)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), &HELLO[..5]);
+
+        // Writes are ordered after the truncation.
+        file.write_all_at(b"!".to_vec(), 5).await.0.unwrap();
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"hello!");
+
+        // Requires write access.
+        let err = OpenOptions::new()
+            .read(true)
+            .truncate_to(0)
+            .open(tempfile.path())
+            .await
+            .unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+    });
+}
+
+#[test]
+fn read_at_cancelable() {
+    use std::io::ErrorKind;
+    use std::os::unix::io::FromRawFd;
+
+    tokio_uring::start(async {
+        // Reading from a pipe without a writer blocks until canceled.
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let tx = unsafe { File::from_raw_fd(fds[1]) };
+
+        let (read, token) = rx.read_at_cancelable(Vec::with_capacity(16), 0);
+        let watchdog = tokio_uring::spawn(async move {
+            tokio::task::yield_now().await;
+            token.cancel();
+            assert!(token.is_canceled());
+        });
+
+        let (res, buf) = read.await;
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::Interrupted);
+        assert_eq!(buf.len(), 0);
+        watchdog.await.unwrap();
+
+        // A token canceled after completion has no effect.
+        tx.write_at(HELLO.to_vec(), 0).await.0.unwrap();
+        let (read, token) = rx.read_at_cancelable(Vec::with_capacity(16), 0);
+        let (res, buf) = read.await;
+        token.cancel();
+        assert_eq!(&buf[..res.unwrap()], HELLO);
+    });
+}
+
+#[test]
+fn readv_at_cancelable() {
+    use std::io::ErrorKind;
+    use std::os::unix::io::FromRawFd;
+
+    tokio_uring::start(async {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let tx = unsafe { File::from_raw_fd(fds[1]) };
+
+        // A read canceled before any data landed leaves the buffers as passed.
+        let mut first = Vec::with_capacity(4);
+        first.extend_from_slice(b"ab");
+        let (read, token) = rx.readv_at_cancelable(vec![first, Vec::with_capacity(64)], 0);
+        let watchdog = tokio_uring::spawn(async move {
+            tokio::task::yield_now().await;
+            token.cancel();
+        });
+
+        let (res, bufs) = read.await;
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::Interrupted);
+        assert_eq!(bufs[0], b"ab");
+        assert!(bufs[1].is_empty());
+        watchdog.await.unwrap();
+
+        // Data arriving as an in-flight read is canceled is either kept in
+        // the buffers, or left in the pipe.
+        let (read, token) = rx.readv_at_cancelable(bufs, 0);
+        let raw_tx = fds[1];
+        let watchdog = tokio_uring::spawn(async move {
+            tokio::task::yield_now().await;
+            let n = unsafe { libc::write(raw_tx, HELLO.as_ptr().cast(), HELLO.len()) };
+            assert_eq!(n, HELLO.len() as isize);
+            token.cancel();
+            assert!(token.is_canceled());
+        });
+
+        let (res, bufs) = read.await;
+        watchdog.await.unwrap();
+        match res {
+            Ok(n) => {
+                assert_eq!(n, HELLO.len());
+                assert_eq!(bufs[0], &[b"ab", &HELLO[..2]].concat()[..]);
+                assert_eq!(bufs[1], &HELLO[2..]);
+            }
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::Interrupted);
+                assert_eq!(bufs[0], b"ab");
+                let (res, buf) = rx.read_at(Vec::with_capacity(64), 0).await;
+                assert_eq!(res.unwrap(), HELLO.len());
+                assert_eq!(buf, HELLO);
+            }
+        }
+        drop(tx);
+    });
+}
+
+#[test]
+fn write_all_at_cancelable() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = File::create(tempfile.path()).await.unwrap();
+        let data: Vec<u8> = (0..3 << 20).map(|i| i as u8).collect();
+
+        // A write canceled while in flight stops once its chunk is written.
+        let (write, token) = file.write_all_at_cancelable(data, 0);
+        let watchdog = tokio_uring::spawn(async move {
+            tokio::task::yield_now().await;
+            token.cancel();
+        });
+
+        let (res, data) = write.await;
+        let n = res.unwrap();
+        watchdog.await.unwrap();
+        assert!(n > 0 && n < data.len());
+        let written = std::fs::read(tempfile.path()).unwrap();
+        assert_eq!(written, &data[..n]);
+
+        // The write can be resumed where it stopped.
+        let (write, _token) = file.write_all_at_cancelable(data[n..].to_vec(), n as u64);
+        let (res, rest) = write.await;
+        assert_eq!(res.unwrap(), rest.len());
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), data);
+
+        // A write canceled before it is polled writes nothing.
+        let (write, token) = file.write_all_at_cancelable(HELLO.to_vec(), 0);
+        token.cancel();
+        assert_eq!(write.await.0.unwrap(), 0);
+    });
+}
+
+#[test]
+fn copy_range() {
+    let data: Vec<u8> = (0..(9 << 20) + 5).map(|i| (i % 251) as u8).collect();
+    let mut src = tempfile();
+    src.write_all(&data).unwrap();
+
+    // Across filesystems, the kernel may not copy and reads and writes are
+    // used instead.
+    let mut dsts = vec![tempfile()];
+    if let Ok(shm) = NamedTempFile::new_in("/dev/shm") {
+        dsts.push(shm);
+    }
+
+    tokio_uring::start(async {
+        let src = File::open(src.path()).await.unwrap();
+
+        for dst in &dsts {
+            let file = File::create(dst.path()).await.unwrap();
+            let mut reported = vec![];
+            let copied = tokio_uring::fs::copy_range(&src, 3, &file, 0, u32::MAX as u64, |n| {
+                reported.push(n)
+            })
+            .await
+            .unwrap();
+
+            // The copy stops at the end of the source.
+            assert_eq!(copied, data.len() as u64 - 3);
+            assert_eq!(reported.last(), Some(&copied));
+            assert!(reported.len() > 1 && reported.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(std::fs::read(dst.path()).unwrap(), &data[3..]);
+
+            let copied = tokio_uring::fs::copy_range(&src, 0, &file, 1, 4, |_| {})
+                .await
+                .unwrap();
+            assert_eq!(copied, 4);
+            assert_eq!(
+                &std::fs::read(dst.path()).unwrap()[..6],
+                &[3, 0, 1, 2, 3, 8]
+            );
+        }
+    });
+}
+
+#[test]
+fn readv_fixed_at() {
+    use tokio_uring::buf::FixedBufRegistry;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        let registry = FixedBufRegistry::register((0..2).map(|_| Vec::with_capacity(5))).unwrap();
+        assert_eq!(registry.len(), 2);
+
+        // Only one registry can be registered at a time.
+        assert!(FixedBufRegistry::register(vec![Vec::with_capacity(5)]).is_err());
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let bufs = vec![
+            registry.check_out(0).unwrap(),
+            registry.check_out(1).unwrap(),
+        ];
+        assert!(registry.check_out(0).is_none());
+
+        let (res, bufs) = file.readv_fixed_at(bufs, 0).await;
+        assert_eq!(res.unwrap(), 10);
+        assert_eq!(&bufs[0][..], &HELLO[..5]);
+        assert_eq!(&bufs[1][..], &HELLO[5..10]);
+        assert_eq!(bufs[1].buf_index(), 1);
+
+        // Buffers return to the registry with their contents.
+        drop(bufs);
+        assert_eq!(&registry.check_out(1).unwrap()[..], &HELLO[5..10]);
+    });
+}
+
+#[test]
+fn fixed_buf_registry_dropped_in_flight() {
+    use tokio_uring::buf::FixedBufRegistry;
+
+    tokio_uring::start(async {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let mut tx = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+
+        // The read holds the last buffer of the registry once dropped.
+        let registry = FixedBufRegistry::register(vec![Vec::with_capacity(5)]).unwrap();
+        poll_once(rx.readv_fixed_at(vec![registry.check_out(0).unwrap()], 0)).await;
+        drop(registry);
+
+        // The registry is dropped as the read completes, while the driver
+        // reaps it, and unregistered right after.
+        tx.write_all(HELLO).unwrap();
+        let mut registered = Err(std::io::Error::from_raw_os_error(libc::EBUSY));
+        for _ in 0..100 {
+            tokio_uring::no_op().await.unwrap();
+            registered = FixedBufRegistry::register(vec![Vec::with_capacity(5)]);
+            if registered.is_ok() {
+                break;
+            }
+        }
+        registered.unwrap();
+    });
+}
+
+#[test]
+fn flags() {
+    use tokio_uring::fs::OpenOptions;
+
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = OpenOptions::new()
+            .append(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+
+        let flags = file.get_flags().await.unwrap();
+        assert!(flags.is_append());
+        assert!(flags.is_writable());
+        assert!(!flags.is_readable());
+        assert!(!flags.is_nonblocking());
+
+        file.set_nonblocking(true).await.unwrap();
+        assert!(file.get_flags().await.unwrap().is_nonblocking());
+        file.set_nonblocking(false).await.unwrap();
+        assert!(!file.get_flags().await.unwrap().is_nonblocking());
+    });
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn direct_alignment() {
+    use std::io::ErrorKind;
+    use std::os::unix::fs::OpenOptionsExt;
+    use tokio_uring::fs::OpenOptions;
+
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(tempfile.path())
+            .await
+        {
+            Ok(file) => file,
+            // The filesystem does not support O_DIRECT.
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+            Err(e) => panic!("{}", e),
+        };
+
+        let (res, _) = file.write_at(vec![0; 100], 0).await;
+        match res {
+            Err(e) if e.kind() == ErrorKind::InvalidInput => {
+                assert!(e.to_string().contains("O_DIRECT"), "{}", e);
+            }
+            // The alignment is unknown before Linux 6.1.
+            Err(e) => assert_eq!(e.raw_os_error(), Some(libc::EINVAL)),
+            Ok(_) => panic!("misaligned O_DIRECT write succeeded"),
+        }
+    });
+}
+
+#[test]
+fn read_exact_range() {
+    use std::io::ErrorKind;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let bytes = file.read_exact_range(6, 5).await.unwrap();
+        assert_eq!(bytes, &HELLO[6..11]);
+        assert_eq!(bytes.capacity(), 5);
+
+        assert!(file.read_exact_range(0, 0).await.unwrap().is_empty());
+
+        let err = file.read_exact_range(6, HELLO.len()).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    });
+}
+
+#[test]
+fn clone_and_close() {
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let fd = file.as_raw_fd();
+
+        // Clones share the file, and read concurrently from their own
+        // positions.
+        let clone = file.clone();
+        assert_eq!(clone.as_raw_fd(), fd);
+        let reader = tokio_uring::spawn(async move {
+            let (res, buf) = clone.read_at(Vec::with_capacity(5), 6).await;
+            assert_eq!(&buf[..res.unwrap()], &HELLO[6..11]);
+            clone
+        });
+        let (res, buf) = file.read_at(Vec::with_capacity(5), 0).await;
+        assert_eq!(&buf[..res.unwrap()], &HELLO[..5]);
+        let clone = reader.await.unwrap();
+
+        // Closing one clone waits for the other.
+        let closing = tokio_uring::spawn(file.close());
+        tokio::task::yield_now().await;
+        assert!(!closing.is_finished());
+        let (res, _) = clone.read_at(Vec::with_capacity(5), 0).await;
+        res.unwrap();
+
+        let other = tokio_uring::spawn(clone.close());
+        closing.await.unwrap().unwrap();
+        other.await.unwrap().unwrap();
+        assert_fd_closed(fd);
+    });
+}
+
+#[test]
+fn read_current_position() {
+    use std::os::unix::io::FromRawFd;
+
+    tokio_uring::start(async {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let mut tx = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+        tx.write_all(HELLO).unwrap();
+
+        let (res, buf) = rx.read(Vec::with_capacity(5)).await;
+        assert_eq!(&buf[..res.unwrap()], &HELLO[..5]);
+        let (res, buf) = rx.read(Vec::with_capacity(64)).await;
+        assert_eq!(&buf[..res.unwrap()], &HELLO[5..]);
+
+        // Regular files are read from their position, which advances.
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+        let file = File::open(tempfile.path()).await.unwrap();
+        let (res, buf) = file.read(Vec::with_capacity(6)).await;
+        assert_eq!(&buf[..res.unwrap()], &HELLO[..6]);
+        let (res, buf) = file.read(Vec::with_capacity(5)).await;
+        assert_eq!(&buf[..res.unwrap()], &HELLO[6..11]);
+    });
+}
+
+#[test]
+fn read_multi() {
+    use futures::StreamExt;
+    use tokio_uring::buf::ProvidedBufPool;
+
+    tokio_uring::start(async {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let mut tx = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+
+        let pool = ProvidedBufPool::new(2, 8).await.unwrap();
+        let mut reads = Box::pin(rx.read_multi(&pool));
+
+        tx.write_all(HELLO).unwrap();
+        let first = reads.next().await.unwrap().unwrap();
+        let second = reads.next().await.unwrap().unwrap();
+        assert_eq!([&first[..], &second[..]].concat(), HELLO);
+
+        // With every buffer held, the read waits for one to be returned.
+        tx.write_all(HELLO).unwrap();
+        poll_once(reads.next()).await;
+        drop((first, second));
+
+        let mut data = vec![];
+        while data.len() < HELLO.len() {
+            data.extend_from_slice(&reads.next().await.unwrap().unwrap());
+        }
+        assert_eq!(data, HELLO);
+
+        drop(tx);
+        assert!(reads.next().await.is_none());
+    });
+}
+
+#[test]
+fn read_multi_drop_returns_buffers() {
+    use futures::StreamExt;
+    use tokio_uring::buf::ProvidedBufPool;
+
+    tokio_uring::start(async {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let mut tx = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+
+        let pool = ProvidedBufPool::new(2, 8).await.unwrap();
+
+        // The second buffer is filled, but never taken from the stream.
+        let mut reads = Box::pin(rx.read_multi(&pool));
+        tx.write_all(HELLO).unwrap();
+        let first = reads.next().await.unwrap().unwrap();
+        drop((reads, first));
+
+        // Both buffers are back in the pool.
+        let mut reads = Box::pin(rx.read_multi(&pool));
+        tx.write_all(HELLO).unwrap();
+        let first = reads.next().await.unwrap().unwrap();
+        let second = reads.next().await.unwrap().unwrap();
+        assert_eq!([&first[..], &second[..]].concat(), HELLO);
+    });
+}
+
+#[test]
+fn read_ring() {
+    use tokio_uring::buf::BufRing;
+
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    tokio_uring::start(async {
+        let file = File::open(tempfile.path()).await.unwrap();
+        let ring = BufRing::new(2, 8).unwrap();
+
+        let first = file.read_ring(&ring, 0).await.unwrap().unwrap();
+        let second = file.read_ring(&ring, 8).await.unwrap().unwrap();
+        assert_ne!(first.buf_id(), second.buf_id());
+        assert_eq!([&first[..], &second[..]].concat(), HELLO);
+
+        // With every buffer held, the read waits for one to be returned.
+        let mut third = Box::pin(file.read_ring(&ring, 0));
+        poll_once(&mut third).await;
+        drop(first);
+        let third = third.await.unwrap().unwrap();
+        assert_eq!(&third[..], &HELLO[..8]);
+
+        drop(second);
+        let eof = file.read_ring(&ring, HELLO.len() as u64).await.unwrap();
+        assert!(eof.is_none());
+
+        let err = BufRing::new(3, 8).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    });
+}
+
+#[test]
+fn file_type() {
+    use tokio_uring::fs::FileType;
+
+    let tempfile = tempfile();
+
+    tokio_uring::start(async {
+        let file = File::open(tempfile.path()).await.unwrap();
+        assert_eq!(file.file_type().await.unwrap(), FileType::Regular);
+        assert!(!file.is_terminal().await.unwrap());
+
+        let dir = File::open(tempfile.path().parent().unwrap()).await.unwrap();
+        assert_eq!(dir.file_type().await.unwrap(), FileType::Directory);
+
+        let null = File::open("/dev/null").await.unwrap();
+        assert_eq!(null.file_type().await.unwrap(), FileType::CharDevice);
+        assert!(!null.is_terminal().await.unwrap());
+
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let _tx = unsafe { File::from_raw_fd(fds[1]) };
+        assert_eq!(rx.file_type().await.unwrap(), FileType::Fifo);
+        assert!(!rx.is_terminal().await.unwrap());
+
+        // The master side of a pseudo-terminal is a terminal.
+        let ptmx = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY) };
+        if ptmx >= 0 {
+            let ptmx = unsafe { File::from_raw_fd(ptmx) };
+            assert!(ptmx.is_terminal().await.unwrap());
+        }
+    });
+}
+
+#[test]
+fn open_with_metadata() {
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    tokio_uring::start(async {
+        let (file, statx) = File::open_with_metadata(tempfile.path()).await.unwrap();
+        assert_eq!(statx.stx_size, HELLO.len() as u64);
+        assert_eq!(
+            statx.stx_ino,
+            file.statx(libc::STATX_INO, 0).await.unwrap().stx_ino
+        );
+        read_hello(&file).await;
+
+        let err = File::open_with_metadata("/does/not/exist")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    });
+}
+
+#[test]
+fn statx() {
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    tokio_uring::start(async {
+        let statx =
+            tokio_uring::fs::statx(tempfile.path(), libc::STATX_SIZE, libc::AT_STATX_DONT_SYNC)
+                .await
+                .unwrap();
+        assert_ne!(statx.stx_mask & libc::STATX_SIZE, 0);
+        assert_eq!(statx.stx_size, HELLO.len() as u64);
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let statx = file
+            .statx(
+                libc::STATX_SIZE | libc::STATX_INO,
+                libc::AT_STATX_FORCE_SYNC,
+            )
+            .await
+            .unwrap();
+        assert_ne!(statx.stx_mask & libc::STATX_INO, 0);
+        assert_eq!(statx.stx_size, HELLO.len() as u64);
+
+        // A symbolic link is queried itself with `AT_SYMLINK_NOFOLLOW`.
+        let link = tempfile.path().with_extension("link");
+        std::os::unix::fs::symlink(tempfile.path(), &link).unwrap();
+        let statx = tokio_uring::fs::statx(&link, libc::STATX_TYPE, libc::AT_SYMLINK_NOFOLLOW)
+            .await
+            .unwrap();
+        std::fs::remove_file(&link).unwrap();
+        assert_eq!(statx.stx_mode as u32 & libc::S_IFMT, libc::S_IFLNK);
+
+        let err = tokio_uring::fs::statx("/does/not/exist", libc::STATX_SIZE, 0)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    });
+}
+
+#[test]
+fn path_only() {
+    use std::os::unix::io::AsRawFd;
+    use tokio_uring::fs::{FileType, OpenOptions};
+
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    tokio_uring::start(async {
+        let file = OpenOptions::new()
+            .path_only(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        assert_eq!(file.file_type().await.unwrap(), FileType::Regular);
+        assert_eq!(file.len().await.unwrap(), HELLO.len() as u64);
+
+        let flags = file.get_flags().await.unwrap();
+        assert!(flags.is_path_only());
+        assert!(!flags.is_readable() && !flags.is_writable());
+
+        let (res, _) = file.read_at(vec![0; 16], 0).await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+        let (res, _) = file.write_at(HELLO, 0).await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+
+        // A path-only directory is the base of relative operations.
+        let dir = OpenOptions::new()
+            .path_only(true)
+            .open(tempfile.path().parent().unwrap())
+            .await
+            .unwrap();
+        let name =
+            std::ffi::CString::new(tempfile.path().file_name().unwrap().to_str().unwrap()).unwrap();
+        let fd = unsafe { libc::openat(dir.as_raw_fd(), name.as_ptr(), libc::O_RDONLY) };
+        assert!(fd >= 0);
+        read_hello(&unsafe { File::from_raw_fd(fd) }).await;
+
+        let err = OpenOptions::new()
+            .path_only(true)
+            .write(true)
+            .open(tempfile.path())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    });
+}
+
+#[test]
+fn open_with_access_advice() {
+    use std::ffi::CString;
+    use tokio_uring::fs::OpenOptions;
+
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    tokio_uring::start(async {
+        let file = OpenOptions::new()
+            .read(true)
+            .sequential(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        read_hello(&file).await;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .sequential(true)
+            .random(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        read_hello(&file).await;
+
+        // A FIFO does not take the advice, which does not fail the open.
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("fifo");
+        let path = CString::new(fifo.to_str().unwrap()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(path.as_ptr(), 0o600) }, 0);
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .sequential(true)
+            .open(&fifo)
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+fn install_registered() {
+    use tokio_uring::fs::OpenOptions;
+
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+
+    tokio_uring::start(async {
+        let registered = OpenOptions::new()
+            .read(true)
+            .open_registered(tempfile.path())
+            .await
+            .unwrap();
+        let file = registered.install().await.unwrap();
+
+        // The installed file outlives the direct descriptor.
+        registered.close().await.unwrap();
+        read_hello(&file).await;
+    });
+}
+
+#[test]
+fn register() {
+    let tempfile = tempfile();
+
+    tokio_uring::start(async {
+        let file = File::create(tempfile.path()).await.unwrap();
+        let registered = file.register().unwrap();
+
+        // The registered file outlives the original one.
+        file.close().await.unwrap();
+
+        let (res, _) = registered.write_at(HELLO, 0).await;
+        assert_eq!(res.unwrap(), HELLO.len());
+        registered.sync_all().await.unwrap();
+
+        let (res, buf) = registered.read_at(vec![0; 64], 0).await;
+        assert!(res.is_err(), "the file is write-only");
+        drop(buf);
+
+        registered.close().await.unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let registered = file.register().unwrap();
+        let (res, buf) = registered.read_at(vec![0; 64], 0).await;
+        assert_eq!(&buf[..res.unwrap()], HELLO);
+    });
+}
+
+#[test]
+fn fallocate() {
+    use std::io::ErrorKind;
+    use tokio_uring::fs::{FallocateMode, OpenOptions};
+
+    let tempfile = tempfile();
+    std::fs::write(tempfile.path(), vec![1; 16 * 1024]).unwrap();
+
+    tokio_uring::start(async {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+
+        file.zero_range(4096, 4096).await.unwrap();
+        let buf = file.read_exact_range(0, 16 * 1024).await.unwrap();
+        assert!(buf[..4096].iter().all(|&b| b == 1));
+        assert!(buf[4096..8192].iter().all(|&b| b == 0));
+        assert!(buf[8192..].iter().all(|&b| b == 1));
+
+        // Extends the file.
+        file.zero_range(16 * 1024, 100).await.unwrap();
+        assert_eq!(
+            std::fs::metadata(tempfile.path()).unwrap().len(),
+            16 * 1024 + 100
+        );
+
+        let err = file.zero_range(0, 0).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        let err = file
+            .fallocate(1, 4096, FallocateMode::CollapseRange)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    });
+}
+
+#[test]
+fn write_hint() {
+    use tokio_uring::fs::WriteLifeHint;
+
+    let tempfile = tempfile();
+
+    tokio_uring::start(async {
+        let file = File::create(tempfile.path()).await.unwrap();
+        assert_eq!(file.get_write_hint().await.unwrap(), WriteLifeHint::NotSet);
+
+        file.set_write_hint(WriteLifeHint::Short).await.unwrap();
+        assert_eq!(file.get_write_hint().await.unwrap(), WriteLifeHint::Short);
+
+        // The hint belongs to the file, not the descriptor.
+        let other = File::open(tempfile.path()).await.unwrap();
+        assert_eq!(other.get_write_hint().await.unwrap(), WriteLifeHint::Short);
+    });
+}
+
+#[test]
+fn read_small() {
+    use tokio_uring::fs::read_small;
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        // More reads than there are fixed file slots, to check they are
+        // returned once the file is closed.
+        for _ in 0..100 {
+            let data = read_small(tempfile.path(), 1024).await.unwrap();
+            assert_eq!(data, HELLO);
+        }
+
+        let data = read_small(tempfile.path(), 5).await.unwrap();
+        assert_eq!(data, &HELLO[..5]);
+
+        let err = read_small(tempfile.path().with_extension("missing"), 1024)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    });
+}
+
+#[test]
+fn read_to_string() {
+    use tokio_uring::fs::read_to_string;
+
+    tokio_uring::start(async {
+        // Pseudo files report a size of zero.
+        let data = read_to_string("/proc/self/cmdline").await.unwrap();
+        assert_eq!(data, std::fs::read_to_string("/proc/self/cmdline").unwrap());
+        assert!(!data.is_empty());
+
+        // The buffer grows past its initial capacity.
+        let mut tempfile = tempfile();
+        let contents = "hello world\n".repeat(1000);
+        tempfile.write_all(contents.as_bytes()).unwrap();
+        assert_eq!(read_to_string(tempfile.path()).await.unwrap(), contents);
+
+        tempfile.write_all(&[0xff, 0xfe]).unwrap();
+        let err = read_to_string(tempfile.path()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    });
+}
+
+#[test]
+fn read_all_shared() {
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        let file = File::open(tempfile.path()).await.unwrap();
+        assert!(file.read_all_shared().await.unwrap().is_empty());
+
+        // Data appended through another handle is read too.
+        tempfile.write_all(HELLO).unwrap();
+        let data = file.read_all_shared().await.unwrap();
+        assert_eq!(&data[..], HELLO);
+
+        // Pseudo files report a size of zero, the buffer grows past it.
+        let file = File::open("/proc/self/status").await.unwrap();
+        let data = file.read_all_shared().await.unwrap();
+        assert!(data.starts_with(b"Name:"));
+    });
+}
+
+#[test]
+fn read_to_end_hashed() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    fn hash(data: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(data);
+        hasher.finish()
+    }
+
+    tokio_uring::start(async {
+        let mut tempfile = tempfile();
+        tempfile.write_all(HELLO).unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let (data, h) = file.read_to_end_hashed(DefaultHasher::new()).await.unwrap();
+        assert_eq!(data, HELLO);
+        assert_eq!(h, hash(HELLO));
+
+        // Read in several chunks, as the file reports no size.
+        let file = File::open("/proc/self/status").await.unwrap();
+        let (data, h) = file.read_to_end_hashed(DefaultHasher::new()).await.unwrap();
+        assert!(data.starts_with(b"Name:"));
+        assert_eq!(h, hash(&data));
+    });
+}
+
 #[test]
 fn vectored_read() {
     tokio_uring::start(async {
@@ -96,6 +1084,189 @@ fn vectored_write() {
     });
 }
 
+#[test]
+fn read_coalesced() {
+    tokio_uring::start(async {
+        let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+        let mut tempfile = tempfile();
+        tempfile.write_all(&data).unwrap();
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        // Unordered, overlapping, near-adjacent, distant and past the end.
+        let ranges = [
+            (5_000, 10),
+            (100, 50),
+            (120, 50),
+            (180, 10),
+            (9_995, 10),
+            (20_000, 10),
+        ];
+        for max_gap in [0, 16, 100_000] {
+            let bufs = file.read_coalesced(&ranges, max_gap).await.unwrap();
+
+            assert_eq!(bufs.len(), ranges.len());
+            for (buf, &(pos, len)) in bufs.iter().zip(&ranges) {
+                let start = (pos as usize).min(data.len());
+                let end = (start + len).min(data.len());
+                assert_eq!(buf, &data[start..end]);
+            }
+        }
+    });
+}
+
+#[test]
+fn splice_vectored() {
+    tokio_uring::start(async {
+        let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+        let mut src = tempfile();
+        src.write_all(&data).unwrap();
+        let dst = tempfile();
+
+        let src_file = File::open(src.path()).await.unwrap();
+        let dst_file = File::create(dst.path()).await.unwrap();
+
+        // The second range is short, and the third one is ignored.
+        let ranges = vec![(100, 50), (9_990, 50), (0, 50)];
+        let n = src_file
+            .splice_vectored(ranges, &dst_file, 5)
+            .await
+            .unwrap();
+        assert_eq!(n, 60);
+
+        let copied = std::fs::read(dst.path()).unwrap();
+        assert_eq!(&copied[..5], &[0; 5]);
+        assert_eq!(&copied[5..55], &data[100..150]);
+        assert_eq!(&copied[55..], &data[9_990..]);
+    });
+}
+
+#[test]
+fn write_at_all_vectored() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = File::create(tempfile.path()).await.unwrap();
+
+        let bufs = vec![b"hello".to_vec(), vec![], b" world".to_vec()];
+        let bufs = file.write_at_all_vectored(bufs, 2).await.unwrap();
+        assert_eq!(bufs.len(), 3);
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"\0\0hello world");
+
+        // The file was opened read-only.
+        let file = File::open(tempfile.path()).await.unwrap();
+        let err = file
+            .write_at_all_vectored(vec![b"hello".to_vec()], 0)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error().raw_os_error(), Some(libc::EBADF));
+        let (buffers_written, bytes_into_next, bufs) = err.into_parts();
+        assert_eq!((buffers_written, bytes_into_next), (0, 0));
+        assert_eq!(bufs, [b"hello"]);
+    });
+}
+
+#[test]
+fn writev_and_sync() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = File::create(tempfile.path()).await.unwrap();
+
+        let bufs = vec![b"first\n".to_vec(), b"second\n".to_vec()];
+        let (res, bufs) = file.writev_and_sync(bufs, 0).await;
+        res.unwrap();
+        assert_eq!(bufs.len(), 2);
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"first\nsecond\n");
+
+        // The file was opened read-only, the sync is canceled.
+        let file = File::open(tempfile.path()).await.unwrap();
+        let (res, bufs) = file.writev_and_sync(vec![b"third\n".to_vec()], 13).await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+        assert_eq!(bufs, [b"third\n"]);
+    });
+}
+
+#[test]
+fn write_at_sync() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = File::create(tempfile.path()).await.unwrap();
+
+        let (res, buf) = file.write_at_sync(HELLO, 0).await;
+        assert_eq!(res.unwrap(), HELLO.len());
+        assert_eq!(buf, HELLO);
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), HELLO);
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let (res, _) = file.write_at_sync(HELLO, 0).await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+    });
+}
+
+#[test]
+fn sync_ranges() {
+    use std::os::unix::io::FromRawFd;
+
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+        let file = File::create(tempfile.path()).await.unwrap();
+        file.write_all_at(vec![1; 64 << 10], 0).await.0.unwrap();
+
+        // Overlapping, adjacent, empty and past the end of the file.
+        file.sync_ranges(&[
+            (4096, 8192),
+            (0, 4096),
+            (8192, 100),
+            (0, 0),
+            (1 << 20, 4096),
+        ])
+        .await
+        .unwrap();
+        // Longer than a single operation syncs.
+        file.sync_ranges(&[(0, 5 << 30)]).await.unwrap();
+        file.sync_ranges(&[]).await.unwrap();
+        // Up to the end of the file, with a single operation.
+        file.sync_ranges(&[(4096, u64::MAX), (i64::MAX as u64, 1)])
+            .await
+            .unwrap();
+        let err = file.sync_ranges(&[(1 << 63, 1)]).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let _tx = unsafe { File::from_raw_fd(fds[1]) };
+        let err = rx.sync_ranges(&[(0, 1), (10, 1)]).await.unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ESPIPE));
+    });
+}
+
+#[test]
+fn vectored_write_empty_bufs() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+
+        let file = File::create(tempfile.path()).await.unwrap();
+        let bufs = vec![
+            vec![],
+            b"hello".to_vec(),
+            vec![],
+            vec![],
+            b" world...".to_vec(),
+            vec![],
+        ];
+
+        let (res, bufs) = file.writev_at(bufs, 0).await;
+        assert_eq!(res.unwrap(), HELLO.len());
+        // All buffers are returned, including the empty ones.
+        assert_eq!(bufs.len(), 6);
+        assert_eq!(bufs[1], b"hello");
+
+        let file = std::fs::read(tempfile.path()).unwrap();
+        assert_eq!(file, HELLO);
+    });
+}
+
 #[test]
 fn basic_write_all() {
     tokio_uring::start(async {
@@ -112,6 +1283,147 @@ fn basic_write_all() {
     });
 }
 
+#[test]
+fn lines() {
+    use futures::StreamExt;
+    use std::io::ErrorKind;
+
+    tokio_uring::start(async {
+        let mut text = tempfile();
+        text.write_all(b"first\r\nsecond line\n\nlast").unwrap();
+        let file = File::open(text.path()).await.unwrap();
+
+        // Lines spanning chunks are reassembled.
+        for chunk_size in [1, 4, 4096] {
+            let lines: Vec<_> = file
+                .lines_with_limits(chunk_size, None)
+                .map(Result::unwrap)
+                .collect()
+                .await;
+            assert_eq!(lines, ["first", "second line", "", "last"]);
+        }
+
+        let lines: Vec<_> = file.lines_with_limits(4, Some(6)).collect().await;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].as_ref().unwrap(), "first");
+        assert_eq!(
+            lines[1].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // A line without newline is rejected before it is read whole.
+        let mut long = tempfile();
+        long.write_all(&[b'x'; 100_000]).unwrap();
+        let file = File::open(long.path()).await.unwrap();
+        let mut lines = Box::pin(file.lines_with_limits(16, Some(64)));
+        let err = lines.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(lines.next().await.is_none());
+
+        let file = File::open(long.path()).await.unwrap();
+        long.write_all(b"\n\xff\n").unwrap();
+        let lines: Vec<_> = file.lines().collect().await;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].as_ref().unwrap().len(), 100_000);
+        assert_eq!(
+            lines[1].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    });
+}
+
+#[test]
+fn split() {
+    use futures::StreamExt;
+    use std::io::ErrorKind;
+
+    tokio_uring::start(async {
+        let mut paths = tempfile();
+        paths.write_all(b"./a\0./b\xff\r\0\0./c/long\0").unwrap();
+        let file = File::open(paths.path()).await.unwrap();
+
+        // Records spanning chunks are reassembled, and kept as raw bytes.
+        for chunk_size in [1, 4, 4096] {
+            let records: Vec<_> = file
+                .split_with_limits(b'\0', chunk_size, None)
+                .map(Result::unwrap)
+                .collect()
+                .await;
+            assert_eq!(records, [&b"./a"[..], b"./b\xff\r", b"", b"./c/long"]);
+        }
+
+        let records: Vec<_> = file.split_with_limits(b'\0', 4, Some(5)).collect().await;
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[2].as_ref().unwrap(), b"");
+        assert_eq!(
+            records[3].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // A final record without delimiter is yielded too.
+        let mut unterminated = tempfile();
+        unterminated.write_all(b"a,b,").unwrap();
+        let file = File::open(unterminated.path()).await.unwrap();
+        unterminated.write_all(b"c").unwrap();
+        let records: Vec<_> = file.split(b',').map(Result::unwrap).collect().await;
+        assert_eq!(records, [b"a", b"b", b"c"]);
+    });
+}
+
+#[test]
+fn read_chunks() {
+    use futures::StreamExt;
+
+    tokio_uring::start(async {
+        let data = HELLO.repeat(100);
+
+        let mut tempfile = tempfile();
+        tempfile.write_all(&data).unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        for read_ahead in [0, 3] {
+            let chunks: Vec<_> = file
+                .chunks_with_read_ahead(512, read_ahead)
+                .map(Result::unwrap)
+                .collect()
+                .await;
+
+            // The final, partial chunk must not be dropped.
+            assert_eq!(chunks.len(), 3);
+            assert_eq!(chunks[2].len(), data.len() - 1024);
+            assert_eq!(chunks.concat(), data);
+        }
+    });
+}
+
+#[test]
+fn scan() {
+    use futures::StreamExt;
+
+    tokio_uring::start(async {
+        let data = HELLO.repeat(100);
+
+        let mut tempfile = tempfile();
+        tempfile.write_all(&data).unwrap();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let chunks: Vec<_> = file.scan(512).map(Result::unwrap).collect().await;
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), data);
+
+        // A file ending on a chunk boundary yields no empty chunk.
+        let chunks: Vec<_> = file
+            .scan(data.len() / 2)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.concat(), data);
+    });
+}
+
 #[test]
 fn cancel_read() {
     tokio_uring::start(async {
@@ -185,6 +1497,113 @@ fn sync_doesnt_kill_anything() {
     });
 }
 
+#[test]
+fn statvfs() {
+    tokio_uring::start(async {
+        let tempfile = tempfile();
+
+        let file = File::open(tempfile.path()).await.unwrap();
+        let by_fd = file.statvfs().await.unwrap();
+        let by_path = tokio_uring::fs::statvfs(tempfile.path()).await.unwrap();
+
+        assert!(by_fd.block_size() > 0);
+        assert!(by_fd.blocks_available() <= by_fd.blocks_free());
+        assert_eq!(by_fd.block_size(), by_path.block_size());
+        assert_eq!(by_fd.fs_type(), by_path.fs_type());
+    });
+}
+
+#[test]
+fn close_sync() {
+    let tempfile = tempfile();
+
+    tokio_uring::start(async {
+        let file = File::create(tempfile.path()).await.unwrap();
+        file.write_at(HELLO, 0).await.0.unwrap();
+        let fd = file.as_raw_fd();
+
+        file.close_sync().await.unwrap();
+
+        assert_eq!(std::fs::read(tempfile.path()).unwrap(), HELLO);
+        assert_fd_closed(fd);
+    });
+}
+
+#[test]
+fn sync_all_of() {
+    tokio_uring::start(async {
+        let tempfiles = [tempfile(), tempfile(), tempfile()];
+
+        let mut files = vec![];
+        for tempfile in &tempfiles {
+            let file = File::create(tempfile.path()).await.unwrap();
+            file.write_at(HELLO, 0).await.0.unwrap();
+            files.push(file);
+        }
+
+        let refs: Vec<&File> = files.iter().collect();
+        tokio_uring::fs::sync_all_of(&refs).await.unwrap();
+        tokio_uring::fs::sync_all_of(&[]).await.unwrap();
+    });
+}
+
+#[test]
+fn commit() {
+    // More files than fit the submission queue at once.
+    tokio_uring::builder().entries(4).start(async {
+        let tempfiles: Vec<_> = (0..6).map(|_| tempfile()).collect();
+
+        let mut files = vec![];
+        for tempfile in &tempfiles {
+            let file = File::create(tempfile.path()).await.unwrap();
+            file.write_at(HELLO, 0).await.0.unwrap();
+            files.push(file);
+        }
+
+        let refs: Vec<&File> = files.iter().collect();
+        tokio_uring::commit(&refs).await.unwrap();
+        tokio_uring::commit(&[]).await.unwrap();
+
+        // Pipes can't be synced.
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { File::from_raw_fd(fds[0]) };
+        let _tx = unsafe { File::from_raw_fd(fds[1]) };
+
+        let err = tokio_uring::commit(&[&files[0], &rx, &files[1]])
+            .await
+            .unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+    });
+}
+
+#[test]
+fn remove_files() {
+    // More paths than fit the submission queue at once.
+    tokio_uring::builder().entries(8).start(async {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<_> = (0..100).map(|i| dir.path().join(i.to_string())).collect();
+        for (i, path) in paths.iter().enumerate() {
+            // Every tenth file is missing.
+            if i % 10 != 3 {
+                std::fs::write(path, b"hello").unwrap();
+            }
+        }
+
+        let results = tokio_uring::fs::remove_files(paths.clone()).await;
+        assert_eq!(results.len(), paths.len());
+        for (i, (path, res)) in results.into_iter().enumerate() {
+            assert_eq!(path, paths[i]);
+            if i % 10 == 3 {
+                assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+            } else {
+                res.unwrap();
+            }
+            assert!(!path.exists());
+        }
+    });
+}
+
 #[test]
 fn rename() {
     use std::ffi::OsStr;
@@ -219,6 +1638,134 @@ fn rename() {
     })
 }
 
+#[test]
+fn rename_with_flags() {
+    use std::io::ErrorKind;
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+    use tokio_uring::fs::{rename_with_flags, RenameFlags};
+
+    tokio_uring::start(async {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::write(&a, b"a").unwrap();
+        std::fs::write(&b, b"b").unwrap();
+
+        let err = rename_with_flags(&a, &b, RenameFlags::NOREPLACE)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+        rename_with_flags(&a, &b, RenameFlags::EXCHANGE)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&a).unwrap(), b"b");
+        assert_eq!(std::fs::read(&b).unwrap(), b"a");
+
+        let err = rename_with_flags(&a, &b, RenameFlags::EXCHANGE | RenameFlags::NOREPLACE)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        // Whiteouts depend on privileges and on the filesystem.
+        let c = dir.path().join("c");
+        match rename_with_flags(&a, &c, RenameFlags::WHITEOUT | RenameFlags::NOREPLACE).await {
+            Ok(()) => {
+                let whiteout = std::fs::symlink_metadata(&a).unwrap();
+                assert!(whiteout.file_type().is_char_device());
+                assert_eq!(whiteout.rdev(), 0);
+                assert_eq!(std::fs::read(&c).unwrap(), b"b");
+            }
+            Err(e) => assert!(matches!(
+                e.kind(),
+                ErrorKind::PermissionDenied | ErrorKind::Unsupported
+            )),
+        }
+    });
+}
+
+#[test]
+fn write_atomic() {
+    tokio_uring::start(async {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+
+        tokio_uring::fs::write_atomic(&path, HELLO).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), HELLO);
+
+        tokio_uring::fs::write_atomic(&path, b"replaced".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"replaced");
+
+        // A failed replacement leaves no temporary file behind.
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        let err = tokio_uring::fs::write_atomic(&sub, HELLO).await;
+        assert!(err.is_err());
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 2);
+    });
+}
+
+#[test]
+fn replace_if_unchanged() {
+    use std::time::{Duration, SystemTime};
+    use tokio_uring::fs::{replace_if_unchanged, replace_if_unchanged_locked, Conflict};
+
+    fn conflict(e: &std::io::Error) -> &Conflict {
+        e.get_ref().unwrap().downcast_ref().unwrap()
+    }
+
+    tokio_uring::start(async {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+
+        // Timestamps are coarse, so the file is given a distinct one.
+        let mtime = SystemTime::UNIX_EPOCH + Duration::new(1_000_000, 123);
+        let touch = |contents: &[u8]| {
+            std::fs::write(&path, contents).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(mtime).unwrap();
+        };
+
+        touch(HELLO);
+        replace_if_unchanged(&path, b"second".to_vec(), mtime)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        // The file was modified by the replacement itself.
+        let err = replace_if_unchanged(&path, b"third".to_vec(), mtime)
+            .await
+            .unwrap_err();
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(conflict(&err).modified(), Some(modified));
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        std::fs::remove_file(&path).unwrap();
+        let err = replace_if_unchanged(&path, b"third".to_vec(), mtime)
+            .await
+            .unwrap_err();
+        assert_eq!(conflict(&err).modified(), None);
+        assert!(!path.exists());
+
+        touch(HELLO);
+        replace_if_unchanged_locked(&path, b"locked".to_vec(), mtime)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"locked");
+        let err = replace_if_unchanged_locked(&path, b"third".to_vec(), mtime)
+            .await
+            .unwrap_err();
+        assert!(conflict(&err).modified().is_some());
+
+        // No temporary file is left behind.
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    });
+}
+
 fn tempfile() -> NamedTempFile {
     NamedTempFile::new().unwrap()
 }
@@ -238,6 +1785,15 @@ async fn poll_once(future: impl std::future::Future) {
     .await;
 }
 
+fn assert_fd_closed(fd: RawFd) {
+    let res = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    assert_eq!(res, -1);
+    assert_eq!(
+        std::io::Error::last_os_error().raw_os_error(),
+        Some(libc::EBADF)
+    );
+}
+
 fn assert_invalid_fd(fd: RawFd) {
     use std::fs::File;
 
@@ -0,0 +1,68 @@
+use std::io::ErrorKind;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use tokio_uring::futex::{self, MATCH_ANY};
+
+fn is_supported() -> bool {
+    // IORING_OP_FUTEX_WAIT
+    tokio_uring::probe().unwrap().is_supported(51)
+}
+
+#[test]
+fn wait_and_wake() {
+    tokio_uring::start(async {
+        if !is_supported() {
+            let futex = AtomicU32::new(0);
+            let err = futex::wake(&futex, 1, MATCH_ANY).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::Unsupported);
+            return;
+        }
+
+        let word = Rc::new(AtomicU32::new(0));
+
+        // The value changed already.
+        let err = futex::wait(&word, 1, MATCH_ANY).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WouldBlock);
+
+        // Nobody waits yet.
+        assert_eq!(futex::wake(&word, 1, MATCH_ANY).await.unwrap(), 0);
+
+        let waiter = {
+            let word = word.clone();
+            tokio_uring::spawn(async move { futex::wait(&word, 0, 0b01).await })
+        };
+        tokio::task::yield_now().await;
+        tokio_uring::flush().unwrap();
+
+        // A wakeup with a disjoint mask leaves the waiter alone.
+        assert_eq!(futex::wake(&word, 1, 0b10).await.unwrap(), 0);
+
+        word.store(1, Ordering::Release);
+        assert_eq!(futex::wake(&word, 1, MATCH_ANY).await.unwrap(), 1);
+        waiter.await.unwrap().unwrap();
+
+        let err = futex::wait(&word, 1, 0).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    });
+}
+
+#[test]
+fn drop_cancels_wait() {
+    tokio_uring::start(async {
+        if !is_supported() {
+            return;
+        }
+
+        let word = AtomicU32::new(0);
+
+        let mut wait = Box::pin(futex::wait(&word, 0, MATCH_ANY));
+        assert!(futures::poll!(&mut wait).is_pending());
+        tokio_uring::flush().unwrap();
+        drop(wait);
+
+        // Let the cancellation go through.
+        tokio_uring::no_op().await.unwrap();
+        assert_eq!(futex::wake(&word, 1, MATCH_ANY).await.unwrap(), 0);
+    });
+}
@@ -34,3 +34,541 @@ fn spawn_a_task() {
         assert_eq!(2, *cell.borrow());
     });
 }
+
+#[test]
+fn abort_a_task() {
+    tokio_uring::start(async {
+        let notifier = tokio_uring::Notifier::new().unwrap();
+        let n = notifier.clone();
+
+        // The task waits on an operation which never completes.
+        let handle = tokio_uring::spawn(async move { n.notified().await });
+
+        tokio::task::yield_now().await;
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+    });
+}
+
+#[test]
+fn probe_opcodes() {
+    use io_uring::opcode;
+
+    tokio_uring::start(async {
+        let probe = tokio_uring::probe().unwrap();
+        assert!(probe.is_supported(opcode::Nop::CODE));
+        assert!(probe.is_supported(opcode::Read::CODE));
+
+        // The result is cached for the lifetime of the runtime.
+        let again = tokio_uring::probe().unwrap();
+        assert_eq!(format!("{:?}", probe), format!("{:?}", again));
+    });
+}
+
+#[test]
+fn notify_from_another_thread() {
+    tokio_uring::start(async {
+        let notifier = tokio_uring::Notifier::new().unwrap();
+
+        let remote = notifier.clone();
+        let thread = std::thread::spawn(move || remote.notify().unwrap());
+
+        notifier.notified().await.unwrap();
+        thread.join().unwrap();
+    });
+}
+
+#[test]
+fn notified_dropped() {
+    use std::future::Future;
+    use std::task::Poll;
+    use std::time::Duration;
+
+    tokio_uring::start(async {
+        let notifier = tokio_uring::Notifier::new().unwrap();
+
+        // The read consumes the notification, but its result is dropped.
+        let mut notified = Box::pin(notifier.notified());
+        futures::future::poll_fn(|cx| {
+            assert!(notified.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        notifier.notify().unwrap();
+        tokio_uring::no_op().await.unwrap();
+        drop(notified);
+
+        // The notification is not lost.
+        tokio::time::timeout(Duration::from_secs(5), notifier.notified())
+            .await
+            .expect("notification lost")
+            .unwrap();
+    });
+}
+
+#[test]
+fn register_eventfd() {
+    use std::os::unix::io::AsRawFd;
+
+    tokio_uring::start(async {
+        let eventfd = tokio_uring::Notifier::new().unwrap();
+        tokio_uring::register_eventfd(eventfd.as_raw_fd()).unwrap();
+
+        // The completion signals the eventfd.
+        tokio_uring::no_op().await.unwrap();
+        eventfd.notified().await.unwrap();
+
+        tokio_uring::unregister_eventfd().unwrap();
+    });
+}
+
+#[test]
+fn invalid_sqpoll_cpu() {
+    let mut builder = tokio_uring::builder();
+    builder
+        .uring_builder(tokio_uring::uring_builder().setup_sqpoll(1000))
+        .sqpoll_cpu(u32::MAX);
+
+    let err = tokio_uring::Runtime::new(&builder).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn taskrun_modes() {
+    use std::io::ErrorKind;
+    use tokio_uring::net::{TcpListener as UringListener, TcpStream as UringStream};
+
+    for (coop, defer) in [(true, false), (false, true), (true, true)] {
+        let mut builder = tokio_uring::builder();
+        builder.coop_taskrun(coop).defer_taskrun(defer);
+
+        let rt = match tokio_uring::Runtime::new(&builder) {
+            Ok(rt) => rt,
+            Err(e) if e.kind() == ErrorKind::Unsupported => continue,
+            Err(e) => panic!("{}", e),
+        };
+
+        rt.block_on(async {
+            tokio_uring::no_op().await.unwrap();
+
+            // A completion which only becomes available after the driver
+            // started waiting.
+            let listener = UringListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+            let addr = listener.local_addr().unwrap();
+            let (client, server) =
+                futures::future::join(UringStream::connect(addr), listener.accept()).await;
+            let (client, (server, _)) = (client.unwrap(), server.unwrap());
+
+            let writer = tokio_uring::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                server.write(b"hello".to_vec()).await.0.unwrap();
+            });
+
+            let (res, buf) = client.read(vec![0; 16]).await;
+            assert_eq!(&buf[..res.unwrap()], b"hello");
+            writer.await.unwrap();
+        });
+    }
+}
+
+#[test]
+fn flush() {
+    tokio_uring::start(async {
+        assert_eq!(tokio_uring::flush().unwrap(), 0);
+
+        let op = tokio_uring::no_op();
+        futures::pin_mut!(op);
+        assert!(futures::poll!(&mut op).is_pending());
+
+        assert_eq!(tokio_uring::flush().unwrap(), 1);
+        assert_eq!(tokio_uring::flush().unwrap(), 0);
+        op.await.unwrap();
+    });
+}
+
+#[test]
+fn sq_stats() {
+    tokio_uring::builder().entries(8).start(async {
+        let stats = tokio_uring::sq_stats();
+        assert_eq!(stats.capacity(), 8);
+        assert!(stats.is_empty());
+
+        let op = tokio_uring::no_op();
+        futures::pin_mut!(op);
+        assert!(futures::poll!(&mut op).is_pending());
+        assert_eq!(tokio_uring::sq_stats().len(), 1);
+
+        tokio_uring::flush().unwrap();
+        assert!(tokio_uring::sq_stats().is_empty());
+        op.await.unwrap();
+    });
+}
+
+#[test]
+fn reserve() {
+    use io_uring::{opcode, squeue};
+
+    tokio_uring::builder().entries(8).start(async {
+        assert!(tokio_uring::reserve(9).is_none());
+
+        // Fill the queue but one entry.
+        let mut queued = vec![];
+        for _ in 0..7 {
+            let mut op = Box::pin(tokio_uring::no_op());
+            assert!(futures::poll!(&mut op).is_pending());
+            queued.push(op);
+        }
+        assert_eq!(tokio_uring::sq_stats().len(), 7);
+
+        // The queued operations are submitted to make room for the chain.
+        let reservation = tokio_uring::reserve(3).unwrap();
+        assert_eq!(reservation.len(), 3);
+        assert!(tokio_uring::sq_stats().len() <= 5);
+
+        let before = tokio_uring::sq_stats().len();
+        let mut chain: Vec<_> = (0..3)
+            .map(|i| {
+                Box::pin(unsafe {
+                    tokio_uring::submit_raw((), move |_| {
+                        let nop = opcode::Nop::new().build();
+                        if i < 2 {
+                            nop.flags(squeue::Flags::IO_LINK)
+                        } else {
+                            nop
+                        }
+                    })
+                })
+            })
+            .collect();
+        for op in &mut chain {
+            assert!(futures::poll!(op).is_pending());
+        }
+        assert_eq!(tokio_uring::sq_stats().len(), before + 3);
+
+        for op in chain {
+            op.await.0.unwrap();
+        }
+        for op in queued {
+            op.await.unwrap();
+        }
+    });
+}
+
+#[test]
+fn quiesce() {
+    use std::os::unix::io::FromRawFd;
+
+    tokio_uring::start(async {
+        // Nothing in flight.
+        tokio_uring::quiesce().await;
+
+        let op = tokio_uring::no_op();
+        futures::pin_mut!(op);
+        assert!(futures::poll!(&mut op).is_pending());
+
+        let quiesce = tokio_uring::quiesce();
+        futures::pin_mut!(quiesce);
+        assert!(futures::poll!(&mut quiesce).is_pending());
+
+        // An operation submitted after the call, here a read from a pipe
+        // which never completes, is not waited for.
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let rx = unsafe { tokio_uring::fs::File::from_raw_fd(fds[0]) };
+        let _tx = unsafe { tokio_uring::fs::File::from_raw_fd(fds[1]) };
+        let (read, token) = rx.read_at_cancelable(Vec::with_capacity(1), 0);
+        futures::pin_mut!(read);
+        assert!(futures::poll!(&mut read).is_pending());
+
+        quiesce.await;
+        assert!(futures::poll!(&mut op).is_ready());
+
+        token.cancel();
+        read.await.0.unwrap_err();
+    });
+}
+
+#[test]
+fn fallback_without_io_uring() {
+    use std::io::ErrorKind;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("file");
+    let ring = tokio_uring::start(async { tokio_uring::ring_handle().unwrap() });
+
+    // Block io_uring on a dedicated thread, and the threads it spawns, with
+    // a seccomp filter, as container runtimes commonly do.
+    std::thread::spawn(move || {
+        deny_io_uring_setup();
+
+        tokio_uring::start_or_fallback(async {
+            let file = tokio_uring::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .open(&path)
+                .await
+                .unwrap();
+            file.write_all_at(b"hello world".to_vec(), 0)
+                .await
+                .0
+                .unwrap();
+            file.sync_all().await.unwrap();
+
+            let (res, buf) = file.read_at(Vec::with_capacity(16), 6).await;
+            assert_eq!(&buf[..res.unwrap()], b"world");
+            file.close().await.unwrap();
+
+            // Operations without a fallback fail cleanly.
+            let err = tokio_uring::net::TcpStream::connect("127.0.0.1:1".parse().unwrap())
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+            // So do the functions reaching for the ring.
+            fn kind<T>(res: std::io::Result<T>) -> ErrorKind {
+                res.map(drop).unwrap_err().kind()
+            }
+            assert_eq!(kind(tokio_uring::probe()), ErrorKind::Unsupported);
+            assert_eq!(kind(tokio_uring::flush()), ErrorKind::Unsupported);
+            assert_eq!(
+                kind(tokio_uring::register_eventfd(0)),
+                ErrorKind::Unsupported
+            );
+            assert_eq!(tokio_uring::sq_stats().capacity(), 0);
+            assert_eq!(
+                kind(tokio_uring::epoll_ctl(0, libc::EPOLL_CTL_DEL, 1, None).await),
+                ErrorKind::Unsupported
+            );
+            assert!(!tokio_uring::features().nodrop());
+
+            // Sets of futures run their operations on the blocking pool too.
+            let file = tokio_uring::fs::File::open(&path).await.unwrap();
+            let mut reads = tokio_uring::OpSet::new();
+            reads.push(async move { file.read_at(Vec::with_capacity(16), 0).await });
+            let (res, buf) = reads.join_next().await.unwrap();
+            assert_eq!(&buf[..res.unwrap()], b"hello world");
+            reads.push(std::future::pending());
+            reads.abort_all();
+
+            // Messages can't be posted to a ring from outside of one.
+            let err = tokio_uring::post_to(&ring, 1).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+            tokio_uring::fs::remove_file(&path).await.unwrap();
+        });
+    })
+    .join()
+    .unwrap();
+}
+
+/// Make `io_uring_setup` fail with `EPERM` on the current thread.
+fn deny_io_uring_setup() {
+    const SYS_IO_URING_SETUP: u32 = 425;
+
+    let filter = [
+        // Load the system call number
+        libc::sock_filter {
+            code: (libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16,
+            jt: 0,
+            jf: 0,
+            k: 0,
+        },
+        libc::sock_filter {
+            code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 1,
+            k: SYS_IO_URING_SETUP,
+        },
+        libc::sock_filter {
+            code: (libc::BPF_RET | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 0,
+            k: libc::SECCOMP_RET_ERRNO | libc::EPERM as u32,
+        },
+        libc::sock_filter {
+            code: (libc::BPF_RET | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 0,
+            k: libc::SECCOMP_RET_ALLOW,
+        },
+    ];
+    let prog = libc::sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_ptr() as *mut _,
+    };
+
+    unsafe {
+        assert_eq!(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0), 0);
+        assert_eq!(
+            libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &prog as *const libc::sock_fprog,
+            ),
+            0
+        );
+    }
+}
+
+#[test]
+fn features() {
+    tokio_uring::start(async {
+        // Supported by all kernels the crate runs on.
+        let features = tokio_uring::features();
+        assert!(features.single_mmap());
+        assert!(features.nodrop());
+        assert!(format!("{:?}", features).contains("nodrop"));
+    });
+}
+
+#[test]
+fn post_to() {
+    use std::sync::mpsc;
+
+    let (handle_tx, handle_rx) = mpsc::channel();
+
+    let receiver = std::thread::spawn(move || {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio_uring::builder()
+            .on_message(move |data| tx.send(data).unwrap())
+            .start(async move {
+                handle_tx.send(tokio_uring::ring_handle().unwrap()).unwrap();
+
+                let mut received = vec![];
+                for _ in 0..3 {
+                    received.push(rx.recv().await.unwrap());
+                }
+                received
+            })
+    });
+
+    tokio_uring::start(async {
+        let target = handle_rx.recv().unwrap();
+        for data in [1, u64::MAX, 0xdead_beef_0123_4567] {
+            tokio_uring::post_to(&target, data).await.unwrap();
+        }
+    });
+
+    assert_eq!(
+        receiver.join().unwrap(),
+        [1, u64::MAX, 0xdead_beef_0123_4567]
+    );
+}
+
+#[test]
+fn build() {
+    use std::io;
+    use tokio_uring::RuntimeError;
+
+    let rt = tokio_uring::builder().build().unwrap();
+    rt.block_on(async {
+        tokio_uring::no_op().await.unwrap();
+    });
+    drop(rt);
+
+    let err = RuntimeError::from(io::Error::from_raw_os_error(libc::ENOMEM));
+    assert!(matches!(err, RuntimeError::MemlockExceeded(_)));
+    assert!(err.to_string().contains("RLIMIT_MEMLOCK"));
+    let err = RuntimeError::from(io::Error::from_raw_os_error(libc::EPERM));
+    assert!(matches!(err, RuntimeError::PermissionDenied(_)));
+    let err = RuntimeError::from(io::Error::from_raw_os_error(libc::ENOSYS));
+    assert!(matches!(err, RuntimeError::Unsupported(_)));
+    let err = RuntimeError::from(io::Error::from_raw_os_error(libc::EINVAL));
+    assert_eq!(io::Error::from(err).raw_os_error(), Some(libc::EINVAL));
+}
+
+#[test]
+fn cq_entries() {
+    let rt = tokio_uring::builder()
+        .entries(8)
+        .cq_entries(1024)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        // Far more completions than the submission queue holds are reaped.
+        let nops = (0..512).map(|_| tokio_uring::no_op());
+        for res in futures::future::join_all(nops).await {
+            res.unwrap();
+        }
+    });
+    drop(rt);
+
+    let builder = tokio_uring::builder();
+    assert!(
+        tokio_uring::builder().cq_entries(4096).memlock_required(0) > builder.memlock_required(0)
+    );
+
+    // The completion queue can't be smaller than the submission queue.
+    let err = tokio_uring::builder()
+        .entries(64)
+        .cq_entries(32)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.io_error().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn raise_memlock() {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let builder = tokio_uring::builder();
+    let small = builder.memlock_required(0);
+    assert_eq!(builder.memlock_required(1), small + page_size);
+    assert!(tokio_uring::builder().entries(4096).memlock_required(0) > small);
+
+    tokio_uring::builder()
+        .try_raise_memlock(true)
+        .start(async {});
+
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    assert_eq!(
+        unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) },
+        0
+    );
+    assert_eq!(limit.rlim_cur, limit.rlim_max);
+}
+
+#[test]
+fn wait_strategy() {
+    use tokio_uring::WaitStrategy;
+
+    let sqpoll = tokio_uring::uring_builder().setup_sqpoll(1000).clone();
+    for (strategy, urb) in [
+        (WaitStrategy::Spin { max_spins: 0 }, None),
+        (WaitStrategy::Spin { max_spins: 100_000 }, None),
+        (WaitStrategy::Spin { max_spins: 100_000 }, Some(sqpoll)),
+    ] {
+        let mut builder = tokio_uring::builder();
+        builder.wait_strategy(strategy);
+        if let Some(urb) = &urb {
+            builder.uring_builder(urb);
+        }
+
+        // SQPOLL may require privileges.
+        let rt = match tokio_uring::Runtime::new(&builder) {
+            Ok(rt) => rt,
+            Err(_) if urb.is_some() => continue,
+            Err(e) => panic!("{}", e),
+        };
+
+        rt.block_on(async {
+            tokio_uring::no_op().await.unwrap();
+
+            // A completion posted by another thread, while the runtime waits.
+            let notifier = tokio_uring::Notifier::new().unwrap();
+            let remote = notifier.clone();
+            let thread = std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                remote.notify().unwrap()
+            });
+
+            notifier.notified().await.unwrap();
+            thread.join().unwrap();
+        });
+    }
+}
@@ -0,0 +1,63 @@
+use socket2::SockAddr;
+use tokio_uring::net::Socket;
+
+#[test]
+fn seqpacket() {
+    tokio_uring::start(async {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = SockAddr::unix(dir.path().join("socket")).unwrap();
+
+        let listener = Socket::new(libc::AF_UNIX, libc::SOCK_SEQPACKET, 0).unwrap();
+        listener.bind(&addr).await.unwrap();
+        listener.listen(1).await.unwrap();
+
+        let client = Socket::new(libc::AF_UNIX, libc::SOCK_SEQPACKET, 0).unwrap();
+        client.connect(&addr).await.unwrap();
+        let (server, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer, None);
+
+        // Message boundaries are preserved.
+        client.send(b"hello".to_vec()).await.0.unwrap();
+        client.send(b"world".to_vec()).await.0.unwrap();
+        let (res, buf) = server.recv(vec![0; 64]).await;
+        assert_eq!(&buf[..res.unwrap()], b"hello");
+        let (res, buf) = server.recv(vec![0; 64]).await;
+        assert_eq!(&buf[..res.unwrap()], b"world");
+    });
+}
+
+#[test]
+fn cloexec_nonblock() {
+    use std::os::unix::io::AsRawFd;
+
+    tokio_uring::start(async {
+        let socket = Socket::new(libc::AF_INET, libc::SOCK_DGRAM, 0).unwrap();
+        let fd = socket.as_raw_fd();
+        assert_ne!(
+            unsafe { libc::fcntl(fd, libc::F_GETFD) } & libc::FD_CLOEXEC,
+            0
+        );
+        assert_ne!(
+            unsafe { libc::fcntl(fd, libc::F_GETFL) } & libc::O_NONBLOCK,
+            0
+        );
+    });
+}
+
+#[test]
+fn bind_listen_errors() {
+    tokio_uring::start(async {
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = SockAddr::from(taken.local_addr().unwrap());
+
+        // Errors are reported, whether the kernel binds through the ring or
+        // not.
+        let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).unwrap();
+        let err = socket.bind(&addr).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+        let socket = Socket::new(libc::AF_INET, libc::SOCK_DGRAM, 0).unwrap();
+        let err = socket.listen(16).await.unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EOPNOTSUPP));
+    });
+}
@@ -0,0 +1,248 @@
+use std::io::Write;
+
+use tempfile::NamedTempFile;
+
+use tokio_uring::fs::File;
+use tokio_uring::net::{TcpListener, TcpStream};
+
+async fn connected_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (client, server) = futures::future::join(TcpStream::connect(addr), listener.accept()).await;
+
+    (client.unwrap(), server.unwrap().0)
+}
+
+async fn read_to_end(stream: &TcpStream) -> Vec<u8> {
+    let mut data = vec![];
+    loop {
+        let (res, buf) = stream.read(vec![0; 4096]).await;
+        let n = res.unwrap();
+        if n == 0 {
+            break data;
+        }
+        data.extend_from_slice(&buf[..n]);
+    }
+}
+
+#[test]
+fn send_file() {
+    tokio_uring::start(async {
+        // Larger than the default pipe size, to send in several steps.
+        let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+
+        let mut tempfile = NamedTempFile::new().unwrap();
+        tempfile.write_all(&data).unwrap();
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let (client, server) = connected_pair().await;
+
+        let (sent, received) = futures::future::join(
+            async {
+                let n = server.send_file(&file, 100, data.len()).await.unwrap();
+                server.shutdown(std::net::Shutdown::Write).unwrap();
+                n
+            },
+            read_to_end(&client),
+        )
+        .await;
+
+        // Stops short at the end of the file.
+        assert_eq!(sent, data.len() - 100);
+        assert_eq!(received, &data[100..]);
+    });
+}
+
+#[test]
+fn shutdown_write() {
+    tokio_uring::start(async {
+        let (client, server) = connected_pair().await;
+
+        // The request ends with a half-close.
+        client.write_all(b"request".to_vec()).await.0.unwrap();
+        client
+            .shutdown_async(std::net::Shutdown::Write)
+            .await
+            .unwrap();
+        assert_eq!(read_to_end(&server).await, b"request");
+
+        // The client still receives the response.
+        server.write_all(b"response".to_vec()).await.0.unwrap();
+        server
+            .shutdown_async(std::net::Shutdown::Both)
+            .await
+            .unwrap();
+        assert_eq!(read_to_end(&client).await, b"response");
+
+        // Writing after the shutdown fails.
+        let (res, _) = client.write(b"more".to_vec()).await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EPIPE));
+    });
+}
+
+async fn reset_pair() -> TcpStream {
+    use std::os::unix::io::AsRawFd;
+
+    let (client, server) = connected_pair().await;
+
+    // Closing with a zero linger timeout resets the connection.
+    let linger = libc::linger {
+        l_onoff: 1,
+        l_linger: 0,
+    };
+    let res = unsafe {
+        libc::setsockopt(
+            server.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_LINGER,
+            &linger as *const _ as *const libc::c_void,
+            std::mem::size_of_val(&linger) as libc::socklen_t,
+        )
+    };
+    assert_eq!(res, 0);
+    drop(server);
+
+    client
+}
+
+#[test]
+fn recv_reset() {
+    tokio_uring::start(async {
+        let client = reset_pair().await;
+        let (res, _) = client.recv(vec![0; 16]).await;
+        assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::ConnectionReset);
+
+        let client = reset_pair().await;
+        let (res, _) = client.recv_or_eof(vec![0; 16]).await;
+        assert_eq!(res.unwrap(), 0);
+    });
+}
+
+#[test]
+fn read_to_end_with_limits() {
+    use std::io::ErrorKind;
+
+    tokio_uring::start(async {
+        let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+
+        for max_len in [None, Some(data.len()), Some(1000)] {
+            let (client, server) = connected_pair().await;
+            let (_, (res, buf)) = futures::future::join(
+                async {
+                    server.write_all(data.clone()).await.0.unwrap();
+                    drop(server);
+                },
+                client.read_to_end_with_limits(b"prefix".to_vec(), max_len, false),
+            )
+            .await;
+
+            assert_eq!(&buf[..6], b"prefix");
+            match max_len {
+                Some(1000) => {
+                    assert_eq!(res.unwrap_err().kind(), ErrorKind::OutOfMemory);
+                    assert_eq!(&buf[6..], &data[..1000]);
+                }
+                _ => {
+                    assert_eq!(res.unwrap(), data.len());
+                    assert_eq!(&buf[6..], &data[..]);
+                }
+            }
+        }
+
+        let client = reset_pair().await;
+        let (res, _) = client.read_to_end(vec![]).await;
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::ConnectionReset);
+
+        let client = reset_pair().await;
+        let (res, _) = client.read_to_end_with_limits(vec![], None, true).await;
+        assert_eq!(res.unwrap(), 0);
+    });
+}
+
+#[test]
+fn lookup_host() {
+    use tokio_uring::net;
+
+    tokio_uring::start(async {
+        let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let addrs: Vec<_> = net::lookup_host(&format!("127.0.0.1:{}", port))
+            .await
+            .unwrap()
+            .collect();
+        assert_eq!(addrs, [listener.local_addr().unwrap()]);
+
+        let addrs: Vec<_> = net::lookup_host(&format!("localhost:{}", port))
+            .await
+            .unwrap()
+            .collect();
+        let addr = addrs.iter().find(|addr| addr.is_ipv4()).unwrap();
+        assert!(addr.ip().is_loopback());
+        TcpStream::connect(*addr).await.unwrap();
+
+        assert!(net::lookup_host("localhost").await.is_err());
+    });
+}
+
+#[test]
+fn send_recv_vectored() {
+    tokio_uring::start(async {
+        let (client, server) = connected_pair().await;
+
+        let bufs = vec![b"header:".to_vec(), vec![], b"body".to_vec()];
+        let (res, bufs) = server.send_vectored(bufs).await;
+        assert_eq!(res.unwrap(), 11);
+        assert_eq!(bufs.len(), 3);
+
+        let bufs = vec![Vec::with_capacity(7), Vec::with_capacity(64)];
+        let (res, bufs) = client.recv_vectored(bufs).await;
+        assert_eq!(res.unwrap(), 11);
+        assert_eq!(bufs[0], b"header:");
+        assert_eq!(bufs[1], b"body");
+    });
+}
+
+#[test]
+fn addrs() {
+    tokio_uring::start(async {
+        let listener = TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (client, server) =
+            futures::future::join(TcpStream::connect(addr), listener.accept()).await;
+        let client = client.unwrap();
+        let (server, client_addr) = server.unwrap();
+
+        assert_eq!(client.peer_addr().unwrap(), addr);
+        assert_eq!(server.local_addr().unwrap(), addr);
+        assert_eq!(client.local_addr().unwrap(), client_addr);
+        assert_eq!(server.peer_addr().unwrap(), client_addr);
+        assert_ne!(client_addr.port(), 0);
+    });
+}
+
+#[test]
+fn cancel_all() {
+    tokio_uring::start(async {
+        let (client, _server) = connected_pair().await;
+
+        let read = |len| client.read(vec![0; len]);
+        let cancel = async {
+            // Let the reads be submitted first.
+            tokio::task::yield_now().await;
+            client.cancel_all().await
+        };
+        let ((first, first_buf), (second, second_buf), canceled) =
+            futures::join!(read(16), read(32), cancel);
+
+        assert_eq!(canceled.unwrap(), 2);
+        assert_eq!(first.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+        assert_eq!(second.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+        assert_eq!((first_buf.len(), second_buf.len()), (16, 32));
+
+        // Nothing is left to cancel.
+        assert_eq!(client.cancel_all().await.unwrap(), 0);
+    });
+}
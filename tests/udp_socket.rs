@@ -0,0 +1,63 @@
+use tokio_uring::net::UdpSocket;
+
+fn bind() -> (UdpSocket, std::net::SocketAddr) {
+    let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    (UdpSocket::from_std(socket), addr)
+}
+
+#[test]
+fn connected_send_recv() {
+    tokio_uring::start(async {
+        let (client, client_addr) = bind();
+        let (server, server_addr) = bind();
+        let (other, other_addr) = bind();
+
+        let (res, _) = client.send(b"ping".to_vec()).await;
+        assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EDESTADDRREQ));
+
+        client.connect(server_addr).await.unwrap();
+        let (res, _) = client.send(b"ping".to_vec()).await;
+        assert_eq!(res.unwrap(), 4);
+        let (res, buf) = server.recv_from(vec![0; 16]).await;
+        let (n, addr) = res.unwrap();
+        assert_eq!(&buf[..n], b"ping");
+        assert_eq!(addr, client_addr);
+
+        // Datagrams from other addresses are filtered out.
+        let (res, _) = other.send_to(b"spam".to_vec(), client_addr).await;
+        res.unwrap();
+        let (res, _) = server.send_to(b"pong".to_vec(), client_addr).await;
+        res.unwrap();
+        let (res, buf) = client.recv(vec![0; 16]).await;
+        assert_eq!(&buf[..res.unwrap()], b"pong");
+
+        // Reconnecting changes the peer.
+        client.connect(other_addr).await.unwrap();
+        let (res, _) = client.send(b"hello".to_vec()).await;
+        res.unwrap();
+        let (res, buf) = other.recv_from(vec![0; 16]).await;
+        let (n, addr) = res.unwrap();
+        assert_eq!(&buf[..n], b"hello");
+        assert_eq!(addr, client_addr);
+    });
+}
+
+#[test]
+fn local_addr() {
+    tokio_uring::start(async {
+        let socket = UdpSocket::bind("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        assert_eq!(addr.ip(), std::net::Ipv4Addr::LOCALHOST);
+        assert_ne!(addr.port(), 0);
+
+        // The reported address is the one datagrams are received on.
+        let (sender, _) = bind();
+        let (res, _) = sender.send_to(b"ping".to_vec(), addr).await;
+        res.unwrap();
+        let (res, buf) = socket.recv(vec![0; 16]).await;
+        assert_eq!(&buf[..res.unwrap()], b"ping");
+    });
+}
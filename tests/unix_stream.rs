@@ -0,0 +1,88 @@
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+
+use tempfile::NamedTempFile;
+
+use tokio_uring::fs::File;
+use tokio_uring::net::UnixStream;
+
+#[test]
+fn pass_fds() {
+    tokio_uring::start(async {
+        let (a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+        let (a, b) = (UnixStream::from_std(a), UnixStream::from_std(b));
+
+        let mut tempfile = NamedTempFile::new().unwrap();
+        tempfile.write_all(b"hello world").unwrap();
+        let file = File::open(tempfile.path()).await.unwrap();
+
+        let fds = [file.as_raw_fd(), file.as_raw_fd()];
+        let (res, _) = a.send_with_fd(b"fds".to_vec(), &fds).await;
+        assert_eq!(res.unwrap(), 3);
+
+        let (res, buf) = b.recv_with_fd(Vec::with_capacity(16)).await;
+        let (n, files) = res.unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(buf, b"fds");
+        assert_eq!(files.len(), 2);
+
+        // The received descriptors are new, and refer to the same file.
+        for received in &files {
+            assert_ne!(received.as_raw_fd(), file.as_raw_fd());
+            let flags = unsafe { libc::fcntl(received.as_raw_fd(), libc::F_GETFD) };
+            assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+
+            let (res, buf) = received.read_at(vec![0; 5], 0).await;
+            assert_eq!(&buf[..res.unwrap()], b"hello");
+        }
+
+        // Data without descriptors is received as well.
+        let (res, _) = a.write(b"more".to_vec()).await;
+        res.unwrap();
+        let (res, buf) = b.recv_with_fd(Vec::with_capacity(16)).await;
+        let (n, files) = res.unwrap();
+        assert_eq!(&buf[..n], b"more");
+        assert!(files.is_empty());
+    });
+}
+
+#[test]
+fn dropped_recv_closes_fds() {
+    use std::future::Future;
+    use std::task::Poll;
+
+    // Counts the descriptors of the process open on `path`.
+    fn open_on(path: &std::path::Path) -> usize {
+        std::fs::read_dir("/proc/self/fd")
+            .unwrap()
+            .filter(|entry| {
+                let entry = entry.as_ref().unwrap();
+                std::fs::read_link(entry.path()).is_ok_and(|target| target == path)
+            })
+            .count()
+    }
+
+    tokio_uring::start(async {
+        let (a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+        let (a, b) = (UnixStream::from_std(a), UnixStream::from_std(b));
+        let tempfile = NamedTempFile::new().unwrap();
+        let open = open_on(tempfile.path());
+
+        let mut recv = Box::pin(b.recv_with_fd(Vec::with_capacity(16)));
+        futures::future::poll_fn(|cx| {
+            assert!(recv.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        let file = std::fs::File::open(tempfile.path()).unwrap();
+        let (res, _) = a.send_with_fd(b"fds".to_vec(), &[file.as_raw_fd()]).await;
+        assert_eq!(res.unwrap(), 3);
+        drop(file);
+
+        // The descriptor is received, but the result is dropped.
+        tokio_uring::no_op().await.unwrap();
+        drop(recv);
+        assert_eq!(open_on(tempfile.path()), open);
+    });
+}